@@ -0,0 +1,86 @@
+//! Measures how fast text is encoded and decoded on the current machine.
+
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use serde_json::{json, Value};
+use zalgo_codec::{zalgo_decode, zalgo_encode, EncodeError};
+
+/// How long encoding and decoding are repeated for.
+pub const BENCH_DURATION: Duration = Duration::from_secs(1);
+
+/// The throughput of encoding and decoding a text.
+pub struct BenchResults {
+    /// The size of the text in bytes.
+    input_bytes: usize,
+    /// The size of the encoded text in bytes.
+    encoded_bytes: usize,
+    /// How many bytes of text are encoded per second.
+    encode_throughput: f64,
+    /// How many bytes of encoded text are decoded per second.
+    decode_throughput: f64,
+}
+
+impl BenchResults {
+    /// Encodes the text and decodes the result repeatedly, each for at least the given duration.
+    pub fn measure(text: &str, duration: Duration) -> Result<Self, EncodeError> {
+        let encoded = zalgo_encode(text)?;
+        let encode_throughput = throughput(text.len(), duration, || {
+            black_box(zalgo_encode(black_box(text))).ok();
+        });
+        let decode_throughput = throughput(encoded.len(), duration, || {
+            black_box(zalgo_decode(black_box(&encoded))).ok();
+        });
+        Ok(Self {
+            input_bytes: text.len(),
+            encoded_bytes: encoded.len(),
+            encode_throughput,
+            decode_throughput,
+        })
+    }
+
+    /// Returns how many times larger the encoded text is than the text.
+    fn expansion_ratio(&self) -> f64 {
+        self.encoded_bytes as f64 / self.input_bytes.max(1) as f64
+    }
+
+    /// Describes the results as a JSON object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "input_bytes": self.input_bytes,
+            "encoded_bytes": self.encoded_bytes,
+            "encode_bytes_per_second": self.encode_throughput,
+            "decode_bytes_per_second": self.decode_throughput,
+            "expansion_ratio": self.expansion_ratio(),
+        })
+    }
+}
+
+impl fmt::Display for BenchResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "encode: {:.1} MB/s", self.encode_throughput / 1e6)?;
+        writeln!(f, "decode: {:.1} MB/s", self.decode_throughput / 1e6)?;
+        write!(
+            f,
+            "expansion ratio: {:.2} ({} bytes into {} bytes)",
+            self.expansion_ratio(),
+            self.input_bytes,
+            self.encoded_bytes
+        )
+    }
+}
+
+/// Calls `f` until the duration has passed and returns how many bytes it processed per second,
+/// if it processes the given number of bytes every time.
+fn throughput(bytes: usize, duration: Duration, mut f: impl FnMut()) -> f64 {
+    let start = Instant::now();
+    let mut iterations = 0_u64;
+    while start.elapsed() < duration || iterations == 0 {
+        f();
+        iterations += 1;
+    }
+    (bytes as f64 * iterations as f64) / start.elapsed().as_secs_f64()
+}
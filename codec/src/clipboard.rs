@@ -0,0 +1,61 @@
+//! Copies the results of the CLI to the clipboard, and lets the GUI paste text from it.
+
+use anyhow::Result;
+
+/// Somewhere the result of a command can be copied to.
+pub trait Clipboard {
+    /// Replaces the contents of the clipboard with the given text.
+    fn set_contents(&mut self, text: String) -> Result<()>;
+
+    /// Returns the text in the clipboard. Only the GUI pastes from the clipboard.
+    #[cfg(feature = "gui")]
+    fn get_contents(&mut self) -> Result<String>;
+}
+
+/// The clipboard of the system.
+///
+/// Copying to it fails if the program was built without the `clipboard` feature.
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    #[cfg(feature = "clipboard")]
+    fn set_contents(&mut self, text: String) -> Result<()> {
+        // The error from `cli_clipboard` can not be sent between threads, so only its message is kept.
+        cli_clipboard::set_contents(text)
+            .map_err(|e| anyhow::anyhow!("failed to copy the result to the clipboard: {e}"))
+    }
+
+    // The `gui` feature enables the `clipboard` feature.
+    #[cfg(feature = "gui")]
+    fn get_contents(&mut self) -> Result<String> {
+        cli_clipboard::get_contents()
+            .map_err(|e| anyhow::anyhow!("failed to paste from the clipboard: {e}"))
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn set_contents(&mut self, _text: String) -> Result<()> {
+        Err(crate::usage_error(
+            "copying to the clipboard requires the program to be built with the `clipboard` feature",
+        ))
+    }
+}
+
+/// A clipboard that remembers what was copied to it, and is empty if nothing was.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockClipboard(pub Option<String>);
+
+#[cfg(test)]
+impl Clipboard for MockClipboard {
+    fn set_contents(&mut self, text: String) -> Result<()> {
+        self.0 = Some(text);
+        Ok(())
+    }
+
+    #[cfg(feature = "gui")]
+    fn get_contents(&mut self) -> Result<String> {
+        self.0
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("the clipboard is empty"))
+    }
+}
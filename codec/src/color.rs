@@ -0,0 +1,86 @@
+//! Colors the error messages of the CLI with ANSI escape codes.
+
+use std::io::{self, IsTerminal};
+
+use clap::ValueEnum;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+const RESET: &str = "\x1b[0m";
+
+/// When the error messages are colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color them if stderr is a terminal and the `NO_COLOR` environment variable is not set.
+    Auto,
+    /// Always color them.
+    Always,
+    /// Never color them.
+    Never,
+}
+
+impl ColorChoice {
+    /// Returns whether the text that is written to stderr should be colored.
+    pub fn use_color(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                io::stderr().is_terminal()
+                    && !matches!(std::env::var_os("NO_COLOR"), Some(value) if !value.is_empty())
+            }
+        }
+    }
+}
+
+/// Colors an error message like "Error: ..." that may contain lines of source code
+/// with a gutter of line numbers and a line of carets under it, as well as notes.
+pub fn paint_error(message: &str) -> String {
+    message
+        .lines()
+        .enumerate()
+        .map(|(i, line)| paint_line(line, i == 0))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn paint_line(line: &str, first: bool) -> String {
+    if let Some(rest) = line.strip_prefix("Error:").filter(|_| first) {
+        return format!("{RED}Error:{RESET}{rest}");
+    }
+    if let Some(rest) = line.strip_prefix("note:") {
+        return format!("{CYAN}note:{RESET}{rest}");
+    }
+    match line.split_once('|') {
+        Some((gutter, rest))
+            if gutter.ends_with(' ') && gutter.chars().all(|c| c == ' ' || c.is_ascii_digit()) =>
+        {
+            let rest = match rest.trim() {
+                carets if !carets.is_empty() && carets.chars().all(|c| c == '^') => {
+                    format!("{RED}{rest}{RESET}")
+                }
+                _ => rest.to_string(),
+            };
+            format!("{BLUE}{gutter}|{RESET}{rest}")
+        }
+        _ => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_caret_annotation() {
+        let message = "Error: can not encode\n  |\n2 | He c\u{f6}mes\n  |     ^\nnote: a note";
+        assert_eq!(
+            paint_error(message),
+            format!(
+                "{RED}Error:{RESET} can not encode\n{BLUE}  |{RESET}\n{BLUE}2 |{RESET} He c\u{f6}mes\n{BLUE}  |{RESET}{RED}     ^{RESET}\n{CYAN}note:{RESET} a note"
+            )
+        );
+        assert_eq!(paint_error("a | b"), "a | b");
+    }
+}
@@ -0,0 +1,64 @@
+//! Contains the definition of the [`Error`] type that can hold the error of any operation in the crate.
+
+use core::fmt;
+
+use crate::{DecodeError, EncodeError};
+
+/// An error that can be the result of either encoding or decoding.
+///
+/// Both [`EncodeError`] and [`DecodeError`] can be converted into this type,
+/// so it lets the `?` operator be used on both kinds of errors in the same function.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec::{zalgo_decode, zalgo_encode, Error};
+/// fn round_trip(s: &str) -> Result<String, Error> {
+///     let encoded = zalgo_encode(s)?;
+///     Ok(zalgo_decode(&encoded)?)
+/// }
+///
+/// assert_eq!(round_trip("Zalgo")?, "Zalgo");
+/// assert!(matches!(round_trip("Zålgo"), Err(Error::Encode(_))));
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A string could not be encoded.
+    Encode(EncodeError),
+    /// A string could not be decoded.
+    Decode(DecodeError),
+}
+
+impl From<EncodeError> for Error {
+    #[inline]
+    fn from(e: EncodeError) -> Self {
+        Self::Encode(e)
+    }
+}
+
+impl From<DecodeError> for Error {
+    #[inline]
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(e) => write!(f, "{e}"),
+            Self::Decode(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Encode(e) => core::error::Error::source(e),
+            Self::Decode(e) => core::error::Error::source(e),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Interprets and produces the backslash escape sequences that let text with line breaks
+//! be passed to and printed by the CLI on a single line.
+
+/// Replaces the escape sequences `\n`, `\t`, `\\`, and `\xNN` in the text with the characters they stand for.
+/// The `NN` in `\xNN` are two hexadecimal digits of an ASCII character.
+///
+/// Returns a description of the first malformed escape sequence if there is one.
+pub fn unescape(text: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some((_, 'n')) => unescaped.push('\n'),
+            Some((_, 't')) => unescaped.push('\t'),
+            Some((_, '\\')) => unescaped.push('\\'),
+            Some((_, 'x')) => {
+                let digits = text.get(index + 2..index + 4).unwrap_or_default();
+                match u8::from_str_radix(digits, 16) {
+                    Ok(byte) if byte.is_ascii() && !digits.starts_with('+') => {
+                        unescaped.push(char::from(byte));
+                        chars.nth(1);
+                    }
+                    _ => {
+                        return Err(format!(
+                            "the escape sequence at index {index} must be `\\x` followed by two hexadecimal digits of an ASCII character, from 00 to 7F"
+                        ))
+                    }
+                }
+            }
+            Some((_, other)) => {
+                return Err(format!(
+                    "unknown escape sequence `\\{other}` at index {index}, only `\\n`, `\\t`, `\\\\`, and `\\xNN` are supported"
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "the text ends with a backslash at index {index} that does not start an escape sequence, use `\\\\` for a backslash"
+                ))
+            }
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Replaces the backslashes, line breaks, and tabs in the text with escape sequences that [`unescape`] reverses.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_sequences() {
+        assert_eq!(unescape(r"a\nb").unwrap(), "a\nb");
+        assert_eq!(unescape(r"\tZalgo\\").unwrap(), "\tZalgo\\");
+        assert_eq!(unescape(r"\x5a\x41lgo\x0A").unwrap(), "ZAlgo\n");
+        assert_eq!(unescape("no escapes \u{e9}").unwrap(), "no escapes \u{e9}");
+    }
+
+    #[test]
+    fn malformed_escape_sequences() {
+        assert!(unescape(r"a\qb").unwrap_err().contains(r"`\q` at index 1"));
+        assert!(unescape(r"Zalgo\").unwrap_err().contains("index 5"));
+        for malformed in [r"\x", r"\x4", r"\xZZ", r"\x80", r"\x+1", "\\x4\u{e9}"] {
+            assert!(unescape(malformed).unwrap_err().contains("hexadecimal"));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "a\\nb\n\tc";
+        assert_eq!(escape(text), r"a\\nb\n\tc");
+        assert_eq!(unescape(&escape(text)).unwrap(), text);
+    }
+}
@@ -1,12 +1,44 @@
-use cli_clipboard::set_contents;
+mod history;
+mod settings;
+
+use crate::clipboard::{Clipboard, SystemClipboard};
+use history::History;
 use iced::{
     self,
-    widget::{button::Button, column, row, text::Text, text_input::TextInput, Space},
-    window::Settings,
-    Element, Length, Size, Task,
+    futures::channel::oneshot,
+    keyboard,
+    widget::{
+        button::{self, Button},
+        column, row,
+        scrollable::Scrollable,
+        text::Text,
+        text_editor::{self, TextEditor},
+        Column, Space,
+    },
+    window, Element, Length, Size, Subscription, Task, Theme,
 };
 use rfd::FileDialog;
-use zalgo_codec_common::{zalgo_decode, zalgo_encode, zalgo_wrap_python};
+use settings::{Settings, ThemeChoice};
+use std::{
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use zalgo_codec::{
+    zalgo_decode, zalgo_encode, zalgo_unwrap_python, zalgo_wrap_python, DecodeError, EncodeError,
+    ZalgoString,
+};
+
+/// Describes an encoding error in a notification, including the grapheme cluster
+/// that contains the unencodable character if it consists of more than that character.
+fn describe_encode_error(error: &EncodeError, source: &str) -> String {
+    match crate::grapheme_note(error, source) {
+        Some(note) => format!("{error}\n{note}"),
+        None => error.to_string(),
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum GuiButton {
@@ -16,154 +48,875 @@ enum GuiButton {
     Unwrap,
     Copy,
     SaveAs,
+    Open,
+    Paste,
+    ToggleTheme,
+    Undo,
+    Redo,
+    Swap,
+    WrapFile,
+    UnwrapFile,
+    SaveFile,
+    SaveFileAs,
+    CancelFile,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 enum UserAction {
-    EditedInputText(String),
+    EditedInputText(text_editor::Action),
+    InteractedWithOutput(text_editor::Action),
     Pressed(GuiButton),
+    /// Clicked the notification with the given id to dismiss it.
+    DismissedNotification(u64),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TimedAction {
-    PopNotification,
+    /// Removes the notification with the given id, if it has not already been dismissed.
+    PopNotification(u64),
 }
 
+/// The most notifications that are shown at once. The oldest ones are removed to make room for new ones.
+const MAX_NOTIFICATIONS: usize = 5;
+
+/// How long a notification is shown before it is removed, if it is not dismissed before that.
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone)]
 enum ToplevelMessage {
-    CodecFinished(String),
+    /// The result of the operation with the given number.
+    CodecFinished {
+        operation: u64,
+        result: Result<String, CodecError>,
+    },
+    InputLoaded(String),
+    /// A file was wrapped or unwrapped, and is shown until it is saved or discarded.
+    FilePrepared(PendingFile),
+    /// The window was resized. Its size is saved with the other settings when it is closed.
+    WindowResized(Size),
+    /// The window was asked to close, so the settings are saved before the application exits.
+    CloseRequested,
+    /// Selects the character at the byte index of the input that the codec failed on.
+    HighlightError {
+        index: usize,
+    },
     TimerFinised(TimedAction),
     PushNotification(String),
     User(UserAction),
 }
 
+/// Copies the text to the clipboard and returns a message that notifies whether that succeeded.
+fn copy_to(clipboard: &mut impl Clipboard, text: String) -> ToplevelMessage {
+    let bytes = text.len();
+    match clipboard.set_contents(text) {
+        Ok(()) => {
+            ToplevelMessage::PushNotification(format!("copied {bytes} bytes to the clipboard"))
+        }
+        Err(e) => ToplevelMessage::PushNotification(e.to_string()),
+    }
+}
+
+/// Returns a message that replaces the input with the text in the clipboard.
+fn paste_from(clipboard: &mut impl Clipboard) -> ToplevelMessage {
+    match clipboard.get_contents() {
+        Ok(text) => ToplevelMessage::InputLoaded(text),
+        Err(e) => ToplevelMessage::PushNotification(e.to_string()),
+    }
+}
+
+/// Whether a Python file is wrapped or unwrapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileOperation {
+    Wrap,
+    Unwrap,
+}
+
+/// A Python file that has been wrapped or unwrapped, whose result is previewed before it is saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingFile {
+    operation: FileOperation,
+    source: PathBuf,
+    source_bytes: usize,
+    /// The first lines of the Python source code, which is the original file when wrapping and the result when unwrapping.
+    preview: String,
+    /// The wrapped or unwrapped contents of the file.
+    result: String,
+}
+
+impl PendingFile {
+    fn description(&self) -> String {
+        let (verb, result) = match self.operation {
+            FileOperation::Wrap => ("Wrapping", "of wrapped Python"),
+            FileOperation::Unwrap => ("Unwrapping", "of Python source code"),
+        };
+        format!(
+            "{verb} \"{}\" ({} bytes) gives {} bytes {result}. The first lines of the source code:",
+            self.source.display(),
+            self.source_bytes,
+            self.result.len(),
+        )
+    }
+}
+
+/// The most lines of a Python file that are shown before it is wrapped or unwrapped.
+const PREVIEW_LINES: usize = 20;
+
+/// Returns the first `lines` lines of the text, without the newline after the last of them.
+fn preview(text: &str, lines: usize) -> &str {
+    if lines == 0 {
+        return "";
+    }
+    text.match_indices('\n')
+        .nth(lines - 1)
+        .map_or(text, |(i, _)| &text[..i])
+}
+
+/// Returns the path next to the file that the result of wrapping or unwrapping it is saved to by default.
+///
+/// Unwrapping a file whose name ends in `_wrapped` replaces that suffix, so that the original file is not overwritten.
+fn output_path(source: &Path, operation: FileOperation) -> PathBuf {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match operation {
+        FileOperation::Wrap => format!("{stem}_wrapped.py"),
+        FileOperation::Unwrap => {
+            format!(
+                "{}_unwrapped.py",
+                stem.strip_suffix("_wrapped").unwrap_or(&stem)
+            )
+        }
+    };
+    source.with_file_name(name)
+}
+
+/// Reads the Python file at the path with the given function and wraps or unwraps it,
+/// into a message that previews the result or a notification of what went wrong.
+fn prepare_file(
+    operation: FileOperation,
+    path: PathBuf,
+    read: impl FnOnce(&Path) -> io::Result<String>,
+) -> ToplevelMessage {
+    let contents = match read(&path) {
+        Ok(contents) => contents.replace('\r', ""),
+        Err(e) => {
+            return ToplevelMessage::PushNotification(format!(
+                "could not read \"{}\": {e}",
+                path.display()
+            ))
+        }
+    };
+    let result = match operation {
+        FileOperation::Wrap => {
+            zalgo_wrap_python(&contents).map_err(|e| describe_encode_error(&e, &contents))
+        }
+        FileOperation::Unwrap => zalgo_unwrap_python(&contents).map_err(|e| e.to_string()),
+    };
+    match result {
+        Ok(result) => ToplevelMessage::FilePrepared(PendingFile {
+            operation,
+            preview: preview(
+                match operation {
+                    FileOperation::Wrap => &contents,
+                    FileOperation::Unwrap => &result,
+                },
+                PREVIEW_LINES,
+            )
+            .to_string(),
+            source: path,
+            source_bytes: contents.len(),
+            result,
+        }),
+        Err(e) => ToplevelMessage::PushNotification(format!(
+            "could not {} \"{}\": {e}",
+            match operation {
+                FileOperation::Wrap => "wrap",
+                FileOperation::Unwrap => "unwrap",
+            },
+            path.display()
+        )),
+    }
+}
+
+/// Whether an operation of the codec is running, and how the last one ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Status {
+    #[default]
+    Idle,
+    Busy,
+    Done,
+    Failed,
+}
+
+/// Runs the function on a new thread, so that it blocks neither the UI nor the executor of the tasks,
+/// and returns a future of its result. The result is `None` if the function panics.
+fn in_background<T: Send + 'static>(
+    work: impl FnOnce() -> T + Send + 'static,
+) -> impl Future<Output = Option<T>> + Send + 'static {
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        // The receiver is gone if the GUI was closed, and then the result is not needed.
+        let _ = sender.send(work());
+    });
+    async move { receiver.await.ok() }
+}
+
 #[derive(Debug, Default)]
 struct ZalgoCodecGui {
+    /// The text in the input editor, which is what the codec is applied to.
     input_field: String,
+    /// The contents of the input editor, including e.g. the cursor position.
+    input_editor: text_editor::Content,
+    /// The earlier states of the input, to undo and redo changes to it.
+    history: History,
+    /// The result of the last operation, which is what is copied and saved.
     output_field: String,
-    notifications: Vec<String>,
+    /// The read-only editor that shows the output, with breaks from [`display_text`] in it.
+    output_editor: text_editor::Content,
+    /// The shown notifications and their ids, with the newest one last.
+    notifications: Vec<(u64, String)>,
+    /// The id of the next notification.
+    next_notification: u64,
+    /// The number of the last operation that was started. Only its result is shown.
+    operation: u64,
+    status: Status,
+    /// The file that was wrapped or unwrapped last, until its result is saved or discarded.
+    pending_file: Option<PendingFile>,
+    settings: Settings,
+    /// The file that the settings are saved to when they change, if there is one.
+    settings_path: Option<PathBuf>,
+}
+
+impl ZalgoCodecGui {
+    /// Creates the GUI with the settings that are stored in the file at the path, if there is one.
+    fn with_settings_from(settings_path: Option<PathBuf>) -> Self {
+        Self {
+            settings: settings_path
+                .as_deref()
+                .map(Settings::load)
+                .unwrap_or_default(),
+            settings_path,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a file dialog that starts in the directory that a file was last opened from or saved to.
+    fn file_dialog(&self) -> FileDialog {
+        match &self.settings.last_directory {
+            Some(dir) => FileDialog::new().set_directory(dir),
+            None => FileDialog::new(),
+        }
+    }
+
+    fn theme(&self) -> Theme {
+        match self.settings.theme {
+            ThemeChoice::Light => Theme::Light,
+            ThemeChoice::Dark => Theme::Dark,
+        }
+    }
+
+    /// Removes the notification with the id, if it is still shown.
+    fn dismiss_notification(&mut self, id: u64) {
+        self.notifications.retain(|(shown, _)| *shown != id);
+    }
+
+    /// Replaces the text in the input editor, without recording the change in the history.
+    ///
+    /// The text is pasted into an empty editor, since [`text_editor::Content::with_text`] leaves out
+    /// the empty line after a newline at the end of the text, which would then be lost at the next edit.
+    fn set_input(&mut self, text: String) {
+        self.input_editor = text_editor::Content::new();
+        self.input_editor
+            .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                std::sync::Arc::new(text.clone()),
+            )));
+        self.input_field = text;
+    }
+
+    /// Replaces the output, and the text in the output editor with it broken up by [`display_text`].
+    fn set_output(&mut self, text: String) {
+        self.output_editor = text_editor::Content::with_text(&display_text(&text));
+        self.output_field = text;
+    }
+
+    /// Describes the sizes of the input and output, and how large the input would be after encoding it.
+    ///
+    /// While an operation is running that is shown instead of the output.
+    /// The grapheme clusters of the output are only counted if it is at most [`GRAPHEME_COUNT_LIMIT_BYTES`] large,
+    /// since the status line is updated every time the input changes.
+    fn status_line(&self) -> String {
+        let input = &self.input_field;
+        let mut status = format!(
+            "input: {} bytes, {} characters, {} bytes if encoded",
+            input.len(),
+            input.chars().count(),
+            2 * input.chars().count() + 1,
+        );
+        if self.status == Status::Busy {
+            status.push_str(" | working\u{2026}");
+            return status;
+        }
+        let output = &self.output_field;
+        if !output.is_empty() {
+            status.push_str(&format!(" | output: {} bytes", output.len()));
+            if output.len() <= GRAPHEME_COUNT_LIMIT_BYTES {
+                status.push_str(&format!(
+                    ", {} grapheme clusters",
+                    output.graphemes(true).count()
+                ));
+            }
+        }
+        status
+    }
+}
+
+/// Outputs that are larger than this many bytes are too slow to count the grapheme clusters of
+/// every time the status line is shown.
+const GRAPHEME_COUNT_LIMIT_BYTES: usize = 100_000;
+
+/// The number of characters in a row after which a zero-width space is inserted into the shown output.
+const DISPLAY_CHUNK_CHARS: usize = 100;
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// Returns the text with a zero-width space after every [`DISPLAY_CHUNK_CHARS`] characters of a line,
+/// so that the long runs of combining characters in encoded text can be wrapped when they are shown.
+/// The result is only for showing, the text without the spaces is what is copied and saved.
+fn display_text(text: &str) -> String {
+    let mut display = String::with_capacity(text.len());
+    let mut run = 0;
+    for c in text.chars() {
+        if c == '\n' {
+            run = 0;
+        } else {
+            if run == DISPLAY_CHUNK_CHARS {
+                display.push(ZERO_WIDTH_SPACE);
+                run = 0;
+            }
+            run += 1;
+        }
+        display.push(c);
+    }
+    display
+}
+
+/// Saves the settings to their file, and notifies the user if that fails.
+fn save_settings(state: &mut ZalgoCodecGui) -> Task<ToplevelMessage> {
+    match state
+        .settings_path
+        .as_deref()
+        .map(|path| state.settings.save(path))
+    {
+        Some(Err(e)) => update(
+            state,
+            ToplevelMessage::PushNotification(format!("could not save the settings: {e}")),
+        ),
+        _ => Task::none(),
+    }
+}
+
+/// Remembers the directory of the file for the next file dialog.
+fn remember_directory(state: &mut ZalgoCodecGui, file: &Path) -> Task<ToplevelMessage> {
+    state.settings.last_directory = file.parent().map(Path::to_path_buf);
+    save_settings(state)
+}
+
+/// Inputs that are larger than this many bytes can make the GUI slow to respond.
+const LARGE_INPUT_BYTES: usize = 1_000_000;
+
+/// Reads the file at the path with the given function into a message that sets the input to its contents,
+/// without the carriage return characters like the CLI does.
+fn load_input(path: &Path, read: impl FnOnce(&Path) -> io::Result<String>) -> ToplevelMessage {
+    match read(path) {
+        Ok(contents) => ToplevelMessage::InputLoaded(contents.replace('\r', "")),
+        Err(e) => {
+            ToplevelMessage::PushNotification(format!("could not read \"{}\": {e}", path.display()))
+        }
+    }
+}
+
+/// Returns the text in the editor, with its lines separated by newlines.
+///
+/// Unlike [`text_editor::Content::text`] this does not add a newline at the end of the last line.
+fn editor_text(editor: &text_editor::Content) -> String {
+    editor
+        .lines()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Why the codec could not be applied to the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CodecError {
+    /// The description of the error to show in a notification.
+    description: String,
+    /// The byte index in the input of the character that caused the error, if it is known.
+    index: Option<usize>,
+}
+
+impl CodecError {
+    fn encode(error: EncodeError, input: &str) -> Self {
+        Self {
+            description: describe_encode_error(&error, input),
+            index: Some(error.index()),
+        }
+    }
+
+    fn decode(error: DecodeError, input: &str) -> Self {
+        Self {
+            description: error.to_string(),
+            // `zalgo_decode` does not say where the problem is, but checking the encoded text does.
+            index: error
+                .index()
+                .or_else(|| ZalgoString::from_encoded(input).err()?.index()),
+        }
+    }
+}
+
+/// Applies the function of the codec that belongs to the button to the input.
+fn run_codec(button: GuiButton, input: &str) -> Result<String, CodecError> {
+    match button {
+        GuiButton::Encode => zalgo_encode(input).map_err(|e| CodecError::encode(e, input)),
+        GuiButton::Decode if input.is_empty() => Err(CodecError {
+            description: String::from("the input string was empty"),
+            index: None,
+        }),
+        GuiButton::Decode => zalgo_decode(input).map_err(|e| CodecError::decode(e, input)),
+        GuiButton::Wrap => zalgo_wrap_python(input).map_err(|e| CodecError::encode(e, input)),
+        GuiButton::Unwrap => zalgo_unwrap_python(input).map_err(|e| CodecError {
+            description: e.to_string(),
+            index: e.index(),
+        }),
+        GuiButton::Copy
+        | GuiButton::SaveAs
+        | GuiButton::Open
+        | GuiButton::Paste
+        | GuiButton::ToggleTheme
+        | GuiButton::Undo
+        | GuiButton::Redo
+        | GuiButton::Swap
+        | GuiButton::WrapFile
+        | GuiButton::UnwrapFile
+        | GuiButton::SaveFile
+        | GuiButton::SaveFileAs
+        | GuiButton::CancelFile => {
+            unreachable!("the {button:?} button does not apply the codec")
+        }
+    }
+}
+
+/// Returns the editor actions that select the character at the byte index of the text in the editor,
+/// which also scrolls the editor to it. Returns no actions if the index is not that of a character in the text.
+///
+/// The cursor is moved one character at a time from the start of the text, since moving it down
+/// would move it by the wrapped lines that are shown rather than by the lines of the text.
+fn select_character(text: &str, index: usize) -> Vec<text_editor::Action> {
+    if index >= text.len() || !text.is_char_boundary(index) {
+        return Vec::new();
+    }
+    let mut actions = vec![text_editor::Action::Move(
+        text_editor::Motion::DocumentStart,
+    )];
+    actions.extend(
+        text[..index]
+            .chars()
+            .map(|_| text_editor::Action::Move(text_editor::Motion::Right)),
+    );
+    actions.push(text_editor::Action::Select(text_editor::Motion::Right));
+    actions
 }
 
 fn update(state: &mut ZalgoCodecGui, message: ToplevelMessage) -> Task<ToplevelMessage> {
     match message {
-        ToplevelMessage::CodecFinished(result) => {
-            state.output_field = result;
+        // The results of operations that were started before the last one are ignored.
+        ToplevelMessage::CodecFinished { operation, .. } if operation != state.operation => {
+            Task::none()
+        }
+        ToplevelMessage::CodecFinished { result, .. } => match result {
+            Ok(output) => {
+                state.status = Status::Done;
+                state.set_output(output);
+                Task::none()
+            }
+            Err(CodecError { description, index }) => {
+                state.status = Status::Failed;
+                let notification = update(state, ToplevelMessage::PushNotification(description));
+                match index {
+                    Some(index) => {
+                        notification.chain(update(state, ToplevelMessage::HighlightError { index }))
+                    }
+                    None => notification,
+                }
+            }
+        },
+        ToplevelMessage::HighlightError { index } => {
+            for action in select_character(&state.input_field, index) {
+                state.input_editor.perform(action);
+            }
+            Task::none()
+        }
+        ToplevelMessage::WindowResized(size) => {
+            state.settings.window_size = (size.width.round() as u32, size.height.round() as u32);
+            Task::none()
+        }
+        // The window is gone by the time a notification would be shown, so the error is printed instead.
+        ToplevelMessage::CloseRequested => {
+            if let Some(Err(e)) = state
+                .settings_path
+                .as_deref()
+                .map(|path| state.settings.save(path))
+            {
+                eprintln!("could not save the settings: {e}");
+            }
+            iced::exit()
+        }
+        ToplevelMessage::InputLoaded(text) => {
+            let bytes = text.len();
+            if text != state.input_field {
+                state.history.record(&state.input_field, false);
+            }
+            state.set_input(text);
+            if bytes > LARGE_INPUT_BYTES {
+                update(
+                    state,
+                    ToplevelMessage::PushNotification(format!(
+                        "the file is {bytes} bytes large, so the GUI may be slow"
+                    )),
+                )
+            } else {
+                Task::none()
+            }
+        }
+        ToplevelMessage::FilePrepared(pending) => {
+            state.pending_file = Some(pending);
             Task::none()
         }
         ToplevelMessage::PushNotification(notification) => {
-            state.notifications.push(notification);
+            let id = state.next_notification;
+            state.next_notification += 1;
+            state.notifications.push((id, notification));
+            if state.notifications.len() > MAX_NOTIFICATIONS {
+                state.notifications.remove(0);
+            }
+            // The timer is created when the executor runs the task, since it needs the runtime of the executor.
             Task::perform(
-                async { std::thread::sleep(std::time::Duration::from_secs(5)) },
-                |_| ToplevelMessage::TimerFinised(TimedAction::PopNotification),
+                async { tokio::time::sleep(NOTIFICATION_DURATION).await },
+                move |()| ToplevelMessage::TimerFinised(TimedAction::PopNotification(id)),
             )
         }
         ToplevelMessage::TimerFinised(action) => {
             match action {
-                TimedAction::PopNotification => state.notifications.pop(),
-            };
+                TimedAction::PopNotification(id) => state.dismiss_notification(id),
+            }
             Task::none()
         }
         ToplevelMessage::User(action) => match action {
-            UserAction::EditedInputText(text) => {
-                state.input_field = text;
+            UserAction::EditedInputText(action) => {
+                let keystroke = matches!(
+                    action,
+                    text_editor::Action::Edit(
+                        text_editor::Edit::Insert(_)
+                            | text_editor::Edit::Backspace
+                            | text_editor::Edit::Delete
+                    )
+                );
+                let edit = action.is_edit();
+                state.input_editor.perform(action);
+                if edit {
+                    let text = editor_text(&state.input_editor);
+                    if text != state.input_field {
+                        state.history.record(&state.input_field, keystroke);
+                        state.input_field = text;
+                    }
+                }
                 Task::none()
             }
-            UserAction::Pressed(GuiButton::Encode) => {
-                let input = state.input_field.clone();
-                Task::perform(async move { zalgo_encode(&input) }, |res| match res {
-                    Ok(encoded) => ToplevelMessage::CodecFinished(encoded),
-                    Err(e) => ToplevelMessage::PushNotification(e.to_string()),
-                })
+            UserAction::DismissedNotification(id) => {
+                state.dismiss_notification(id);
+                Task::none()
             }
-            UserAction::Pressed(GuiButton::Decode) => {
-                let input = state.input_field.clone();
-                if input.is_empty() {
-                    Task::perform(
-                        async { String::from("the input string was empty") },
-                        ToplevelMessage::PushNotification,
-                    )
-                } else {
-                    Task::perform(async move { zalgo_decode(&input) }, |res| match res {
-                        Ok(decoded) => ToplevelMessage::CodecFinished(decoded),
-                        Err(e) => ToplevelMessage::PushNotification(e.to_string()),
-                    })
+            UserAction::Pressed(GuiButton::Undo) => {
+                if let Some(text) = state.history.undo(&state.input_field) {
+                    state.set_input(text);
                 }
+                Task::none()
             }
-            UserAction::Pressed(GuiButton::Wrap) => {
-                let input = state.input_field.clone();
-                Task::perform(async move { zalgo_wrap_python(&input) }, |res| match res {
-                    Ok(wrapped) => ToplevelMessage::CodecFinished(wrapped),
-                    Err(e) => ToplevelMessage::PushNotification(e.to_string()),
-                })
-            }
-            UserAction::Pressed(GuiButton::Unwrap) => {
-                let mut chars = state.input_field.chars();
-                for _ in 0..3 {
-                    chars.next();
+            UserAction::Pressed(GuiButton::Redo) => {
+                if let Some(text) = state.history.redo(&state.input_field) {
+                    state.set_input(text);
+                }
+                Task::none()
+            }
+            // Moves the output into the input so that it can be worked on further, and the input into the output.
+            UserAction::Pressed(GuiButton::Swap) => {
+                if !state.output_field.is_empty() {
+                    let output = std::mem::take(&mut state.output_field);
+                    if output != state.input_field {
+                        state.history.record(&state.input_field, false);
+                    }
+                    let input = std::mem::take(&mut state.input_field);
+                    state.set_input(output);
+                    state.set_output(input);
                 }
-                for _ in 0..88 {
-                    chars.next_back();
+                Task::none()
+            }
+            // The output can be selected and scrolled, but not edited.
+            UserAction::InteractedWithOutput(action) => {
+                if !action.is_edit() {
+                    state.output_editor.perform(action);
                 }
-                let encoded: String = chars.collect();
-                Task::perform(async move { zalgo_decode(&encoded) }, |res| match res {
-                    Ok(unwrapped) => ToplevelMessage::CodecFinished(unwrapped),
-                    Err(e) => ToplevelMessage::PushNotification(e.to_string()),
-                })
+                Task::none()
             }
+            UserAction::Pressed(
+                button @ (GuiButton::Encode
+                | GuiButton::Decode
+                | GuiButton::Wrap
+                | GuiButton::Unwrap),
+            ) => {
+                let input = state.input_field.clone();
+                state.operation += 1;
+                state.status = Status::Busy;
+                let operation = state.operation;
+                Task::perform(
+                    in_background(move || run_codec(button, &input)),
+                    move |result| ToplevelMessage::CodecFinished {
+                        operation,
+                        result: result.unwrap_or_else(|| {
+                            Err(CodecError {
+                                description: format!(
+                                    "the {button:?} operation failed unexpectedly"
+                                ),
+                                index: None,
+                            })
+                        }),
+                    },
+                )
+            }
+            // The clipboard can be slow with large grapheme clusters, and its calls block,
+            // so it is used on a thread of its own.
             UserAction::Pressed(GuiButton::Copy) => {
-                if let Err(e) = set_contents(state.output_field.clone()) {
-                    let s = e.to_string();
-                    Task::future(async { ToplevelMessage::PushNotification(s) })
+                let output = state.output_field.clone();
+                Task::perform(
+                    in_background(move || copy_to(&mut SystemClipboard, output)),
+                    |message| {
+                        message.unwrap_or_else(|| {
+                            ToplevelMessage::PushNotification(String::from(
+                                "could not copy to the clipboard",
+                            ))
+                        })
+                    },
+                )
+            }
+            UserAction::Pressed(GuiButton::Paste) => Task::perform(
+                in_background(|| paste_from(&mut SystemClipboard)),
+                |message| {
+                    message.unwrap_or_else(|| {
+                        ToplevelMessage::PushNotification(String::from(
+                            "could not paste from the clipboard",
+                        ))
+                    })
+                },
+            ),
+            UserAction::Pressed(GuiButton::Open) => {
+                if let Some(path) = state.file_dialog().pick_file() {
+                    remember_directory(state, &path).chain(Task::future(async move {
+                        load_input(&path, |path| std::fs::read_to_string(path))
+                    }))
                 } else {
                     Task::none()
                 }
             }
             UserAction::Pressed(GuiButton::SaveAs) => {
-                if let Some(path) = FileDialog::new().set_file_name("zalgo.txt").save_file() {
-                    if let Err(e) = std::fs::write(path, &state.output_field) {
+                if let Some(path) = state.file_dialog().set_file_name("zalgo.txt").save_file() {
+                    if let Err(e) = std::fs::write(&path, &state.output_field) {
                         let s = e.to_string();
                         Task::future(async { ToplevelMessage::PushNotification(s) })
                     } else {
-                        Task::none()
+                        remember_directory(state, &path)
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            UserAction::Pressed(button @ (GuiButton::WrapFile | GuiButton::UnwrapFile)) => {
+                let operation = if button == GuiButton::WrapFile {
+                    FileOperation::Wrap
+                } else {
+                    FileOperation::Unwrap
+                };
+                if let Some(path) = state.file_dialog().pick_file() {
+                    remember_directory(state, &path).chain(Task::perform(
+                        in_background(move || {
+                            prepare_file(operation, path, |path| std::fs::read_to_string(path))
+                        }),
+                        |message| {
+                            message.unwrap_or_else(|| {
+                                ToplevelMessage::PushNotification(String::from(
+                                    "the file could not be processed",
+                                ))
+                            })
+                        },
+                    ))
+                } else {
+                    Task::none()
+                }
+            }
+            // Saves the result next to the original file, but never over an existing file.
+            UserAction::Pressed(GuiButton::SaveFile) => match state.pending_file.take() {
+                Some(pending) => {
+                    let path = output_path(&pending.source, pending.operation);
+                    let written = std::fs::OpenOptions::new()
+                        .write(true)
+                        .create_new(true)
+                        .open(&path)
+                        .and_then(|mut file| {
+                            io::Write::write_all(&mut file, pending.result.as_bytes())
+                        });
+                    let notification = match written {
+                        Ok(()) => format!("saved \"{}\"", path.display()),
+                        Err(e) => {
+                            state.pending_file = Some(pending);
+                            format!("could not save \"{}\": {e}", path.display())
+                        }
+                    };
+                    update(state, ToplevelMessage::PushNotification(notification))
+                }
+                None => Task::none(),
+            },
+            UserAction::Pressed(GuiButton::SaveFileAs) => {
+                let Some(pending) = &state.pending_file else {
+                    return Task::none();
+                };
+                let default = output_path(&pending.source, pending.operation);
+                let mut dialog = state.file_dialog();
+                if let Some(name) = default.file_name() {
+                    dialog = dialog.set_file_name(name.to_string_lossy());
+                }
+                if let Some(path) = dialog.save_file() {
+                    if let Err(e) = std::fs::write(&path, &pending.result) {
+                        update(
+                            state,
+                            ToplevelMessage::PushNotification(format!(
+                                "could not save \"{}\": {e}",
+                                path.display()
+                            )),
+                        )
+                    } else {
+                        state.pending_file = None;
+                        remember_directory(state, &path)
                     }
                 } else {
                     Task::none()
                 }
             }
+            UserAction::Pressed(GuiButton::CancelFile) => {
+                state.pending_file = None;
+                Task::none()
+            }
+            UserAction::Pressed(GuiButton::ToggleTheme) => {
+                state.settings.theme = state.settings.theme.toggled();
+                save_settings(state)
+            }
         },
     }
 }
 
-fn view(state: &ZalgoCodecGui) -> Element<ToplevelMessage> {
-    const BUTTON_WIDTH: f32 = 80.0;
+const BUTTON_WIDTH: f32 = 80.0;
+
+/// A button that runs the codec, which is disabled while an operation is running.
+fn codec_button(label: &str, button: GuiButton, status: Status) -> Button<'_, ToplevelMessage> {
+    Button::new(label)
+        .on_press_maybe(
+            (status != Status::Busy).then_some(ToplevelMessage::User(UserAction::Pressed(button))),
+        )
+        .width(Length::Fixed(BUTTON_WIDTH))
+}
+
+/// Shows the preview of the file that was wrapped or unwrapped and the buttons that save or discard its result,
+/// or nothing if there is no such file.
+fn file_panel(pending: Option<&PendingFile>) -> Element<'_, ToplevelMessage> {
+    const SPACE: f32 = 10.0;
+    const PREVIEW_HEIGHT: f32 = 200.0;
+    let Some(pending) = pending else {
+        return Space::with_height(Length::Shrink).into();
+    };
+    let press = |button| ToplevelMessage::User(UserAction::Pressed(button));
+    column![
+        Space::with_height(Length::Fixed(SPACE)),
+        Text::new(pending.description()),
+        Scrollable::new(Text::new(&pending.preview).width(Length::Fill))
+            .height(Length::Fixed(PREVIEW_HEIGHT)),
+        Space::with_height(Length::Fixed(SPACE)),
+        row![
+            Button::new("Save")
+                .on_press(press(GuiButton::SaveFile))
+                .width(Length::Fixed(BUTTON_WIDTH)),
+            Space::with_width(Length::Fixed(SPACE)),
+            Button::new("Save as\u{2026}")
+                .on_press(press(GuiButton::SaveFileAs))
+                .width(Length::Fixed(BUTTON_WIDTH)),
+            Space::with_width(Length::Fixed(SPACE)),
+            Button::new("Cancel")
+                .on_press(press(GuiButton::CancelFile))
+                .width(Length::Fixed(BUTTON_WIDTH)),
+        ],
+    ]
+    .into()
+}
+
+fn view(state: &ZalgoCodecGui) -> Element<'_, ToplevelMessage> {
     const SPACE_HEIGHT: f32 = 10.0;
+    const OUTPUT_HEIGHT: f32 = 100.0;
     column![
         row![
             column![
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
-                TextInput::new("Type or paste text here!", &state.input_field)
-                    .on_input(|s| ToplevelMessage::User(UserAction::EditedInputText(s)))
-                    .on_paste(|s| ToplevelMessage::User(UserAction::EditedInputText(s))),
+                TextEditor::new(&state.input_editor)
+                    .placeholder("Type or paste text here!")
+                    .on_action(|action| ToplevelMessage::User(UserAction::EditedInputText(action))),
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
-                Button::new("Encode")
-                    .on_press(ToplevelMessage::User(UserAction::Pressed(
-                        GuiButton::Encode,
-                    )))
+                Button::new("Open\u{2026}")
+                    .on_press(ToplevelMessage::User(UserAction::Pressed(GuiButton::Open)))
                     .width(Length::Fixed(BUTTON_WIDTH)),
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
-                Button::new("Decode")
-                    .on_press(ToplevelMessage::User(UserAction::Pressed(
-                        GuiButton::Decode,
-                    )))
+                row![
+                    Button::new("Undo")
+                        .on_press_maybe(
+                            state.history.can_undo().then_some(ToplevelMessage::User(
+                                UserAction::Pressed(GuiButton::Undo)
+                            ))
+                        )
+                        .width(Length::Fixed(BUTTON_WIDTH)),
+                    Space::with_width(Length::Fixed(SPACE_HEIGHT)),
+                    Button::new("Redo")
+                        .on_press_maybe(
+                            state.history.can_redo().then_some(ToplevelMessage::User(
+                                UserAction::Pressed(GuiButton::Redo)
+                            ))
+                        )
+                        .width(Length::Fixed(BUTTON_WIDTH)),
+                ],
+                Space::with_height(Length::Fixed(SPACE_HEIGHT)),
+                Button::new("Paste")
+                    .on_press(ToplevelMessage::User(UserAction::Pressed(GuiButton::Paste)))
                     .width(Length::Fixed(BUTTON_WIDTH)),
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
-                Button::new("Wrap")
-                    .on_press(ToplevelMessage::User(UserAction::Pressed(GuiButton::Wrap)))
+                codec_button("Encode", GuiButton::Encode, state.status),
+                Space::with_height(Length::Fixed(SPACE_HEIGHT)),
+                codec_button("Decode", GuiButton::Decode, state.status),
+                Space::with_height(Length::Fixed(SPACE_HEIGHT)),
+                codec_button("Wrap", GuiButton::Wrap, state.status),
+                Space::with_height(Length::Fixed(SPACE_HEIGHT)),
+                codec_button("Unwrap", GuiButton::Unwrap, state.status),
+                Space::with_height(Length::Fixed(SPACE_HEIGHT)),
+                Button::new("Wrap file\u{2026}")
+                    .on_press(ToplevelMessage::User(UserAction::Pressed(
+                        GuiButton::WrapFile
+                    )))
                     .width(Length::Fixed(BUTTON_WIDTH)),
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
-                Button::new("Unwrap")
+                Button::new("Unwrap file\u{2026}")
                     .on_press(ToplevelMessage::User(UserAction::Pressed(
-                        GuiButton::Unwrap
+                        GuiButton::UnwrapFile
                     )))
                     .width(Length::Fixed(BUTTON_WIDTH)),
             ]
@@ -171,7 +924,18 @@ fn view(state: &ZalgoCodecGui) -> Element<ToplevelMessage> {
             Space::with_width(Length::Fill),
             column![
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
-                Text::new(&state.output_field),
+                TextEditor::new(&state.output_editor)
+                    .on_action(
+                        |action| ToplevelMessage::User(UserAction::InteractedWithOutput(action))
+                    )
+                    .height(Length::Fixed(OUTPUT_HEIGHT)),
+                Space::with_height(Length::Fixed(SPACE_HEIGHT)),
+                Button::new("\u{21c4} Swap")
+                    .on_press_maybe(
+                        (!state.output_field.is_empty() && state.status != Status::Busy)
+                            .then_some(ToplevelMessage::User(UserAction::Pressed(GuiButton::Swap)))
+                    )
+                    .width(Length::Fixed(BUTTON_WIDTH)),
                 Space::with_height(Length::Fixed(SPACE_HEIGHT)),
                 Button::new("Copy")
                     .on_press(ToplevelMessage::User(UserAction::Pressed(GuiButton::Copy)))
@@ -186,14 +950,28 @@ fn view(state: &ZalgoCodecGui) -> Element<ToplevelMessage> {
             .width(Length::FillPortion(3)),
         ]
         .width(Length::Fill),
+        file_panel(state.pending_file.as_ref()),
         Space::with_height(SPACE_HEIGHT),
-        Text::new(
-            state
-                .notifications
-                .iter()
-                .rev()
-                .fold(String::new(), |toast, msg| format!("{toast}\n{msg}"))
-        )
+        row![
+            Text::new(state.status_line()).width(Length::Fill),
+            Button::new(match state.settings.theme {
+                ThemeChoice::Light => "Dark theme",
+                ThemeChoice::Dark => "Light theme",
+            })
+            .on_press(ToplevelMessage::User(UserAction::Pressed(
+                GuiButton::ToggleTheme
+            ))),
+        ],
+        // The newest notification is shown at the top, and clicking one dismisses it.
+        Column::with_children(state.notifications.iter().rev().map(|(id, notification)| {
+            Button::new(Text::new(notification))
+                .style(button::text)
+                .padding(0)
+                .on_press(ToplevelMessage::User(UserAction::DismissedNotification(
+                    *id,
+                )))
+                .into()
+        }))
         .width(Length::Fill)
         .height(Length::Fill),
     ]
@@ -201,17 +979,44 @@ fn view(state: &ZalgoCodecGui) -> Element<ToplevelMessage> {
     .into()
 }
 
+/// Turns Ctrl+Z into undo and Ctrl+Shift+Z into redo, or Cmd instead of Ctrl on macOS.
+fn undo_shortcut(key: keyboard::Key, modifiers: keyboard::Modifiers) -> Option<ToplevelMessage> {
+    match key.as_ref() {
+        keyboard::Key::Character("z" | "Z") if modifiers.command() => Some(ToplevelMessage::User(
+            UserAction::Pressed(if modifiers.shift() {
+                GuiButton::Redo
+            } else {
+                GuiButton::Undo
+            }),
+        )),
+        _ => None,
+    }
+}
+
+fn subscription(_state: &ZalgoCodecGui) -> Subscription<ToplevelMessage> {
+    Subscription::batch([
+        window::resize_events().map(|(_, size)| ToplevelMessage::WindowResized(size)),
+        window::close_requests().map(|_| ToplevelMessage::CloseRequested),
+        keyboard::on_key_press(undo_shortcut),
+    ])
+}
+
 /// Runs the GUI version of the application and then exits.
 pub fn run_gui() -> ! {
+    let state = ZalgoCodecGui::with_settings_from(Settings::path());
+    let (width, height) = state.settings.window_size;
     match iced::application("zalgo codec GUI", update, view)
-        .window(Settings {
+        .window(window::Settings {
             size: Size {
-                width: 500.0,
-                height: 300.0,
+                width: width as f32,
+                height: height as f32,
             },
+            exit_on_close_request: false,
             ..Default::default()
         })
-        .run()
+        .theme(ZalgoCodecGui::theme)
+        .subscription(subscription)
+        .run_with(move || (state, Task::none()))
     {
         Ok(()) => std::process::exit(0),
         Err(e) => {
@@ -220,3 +1025,447 @@ pub fn run_gui() -> ! {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clipboard::MockClipboard;
+    use std::sync::Arc;
+
+    fn finished(operation: u64, result: Result<String, CodecError>) -> ToplevelMessage {
+        ToplevelMessage::CodecFinished { operation, result }
+    }
+
+    fn notification_texts(state: &ZalgoCodecGui) -> Vec<&str> {
+        state
+            .notifications
+            .iter()
+            .map(|(_, notification)| notification.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn notifications_expire_by_id() {
+        let mut state = ZalgoCodecGui::default();
+        for notification in ["first", "second", "third"] {
+            let _ = update(
+                &mut state,
+                ToplevelMessage::PushNotification(String::from(notification)),
+            );
+        }
+        let expire = |id| ToplevelMessage::TimerFinised(TimedAction::PopNotification(id));
+
+        let _ = update(&mut state, expire(1));
+        assert_eq!(notification_texts(&state), ["first", "third"]);
+        let _ = update(
+            &mut state,
+            ToplevelMessage::User(UserAction::DismissedNotification(2)),
+        );
+        assert_eq!(notification_texts(&state), ["first"]);
+        // The timer of a dismissed notification does nothing when it finishes.
+        let _ = update(&mut state, expire(2));
+        assert_eq!(notification_texts(&state), ["first"]);
+        let _ = update(&mut state, expire(0));
+        assert!(state.notifications.is_empty());
+
+        for i in 0..=MAX_NOTIFICATIONS {
+            let _ = update(&mut state, ToplevelMessage::PushNotification(i.to_string()));
+        }
+        assert_eq!(state.notifications.len(), MAX_NOTIFICATIONS);
+        assert_eq!(notification_texts(&state)[0], "1");
+    }
+
+    #[test]
+    fn encode_multi_line_input() {
+        let text = "Zalgo\nHe comes\n";
+        let mut state = ZalgoCodecGui::default();
+        let _ = update(
+            &mut state,
+            ToplevelMessage::User(UserAction::EditedInputText(text_editor::Action::Edit(
+                text_editor::Edit::Paste(Arc::new(text.to_string())),
+            ))),
+        );
+        assert_eq!(state.input_field, text);
+
+        // The codec runs in a task, so its result is passed back to `update` like the runtime does.
+        let encoded = run_codec(GuiButton::Encode, &state.input_field).unwrap();
+        let _ = update(&mut state, finished(0, Ok(encoded)));
+        assert_eq!(zalgo_decode(&state.output_field).unwrap(), text);
+    }
+
+    #[test]
+    fn unwrap_with_shebang() {
+        let python = "print('Zalgo')\n";
+        let shebang = "#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\n";
+        let mut state = ZalgoCodecGui::default();
+        state.set_input(format!("{shebang}{}\n", zalgo_wrap_python(python).unwrap()));
+        let unwrap = || ToplevelMessage::User(UserAction::Pressed(GuiButton::Unwrap));
+
+        let _ = update(&mut state, unwrap());
+        let result = run_codec(GuiButton::Unwrap, &state.input_field);
+        let _ = update(&mut state, finished(1, result));
+        assert_eq!(state.status, Status::Done);
+        assert_eq!(state.output_field, format!("{shebang}{python}"));
+
+        // Unwrapping code that is not wrapped fails like the other operations do.
+        state.set_input(String::from(python));
+        let _ = update(&mut state, unwrap());
+        let result = run_codec(GuiButton::Unwrap, &state.input_field);
+        let _ = update(&mut state, finished(2, result));
+        assert_eq!(state.status, Status::Failed);
+        assert_eq!(state.output_field, format!("{shebang}{python}"));
+        assert_eq!(state.notifications.len(), 1);
+    }
+
+    #[test]
+    fn open_file() {
+        let path = Path::new("input.txt");
+        let message = load_input(path, |_| Ok(String::from("Zalgo\r\nHe comes\r\n")));
+        let mut state = ZalgoCodecGui::default();
+        let _ = update(&mut state, message);
+        assert_eq!(state.input_field, "Zalgo\nHe comes\n");
+        assert_eq!(editor_text(&state.input_editor), state.input_field);
+        assert!(state.notifications.is_empty());
+
+        let _ = update(
+            &mut state,
+            ToplevelMessage::InputLoaded("a".repeat(LARGE_INPUT_BYTES + 1)),
+        );
+        assert_eq!(state.notifications.len(), 1);
+
+        let message = load_input(path, |_| Err(io::ErrorKind::NotFound.into()));
+        match message {
+            ToplevelMessage::PushNotification(notification) => {
+                assert!(notification.contains("input.txt"))
+            }
+            other => panic!("expected a notification, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn file_preview() {
+        let text = "line 1\nline 2\nline 3\n";
+        assert_eq!(preview(text, 0), "");
+        assert_eq!(preview(text, 2), "line 1\nline 2");
+        assert_eq!(preview(text, 3), "line 1\nline 2\nline 3");
+        assert_eq!(preview(text, 20), text);
+        assert_eq!(preview("", 20), "");
+    }
+
+    #[test]
+    fn file_output_paths() {
+        let dir = Path::new("scripts");
+        assert_eq!(
+            output_path(&dir.join("main.py"), FileOperation::Wrap),
+            dir.join("main_wrapped.py")
+        );
+        assert_eq!(
+            output_path(&dir.join("main_wrapped.py"), FileOperation::Unwrap),
+            dir.join("main_unwrapped.py")
+        );
+        assert_eq!(
+            output_path(Path::new("script"), FileOperation::Unwrap),
+            Path::new("script_unwrapped.py")
+        );
+    }
+
+    #[test]
+    fn wrap_and_unwrap_file() {
+        let python = "print('Zalgo')\r\n".repeat(PREVIEW_LINES + 1);
+        let path = PathBuf::from("main.py");
+        let read = |contents: String| move |_: &Path| Ok(contents);
+        let mut state = ZalgoCodecGui::default();
+
+        let _ = update(
+            &mut state,
+            prepare_file(FileOperation::Wrap, path.clone(), read(python.clone())),
+        );
+        let wrapped = state.pending_file.clone().unwrap();
+        assert_eq!(wrapped.preview.lines().count(), PREVIEW_LINES);
+        assert_eq!(wrapped.source_bytes, python.len() - PREVIEW_LINES - 1);
+        assert!(wrapped
+            .description()
+            .contains(&format!("{} bytes", wrapped.result.len())));
+
+        let _ = update(
+            &mut state,
+            prepare_file(FileOperation::Unwrap, path.clone(), read(wrapped.result)),
+        );
+        let unwrapped = state.pending_file.clone().unwrap();
+        assert_eq!(unwrapped.result, python.replace('\r', ""));
+        assert_eq!(unwrapped.preview, wrapped.preview);
+
+        let _ = update(
+            &mut state,
+            ToplevelMessage::User(UserAction::Pressed(GuiButton::CancelFile)),
+        );
+        assert!(state.pending_file.is_none());
+
+        match prepare_file(FileOperation::Unwrap, path, read(python)) {
+            ToplevelMessage::PushNotification(notification) => {
+                assert!(notification.contains("main.py"))
+            }
+            other => panic!("expected a notification, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn paste_and_copy() {
+        let mut state = ZalgoCodecGui::default();
+        let mut clipboard = MockClipboard(Some(String::from("Zalgo\nHe comes")));
+        let _ = update(&mut state, paste_from(&mut clipboard));
+        assert_eq!(state.input_field, "Zalgo\nHe comes");
+
+        state.output_field = zalgo_encode(&state.input_field).unwrap();
+        let message = copy_to(&mut clipboard, state.output_field.clone());
+        let _ = update(&mut state, message);
+        assert_eq!(clipboard.0.as_ref(), Some(&state.output_field));
+
+        let message = paste_from(&mut MockClipboard::default());
+        let _ = update(&mut state, message);
+        assert_eq!(state.input_field, "Zalgo\nHe comes");
+        assert_eq!(
+            notification_texts(&state),
+            ["copied 29 bytes to the clipboard", "the clipboard is empty"]
+        );
+    }
+
+    #[test]
+    fn long_output_is_shown_in_chunks() {
+        let text = "a".repeat(3 * DISPLAY_CHUNK_CHARS);
+        let encoded = zalgo_encode(&text).unwrap();
+        let mut state = ZalgoCodecGui::default();
+        let _ = update(&mut state, finished(0, Ok(encoded.clone())));
+
+        let shown = editor_text(&state.output_editor);
+        assert_eq!(shown.matches(ZERO_WIDTH_SPACE).count(), 3);
+        assert_eq!(shown.replace(ZERO_WIDTH_SPACE, ""), encoded);
+
+        // Copying and saving use the output itself, not what is shown.
+        assert_eq!(state.output_field, encoded);
+        let mut clipboard = MockClipboard::default();
+        let _ = copy_to(&mut clipboard, state.output_field.clone());
+        assert_eq!(clipboard.0.unwrap(), encoded);
+
+        let _ = update(
+            &mut state,
+            ToplevelMessage::User(UserAction::InteractedWithOutput(text_editor::Action::Edit(
+                text_editor::Edit::Insert('a'),
+            ))),
+        );
+        assert_eq!(editor_text(&state.output_editor), shown);
+    }
+
+    #[test]
+    fn display_text_restarts_on_new_lines() {
+        let line = "b".repeat(DISPLAY_CHUNK_CHARS);
+        assert_eq!(
+            display_text(&format!("{line}\n{line}")),
+            format!("{line}\n{line}")
+        );
+        assert_eq!(
+            display_text(&format!("{line}b")),
+            format!("{line}{ZERO_WIDTH_SPACE}b")
+        );
+    }
+
+    #[test]
+    fn status_line() {
+        let mut state = ZalgoCodecGui::default();
+        state.set_input(String::from("Zalgo\n"));
+        assert_eq!(
+            state.status_line(),
+            "input: 6 bytes, 6 characters, 13 bytes if encoded"
+        );
+
+        let _ = update(
+            &mut state,
+            finished(0, Ok(zalgo_encode("Zalgo\n").unwrap())),
+        );
+        assert_eq!(
+            state.status_line(),
+            "input: 6 bytes, 6 characters, 13 bytes if encoded | output: 13 bytes, 1 grapheme clusters"
+        );
+
+        state.output_field = "a".repeat(GRAPHEME_COUNT_LIMIT_BYTES + 1);
+        assert!(state
+            .status_line()
+            .ends_with(&format!("output: {} bytes", GRAPHEME_COUNT_LIMIT_BYTES + 1)));
+    }
+
+    #[test]
+    fn select_error_position() {
+        use text_editor::{Action, Motion};
+
+        let input = "ab\ncd\u{eb}";
+        let error = run_codec(GuiButton::Encode, input).unwrap_err();
+        assert_eq!(error.index, Some(5));
+
+        let actions = select_character(input, 5);
+        assert_eq!(actions.first(), Some(&Action::Move(Motion::DocumentStart)));
+        assert_eq!(actions.last(), Some(&Action::Select(Motion::Right)));
+        // The cursor passes over "ab", the line break, and "cd".
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|&action| *action == Action::Move(Motion::Right))
+                .count(),
+            5
+        );
+
+        assert!(select_character(input, 6).is_empty());
+        assert!(select_character(input, 7).is_empty());
+    }
+
+    #[test]
+    fn decode_error_index() {
+        let error = run_codec(GuiButton::Decode, "E\u{33a}\u{e9}").unwrap_err();
+        assert_eq!(error.index, Some(3));
+    }
+
+    #[test]
+    fn toggle_theme() {
+        let mut state = ZalgoCodecGui::with_settings_from(None);
+        assert_eq!(state.theme(), Theme::Light);
+        let _ = update(
+            &mut state,
+            ToplevelMessage::User(UserAction::Pressed(GuiButton::ToggleTheme)),
+        );
+        assert_eq!(state.theme(), Theme::Dark);
+
+        let _ = update(
+            &mut state,
+            ToplevelMessage::WindowResized(Size {
+                width: 640.4,
+                height: 480.0,
+            }),
+        );
+        assert_eq!(state.settings.window_size, (640, 480));
+    }
+
+    #[test]
+    fn save_window_size_on_close() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let mut state = ZalgoCodecGui::with_settings_from(Some(path.clone()));
+        for width in [500.0, 600.0, 700.0] {
+            let _ = update(
+                &mut state,
+                ToplevelMessage::WindowResized(Size {
+                    width,
+                    height: 480.0,
+                }),
+            );
+        }
+        // Resizing the window does not write to the file every time it changes size.
+        assert!(!path.exists());
+
+        let _ = update(&mut state, ToplevelMessage::CloseRequested);
+        assert_eq!(Settings::load(&path).window_size, (700, 480));
+    }
+
+    #[test]
+    fn undo_edits() {
+        let mut state = ZalgoCodecGui::default();
+        let edit = |edit| {
+            ToplevelMessage::User(UserAction::EditedInputText(text_editor::Action::Edit(edit)))
+        };
+        for c in "Zalgo".chars() {
+            let _ = update(&mut state, edit(text_editor::Edit::Insert(c)));
+        }
+        let _ = update(&mut state, edit(text_editor::Edit::Enter));
+        let _ = update(
+            &mut state,
+            ToplevelMessage::InputLoaded(String::from("pasted")),
+        );
+        assert_eq!(state.input_field, "pasted");
+
+        let press = |button| ToplevelMessage::User(UserAction::Pressed(button));
+        let _ = update(&mut state, press(GuiButton::Undo));
+        assert_eq!(state.input_field, "Zalgo\n");
+        assert_eq!(editor_text(&state.input_editor), "Zalgo\n");
+        let _ = update(&mut state, press(GuiButton::Undo));
+        assert_eq!(state.input_field, "Zalgo");
+        let _ = update(&mut state, press(GuiButton::Undo));
+        assert_eq!(state.input_field, "");
+        let _ = update(&mut state, press(GuiButton::Redo));
+        assert_eq!(state.input_field, "Zalgo");
+
+        let _ = update(&mut state, edit(text_editor::Edit::Insert('!')));
+        assert!(!state.history.can_redo());
+        assert_eq!(state.input_field, "Zalgo!");
+    }
+
+    #[test]
+    fn swap_input_and_output() {
+        let mut state = ZalgoCodecGui::default();
+        let swap = || ToplevelMessage::User(UserAction::Pressed(GuiButton::Swap));
+        state.set_input(String::from("Zalgo"));
+
+        // There is nothing to swap in before the codec has run.
+        let _ = update(&mut state, swap());
+        assert_eq!(state.input_field, "Zalgo");
+        assert!(!state.history.can_undo());
+
+        let encoded = zalgo_encode("Zalgo").unwrap();
+        let _ = update(&mut state, finished(0, Ok(encoded.clone())));
+        let _ = update(&mut state, swap());
+        assert_eq!(state.input_field, encoded);
+        assert_eq!(editor_text(&state.input_editor), encoded);
+        assert_eq!(state.output_field, "Zalgo");
+
+        let _ = update(
+            &mut state,
+            ToplevelMessage::User(UserAction::Pressed(GuiButton::Undo)),
+        );
+        assert_eq!(state.input_field, "Zalgo");
+    }
+
+    #[test]
+    fn undo_shortcuts() {
+        let z = || keyboard::Key::Character("z".into());
+        assert!(undo_shortcut(z(), keyboard::Modifiers::default()).is_none());
+        assert!(matches!(
+            undo_shortcut(z(), keyboard::Modifiers::COMMAND),
+            Some(ToplevelMessage::User(UserAction::Pressed(GuiButton::Undo)))
+        ));
+        assert!(matches!(
+            undo_shortcut(
+                z(),
+                keyboard::Modifiers::COMMAND | keyboard::Modifiers::SHIFT
+            ),
+            Some(ToplevelMessage::User(UserAction::Pressed(GuiButton::Redo)))
+        ));
+    }
+
+    #[test]
+    fn status_transitions() {
+        let mut state = ZalgoCodecGui::default();
+        state.set_input(String::from("Zalgo"));
+        let encode = || ToplevelMessage::User(UserAction::Pressed(GuiButton::Encode));
+        assert_eq!(state.status, Status::Idle);
+
+        let _ = update(&mut state, encode());
+        assert_eq!(state.status, Status::Busy);
+        assert!(state.status_line().ends_with("working\u{2026}"));
+        let _ = update(&mut state, finished(1, Ok(String::from("first"))));
+        assert_eq!(state.status, Status::Done);
+        assert_eq!(state.output_field, "first");
+
+        // Only the result of the latest operation is shown.
+        let _ = update(&mut state, encode());
+        let _ = update(&mut state, encode());
+        let _ = update(&mut state, finished(2, Ok(String::from("second"))));
+        assert_eq!(state.status, Status::Busy);
+        assert_eq!(state.output_field, "first");
+
+        let error = CodecError {
+            description: String::from("failed"),
+            index: None,
+        };
+        let _ = update(&mut state, finished(3, Err(error)));
+        assert_eq!(state.status, Status::Failed);
+        assert_eq!(state.output_field, "first");
+        assert_eq!(notification_texts(&state), ["failed"]);
+    }
+}
@@ -0,0 +1,149 @@
+//! The undo and redo history of the input of the GUI.
+
+use std::collections::VecDeque;
+
+/// The most states of the input that are kept to undo to.
+const MAX_ENTRIES: usize = 100;
+
+/// The most bytes of text that are kept to undo to.
+const MAX_BYTES: usize = 10_000_000;
+
+/// The earlier and later states of a text, so that changes to it can be undone and redone.
+#[derive(Debug, Default)]
+pub struct History {
+    /// The states to undo to, with the most recent one last.
+    undo: VecDeque<String>,
+    /// The states to redo to, with the most recently undone one last.
+    redo: Vec<String>,
+    /// The total size of the states to undo to.
+    undo_bytes: usize,
+    /// Whether the last change was a keystroke, which the next keystroke is merged with.
+    after_keystroke: bool,
+}
+
+impl History {
+    /// Records the state of the text before it was changed, and forgets the states that were undone.
+    ///
+    /// Consecutive keystrokes are merged into a single change, so that undoing them removes e.g. a whole word.
+    pub fn record(&mut self, previous: &str, keystroke: bool) {
+        self.redo.clear();
+        if !(keystroke && self.after_keystroke) {
+            self.push_undo(previous.to_string());
+        }
+        self.after_keystroke = keystroke;
+    }
+
+    /// Returns the state before the last change if there is one, and remembers the current one to redo to.
+    pub fn undo(&mut self, current: &str) -> Option<String> {
+        let previous = self.undo.pop_back()?;
+        self.undo_bytes -= previous.len();
+        self.redo.push(current.to_string());
+        self.after_keystroke = false;
+        Some(previous)
+    }
+
+    /// Returns the state from before the last undo if there is one, and remembers the current one to undo to.
+    pub fn redo(&mut self, current: &str) -> Option<String> {
+        let next = self.redo.pop()?;
+        self.push_undo(current.to_string());
+        self.after_keystroke = false;
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Adds a state to undo to, and forgets the oldest states if there are too many or they are too large.
+    fn push_undo(&mut self, state: String) {
+        self.undo_bytes += state.len();
+        self.undo.push_back(state);
+        while self.undo.len() > MAX_ENTRIES || self.undo_bytes > MAX_BYTES {
+            match self.undo.pop_front() {
+                Some(oldest) => self.undo_bytes -= oldest.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::default();
+        assert!(!history.can_undo());
+        assert_eq!(history.undo("a"), None);
+
+        history.record("", false);
+        history.record("Zalgo", false);
+        assert_eq!(history.undo("Zalgo\nHe comes").as_deref(), Some("Zalgo"));
+        assert_eq!(history.undo("Zalgo").as_deref(), Some(""));
+        assert_eq!(history.undo(""), None);
+
+        assert_eq!(history.redo("").as_deref(), Some("Zalgo"));
+        assert_eq!(history.redo("Zalgo").as_deref(), Some("Zalgo\nHe comes"));
+        assert_eq!(history.redo("Zalgo\nHe comes"), None);
+        assert_eq!(history.undo("Zalgo\nHe comes").as_deref(), Some("Zalgo"));
+    }
+
+    #[test]
+    fn new_changes_clear_redo() {
+        let mut history = History::default();
+        history.record("a", false);
+        assert_eq!(history.undo("b").as_deref(), Some("a"));
+        assert!(history.can_redo());
+        history.record("a", false);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo("c"), None);
+    }
+
+    #[test]
+    fn keystrokes_are_merged() {
+        let mut history = History::default();
+        for previous in ["", "Z", "Za", "Zal"] {
+            history.record(previous, true);
+        }
+        history.record("Zalg", false);
+        history.record("Zalgo is pasted", true);
+        history.record("Zalgo is pasted!", true);
+        assert_eq!(
+            history.undo("Zalgo is pasted!!").as_deref(),
+            Some("Zalgo is pasted")
+        );
+        assert_eq!(history.undo("Zalgo is pasted").as_deref(), Some("Zalg"));
+        assert_eq!(history.undo("Zalg").as_deref(), Some(""));
+
+        // A keystroke after an undo is not merged with the ones before it.
+        history.record("", true);
+        assert_eq!(history.undo("a").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn bounded() {
+        let mut history = History::default();
+        for i in 0..MAX_ENTRIES + 10 {
+            history.record(&i.to_string(), false);
+        }
+        assert_eq!(history.undo.len(), MAX_ENTRIES);
+        assert_eq!(history.undo.front().map(String::as_str), Some("10"));
+
+        let mut history = History::default();
+        let large = "a".repeat(MAX_BYTES / 3);
+        for _ in 0..4 {
+            history.record(&large, false);
+        }
+        assert_eq!(history.undo.len(), 3);
+        assert_eq!(history.undo_bytes, 3 * large.len());
+
+        history.record(&"a".repeat(MAX_BYTES + 1), false);
+        assert!(!history.can_undo());
+        assert_eq!(history.undo_bytes, 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! The preferences of the user of the GUI, which are stored in a JSON file in the configuration directory
+//! of the platform so that they are kept between runs.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{json, Value};
+
+/// The color theme of the GUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeChoice {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    /// Returns the other theme.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::Light,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+/// The preferences of the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub theme: ThemeChoice,
+    /// The width and height of the window in logical pixels.
+    pub window_size: (u32, u32),
+    /// The directory that a file was last opened from or saved to, where the file dialogs start.
+    pub last_directory: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: ThemeChoice::default(),
+            window_size: (500, 300),
+            last_directory: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the path of the file that the settings are stored in, if the platform has a configuration directory.
+    pub fn path() -> Option<PathBuf> {
+        directories::ProjectDirs::from("", "", "zalgo-codec")
+            .map(|dirs| dirs.config_dir().join("gui.json"))
+    }
+
+    /// Reads the settings from the file at the path.
+    ///
+    /// Falls back to the default value of every setting that is missing or invalid,
+    /// and to the default settings if the file can not be read.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Value>(&contents).ok())
+            .map(|value| Self::from_json(&value))
+            .unwrap_or_default()
+    }
+
+    /// Writes the settings to the file at the path, and creates its directory if it does not exist.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_json().to_string())
+    }
+
+    /// Describes the settings as a JSON object.
+    fn to_json(&self) -> Value {
+        json!({
+            "theme": self.theme.name(),
+            "window_width": self.window_size.0,
+            "window_height": self.window_size.1,
+            "last_directory": self
+                .last_directory
+                .as_ref()
+                .and_then(|dir| dir.to_str())
+                .map(String::from),
+        })
+    }
+
+    /// Reads the settings from a JSON object like the one from [`Settings::to_json`].
+    fn from_json(value: &Value) -> Self {
+        let default = Self::default();
+        let dimension = |key: &str| {
+            value[key]
+                .as_u64()
+                .and_then(|pixels| u32::try_from(pixels).ok())
+                .filter(|&pixels| pixels > 0)
+        };
+        Self {
+            theme: match value["theme"].as_str() {
+                Some("dark") => ThemeChoice::Dark,
+                Some("light") => ThemeChoice::Light,
+                _ => default.theme,
+            },
+            window_size: (
+                dimension("window_width").unwrap_or(default.window_size.0),
+                dimension("window_height").unwrap_or(default.window_size.1),
+            ),
+            last_directory: value["last_directory"].as_str().map(PathBuf::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join("gui.json");
+        let settings = Settings {
+            theme: ThemeChoice::Dark,
+            window_size: (800, 600),
+            last_directory: Some(PathBuf::from("/home/zalgo/scripts")),
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path), settings);
+    }
+
+    #[test]
+    fn fall_back_to_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gui.json");
+        assert_eq!(Settings::load(&path), Settings::default());
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(Settings::load(&path), Settings::default());
+
+        std::fs::write(
+            &path,
+            r#"{"theme": "dark", "window_width": -3, "window_height": "tall"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Settings::load(&path),
+            Settings {
+                theme: ThemeChoice::Dark,
+                ..Settings::default()
+            }
+        );
+    }
+}
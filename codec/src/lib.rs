@@ -60,11 +60,49 @@
 //! `std`: enables [`EncodeError`] and [`DecodeError`] to capture a [`Backtrace`](std::backtrace::Backtrace).
 //! If this feature is not enabled the library is `no_std` compatible, but still uses the `alloc` crate.
 //!
-//! `serde`: derives the `Serialize` and `Deserialize` traits from [`serde`](https://docs.rs/serde) for [`ZalgoString`].
+//! `serde`: derives the `Serialize` and `Deserialize` traits from [`serde`](https://docs.rs/serde) for [`ZalgoString`],
+//! and implements `Serialize` for [`EncodeError`] and [`DecodeError`].
+//! Deserialization of a [`ZalgoString`] validates the string in the same way as [`ZalgoString::from_encoded`].
+//! Also adds the [`serde_support`] module with helpers for `#[serde(with = "...")]`.
+//!
+//! `serde-compact`: makes [`ZalgoString`] serialize its decoded string in formats that are not human-readable,
+//! which halves the size of the payload. This changes the serialization format. Enables `serde`.
 //!
 //! `rkyv`: derives the `Serialize`, `Deserialize`, and `Archive` traits from [`rkyv`](https://docs.rs/rkyv) for [`ZalgoString`].
+//! Accessing an archived [`ZalgoString`] with validation also validates the string.
+//!
+//! `borsh`: implements the `BorshSerialize` and `BorshDeserialize` traits from [`borsh`](https://docs.rs/borsh) for [`ZalgoString`].
+//! Deserialization of a [`ZalgoString`] validates the string in the same way as [`ZalgoString::from_encoded`].
+//!
+//! `schemars`: implements the `JsonSchema` trait from [`schemars`](https://docs.rs/schemars) for [`ZalgoString`].
+//!
+//! `defmt`: implements the `Format` trait from [`defmt`](https://docs.rs/defmt) for [`ZalgoString`], [`EncodeError`], and [`DecodeError`].
+//!
+//! `arbitrary`: implements the `Arbitrary` trait from [`arbitrary`](https://docs.rs/arbitrary) for [`ZalgoString`].
+//!
+//! `proptest`: re-exports the `proptest` module with [`proptest`](https://docs.rs/proptest) strategies
+//! that generate encodable text and [`ZalgoString`]s. Enables `std`.
+//!
+//! `quickcheck`: implements the `Arbitrary` trait from [`quickcheck`](https://docs.rs/quickcheck) for [`ZalgoString`]. Enables `std`.
+//!
+//! `zeroize`: implements the `Zeroize` trait from [`zeroize`](https://docs.rs/zeroize) for [`ZalgoString`],
+//! so that it can be scrubbed from memory when it is dropped.
+//!
+//! `sqlx`: implements the `Type`, `Encode`, and `Decode` traits from [`sqlx`](https://docs.rs/sqlx) for [`ZalgoString`],
+//! and validates the string when it is decoded. Enables `std`.
+//! The `sqlx-postgres` and `sqlx-sqlite` features additionally enable the corresponding database backends of `sqlx`.
+//!
+//! `segmentation`: adds the `EncodeError::grapheme` and `EncodeError::grapheme_range` methods that
+//! find the full grapheme cluster that contains an unencodable character.
+//!
+//! `diagnostics`: implements `Diagnostic` from [`miette`](https://docs.rs/miette) for [`EncodeError`] and [`DecodeError`],
+//! and adds the `EncodeErrorWithSource` type that can show the source of an [`EncodeError`]. Enables `std`.
+//!
+//! `files`: adds the [`files`] module with functions that encode and decode files and wrap Python source files. Enables `std`.
+//!
+//! `tracing`: emits spans and `debug` level events from [`tracing`](https://docs.rs/tracing) in the functions of the [`files`] module. Enables `files`.
 //!
-//! `macro` *(enabled by default)*: exports the procedural macros [`zalgo_embed!`] and [`zalgofy!`].
+//! `macro` *(enabled by default)*: exports the procedural macros [`zalgo_embed!`], [`zalgo_embed_file!`], [`zalgofy!`], [`zalgofy_file!`], and [`zalgo_decodify!`], and the attribute macros [`macro@zalgo_embed_mod`] and [`macro@zalgofy_strings`].
 //!   
 //! # Explanation
 //!
@@ -180,50 +218,55 @@
 //! There is an executable available for experimenting with the codec on text and files.
 //! It can also be used to generate grapheme clusters from source code for use with [`zalgo_embed!`].
 //! It can be installed with `cargo install zalgo-codec --features binary`.
-//! You can optionally enable the `gui` feature during installation to include a rudimentary GUI mode for the program.
+//! You can optionally enable the `gui` feature during installation to include a rudimentary GUI mode for the program,
+//! or only the `clipboard` feature to let the program copy its results to the clipboard with `--copy`.
 
 #![no_std]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
 pub use zalgo_codec_common::{
-    zalgo_decode, zalgo_encode, zalgo_string, zalgo_wrap_python, DecodeError, EncodeError,
+    is_normalization_stable, is_zalgo_encoded, zalgo_decode, zalgo_decode_normalized, zalgo_encode,
+    zalgo_encode_named, zalgo_string, zalgo_unwrap_python, zalgo_wrap_python, ArrayEncodeError,
+    DecodeError, DecodeErrorKind, DecodedOrd, EncodeError, EncodeErrorKind, ZalgoArrayString,
     ZalgoString,
 };
 
+#[cfg(feature = "diagnostics")]
+pub use zalgo_codec_common::EncodeErrorWithSource;
+
+#[cfg(feature = "files")]
+pub use zalgo_codec_common::files;
+
+#[cfg(feature = "proptest")]
+pub use zalgo_codec_common::proptest;
+#[cfg(feature = "serde")]
+pub use zalgo_codec_common::serde_support;
+
+mod error;
+pub use error::Error;
+
 #[cfg(feature = "macro")]
-pub use zalgo_codec_macro::{zalgo_embed, zalgofy};
+pub use zalgo_codec_macro::{
+    zalgo_decodify, zalgo_embed, zalgo_embed_file, zalgo_embed_mod, zalgofy, zalgofy_file,
+    zalgofy_strings,
+};
+
+#[cfg(test)]
+mod printable_ascii;
 
 #[cfg(test)]
 mod tests {
     extern crate alloc;
+    #[cfg(feature = "files")]
+    extern crate std;
 
     use super::*;
+    use crate::printable_ascii::PrintableAsciiAndNewline;
     use alloc::string::String;
     use core::str;
-    use rand::{
-        distributions::{DistString, Distribution},
-        seq::SliceRandom,
-        Rng,
-    };
+    use rand::distributions::DistString;
     use unicode_segmentation::UnicodeSegmentation;
 
-    struct PrintableAsciiAndNewline;
-
-    impl Distribution<char> for PrintableAsciiAndNewline {
-        fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
-            *b" !\"#$%&'()*,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVXYZ[\\]^_`abcdefghijklmnopqrstuvxyz{|}~\n".choose(rng).unwrap() as char
-        }
-    }
-
-    impl DistString for PrintableAsciiAndNewline {
-        fn append_string<R: Rng + ?Sized>(&self, rng: &mut R, string: &mut String, len: usize) {
-            string.reserve(len);
-            for _ in 0..len {
-                string.push(self.sample(rng));
-            }
-        }
-    }
-
     #[cfg(feature = "macro")]
     #[test]
     fn test_embed_function() {
@@ -301,6 +344,88 @@ mod tests {
         assert!(zalgo_encode("\0").is_err());
     }
 
+    #[test]
+    fn check_error_propagation() {
+        fn encode_then_parse(s: &str, encoded: &str) -> Result<(String, ZalgoString), Error> {
+            let encoded_input = zalgo_encode(s)?;
+            let parsed: ZalgoString = encoded.parse()?;
+            Ok((encoded_input, parsed))
+        }
+
+        assert!(encode_then_parse("Zalgo", "E").is_ok());
+        assert!(matches!(
+            encode_then_parse("Z\u{e5}lgo", "E"),
+            Err(Error::Encode(e)) if e.kind() == EncodeErrorKind::NotAscii
+        ));
+        assert!(matches!(
+            encode_then_parse("Zalgo", "Zalgo"),
+            Err(Error::Decode(e)) if e.kind() == DecodeErrorKind::MissingLeadingE
+        ));
+
+        // Labels attached to an `EncodeError` are kept by the umbrella error.
+        let err = Error::from(zalgo_encode_named("name", "Z\u{e5}lgo").unwrap_err());
+        assert!(matches!(&err, Error::Encode(e) if e.context() == Some("name")));
+        assert!(alloc::string::ToString::to_string(&err).starts_with("name: "));
+    }
+
+    #[cfg(feature = "files")]
+    #[test]
+    fn file_encoding() {
+        use files::{decode_file, encode_file};
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("lorem.txt");
+        let encoded = dir.path().join("lorem_encoded.txt");
+        let decoded = dir.path().join("lorem_decoded.txt");
+        let lorem = "Lorem ipsum dolor sit amet,\n\tconsectetur adipiscing elit.\r\n";
+        fs::write(&original, lorem).unwrap();
+
+        encode_file(&original, &encoded).unwrap();
+        assert!(is_zalgo_encoded(&fs::read_to_string(&encoded).unwrap()));
+        decode_file(&encoded, &decoded).unwrap();
+        assert_eq!(
+            fs::read_to_string(&decoded).unwrap(),
+            lorem.replace('\t', "    ").replace("\r\n", "\n")
+        );
+
+        fs::write(&original, "Z\u{e5}lgo").unwrap();
+        assert!(matches!(
+            encode_file(&original, &encoded),
+            Err(files::UnencodableFileError::UnencodableContent { error: e, .. }) if e.kind() == EncodeErrorKind::NotAscii
+        ));
+        assert!(matches!(
+            decode_file(&original, &decoded),
+            Err(files::UndecodableFileError::NotEncoded { .. })
+        ));
+    }
+
+    #[cfg(feature = "files")]
+    #[test]
+    fn python_encoding() {
+        use files::{unwrap_python_file, wrap_python_file};
+        use std::fs;
+
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("hello.py");
+        let wrapped = dir.path().join("hello_wrapped.py");
+        let unwrapped = dir.path().join("hello_unwrapped.py");
+        let python =
+            "#!/usr/bin/env python3\ndef hello():\n\tprint(\"Hello, world!\")\n\nhello()\n";
+        fs::write(&original, python).unwrap();
+
+        wrap_python_file(&original, &wrapped).unwrap();
+        assert_eq!(
+            fs::read_to_string(&wrapped).unwrap(),
+            zalgo_wrap_python(&python.replace('\t', "    ")).unwrap()
+        );
+        unwrap_python_file(&wrapped, &unwrapped).unwrap();
+        assert_eq!(
+            fs::read_to_string(&unwrapped).unwrap(),
+            python.replace('\t', "    ")
+        );
+    }
+
     #[cfg(feature = "macro")]
     #[test]
     fn check_zalgofy() {
@@ -1,21 +1,151 @@
+mod bench;
+mod clipboard;
+mod color;
+mod escape;
 #[cfg(feature = "gui")]
 mod gui;
+mod printable_ascii;
+mod repl;
+mod table;
+mod text_encoding;
+mod watch;
 
-use std::path::PathBuf;
+use std::{
+    fmt,
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-use zalgo_codec_common::{zalgo_decode, zalgo_encode, zalgo_wrap_python};
+use zalgo_codec::{
+    files::{
+        decode_dir, decode_file, decode_file_with_options, encode_dir, encode_file_with_options,
+        unwrap_python_file_with_options, wrap_python_file_with_options, CarriageReturns, FileError,
+        FileOptions, FileStats, Normalization, TabHandling, UndecodableFileError,
+        UnencodableFileError,
+    },
+    is_zalgo_encoded, zalgo_decode, zalgo_encode, zalgo_unwrap_python, zalgo_wrap_python,
+    DecodeError, EncodeError, ZalgoString,
+};
 
 use anyhow::{anyhow, Result};
-use clap::{Parser, Subcommand};
+use bench::{BenchResults, BENCH_DURATION};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use clipboard::{Clipboard, SystemClipboard};
+use color::{paint_error, ColorChoice};
+use printable_ascii::PrintableAsciiAndNewline;
+use rand::distributions::DistString;
+use serde_json::{json, Value};
+use table::{conversion_table, render_table, render_table_csv, TableRow};
+use text_encoding::{decode_bytes, has_byte_order_mark, OutputEncoding};
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, Clone, Subcommand)]
 enum Source {
     /// Operate on all text after the command.
     Text { text: Vec<String> },
 
-    /// Operate on the contents of the file at the path given after the command.
-    /// Ignores carriage return characters.
-    File { path: PathBuf },
+    /// Operate on the contents of the file at the path given after the command,
+    /// or on the text from stdin if the path is `-`.
+    /// Ignores carriage return characters unless --strict is given.
+    /// If several paths are given every file is encoded into `<stem>.zalgo`, or decoded into `<stem>`,
+    /// in the directory given by OUT_PATH, which is required.
+    File {
+        path: PathBuf,
+        #[arg(value_name = "PATHS")]
+        more_paths: Vec<PathBuf>,
+    },
+
+    /// Operate on every file in the directory at the path given after the command and its subdirectories.
+    /// The results are stored at the same relative paths in the directory given by OUT_PATH, which is required.
+    /// Files that are not UTF-8 text are skipped.
+    Dir { path: PathBuf },
+}
+
+impl Source {
+    /// Returns the files that should be processed into a directory, if there are several.
+    fn batch(&self) -> Option<Batch<'_>> {
+        match self {
+            Self::Dir { path } => Some(Batch::Dir(path)),
+            Self::File { path, more_paths } if !more_paths.is_empty() => Some(Batch::Files(
+                std::iter::once(path)
+                    .chain(more_paths)
+                    .map(PathBuf::as_path)
+                    .collect(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Several files that are processed into a directory.
+enum Batch<'a> {
+    /// Every file in the directory tree.
+    Dir(&'a Path),
+    /// The files at the paths.
+    Files(Vec<&'a Path>),
+}
+
+/// The path that stands for stdin.
+const STDIN_PATH: &str = "-";
+
+/// The size in bytes of the random text that the `bench` command measures with if no size is given.
+const DEFAULT_BENCH_SIZE: usize = 1_000_000;
+
+/// Returns the path if it names a file and not stdin.
+fn file_path(path: Option<&Path>) -> Option<&Path> {
+    path.filter(|path| *path != Path::new(STDIN_PATH))
+}
+
+/// Reads the file at the given path, or all of stdin if there is no path or the path is `-`.
+/// The input is read as UTF-16 if it starts with a UTF-16 byte order mark, and as UTF-8 otherwise.
+fn read_raw_input(path: Option<&Path>) -> Result<String> {
+    let bytes = match file_path(path) {
+        Some(path) => std::fs::read(path)?,
+        None => {
+            let mut bytes = Vec::new();
+            io::stdin().read_to_end(&mut bytes)?;
+            bytes
+        }
+    };
+    Ok(decode_bytes(bytes)?)
+}
+
+/// Works like [`read_raw_input`], but removes the carriage return characters from the input.
+fn read_input(path: Option<&Path>) -> Result<String> {
+    Ok(read_raw_input(path)?.replace('\r', ""))
+}
+
+/// Returns the text of the given source, or the text from stdin if there is no source.
+fn read_raw_source(source: Option<Source>) -> Result<String> {
+    match source {
+        Some(Source::Text { text }) => Ok(text.join(" ")),
+        Some(Source::File { path, more_paths }) if more_paths.is_empty() => {
+            read_raw_input(Some(&path))
+        }
+        Some(Source::File { .. }) => Err(usage_error(
+            "several files can only be processed into a directory given by --out-path",
+        )),
+        Some(Source::Dir { .. }) => Err(usage_error(
+            "a directory can only be processed into another directory given by --out-path",
+        )),
+        None => read_raw_input(None),
+    }
+}
+
+/// Reads the first few bytes of the file at the given path, enough to find a byte order mark.
+fn read_prefix(path: &Path) -> Result<Vec<u8>> {
+    let mut prefix = Vec::new();
+    std::fs::File::open(path)?
+        .take(3)
+        .read_to_end(&mut prefix)?;
+    Ok(prefix)
+}
+
+/// Works like [`read_raw_source`], but removes the carriage return characters from the text.
+fn read_source(source: Option<Source>) -> Result<String> {
+    Ok(read_raw_source(source)?.replace('\r', ""))
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -23,33 +153,920 @@ enum Mode {
     #[cfg(feature = "gui")]
     /// Opens up a rudimentary GUI application where you can apply the functions of the codec to text
     /// entered through a text box as well as copy the results or save them to a file.
-    /// It is currently not possible to enter newlines into the text box.
     /// Overrides all other options.
     Gui,
 
     /// Turn normal (printable ascii + newline) text into a single grapheme cluster.
+    /// Reads the text from stdin if no source is given.
     Encode {
+        #[arg(long)]
+        /// Encode every line on its own, and print one grapheme cluster per line.
+        /// Empty lines are encoded into a bare "E".
+        per_line: bool,
+
         #[command(subcommand)]
-        source: Source,
+        source: Option<Source>,
     },
 
     /// Turn python code into a decoder wrapped around encoded source code.
+    /// Reads the code from stdin if no source is given.
     Wrap {
-        /// The path to the file that is to be encoded. Ignores carriage return characters.
-        path: PathBuf,
+        #[command(subcommand)]
+        source: Option<Source>,
     },
 
     /// Turn text that has been encoded back into its normal form.
+    /// Reads the encoded text from stdin if no source is given.
+    /// Every line or argument is decoded on its own, and the results are joined with newlines.
     Decode {
+        #[arg(long)]
+        /// Decode the input as a single grapheme cluster, without removing the whitespace around it.
+        single: bool,
+
+        #[arg(long, conflicts_with = "single")]
+        /// Decode every line on its own, without skipping the empty ones,
+        /// so that the output of `encode --per-line` is turned back into exactly its input.
+        per_line: bool,
+
+        #[arg(long)]
+        /// Show the line breaks, tabs, and backslashes in the decoded text as `\n`, `\t`, and `\\`,
+        /// so that it is printed on a single line. `--escape` turns it back into the same text.
+        escape_output: bool,
+
         #[command(subcommand)]
-        source: Source,
+        source: Option<Source>,
     },
 
-    /// Unwrap and decode a wrapped python file.
+    /// Unwrap and decode python code that has been wrapped.
+    /// Reads the wrapped code from stdin if no source is given.
     Unwrap {
-        /// The path to the file to unwrap and decode.
-        path: PathBuf,
+        #[command(subcommand)]
+        source: Option<Source>,
+    },
+
+    /// Print the size and composition of an encoded text, e.g. to judge whether it fits in a message.
+    /// If the text is not encoded, print what they would be after encoding it instead.
+    /// Reads the text from stdin if no source is given.
+    Stats {
+        #[command(subcommand)]
+        source: Option<Source>,
+    },
+
+    /// Start an interactive session where every line that is entered is encoded, until the input ends (Ctrl-D).
+    /// A line that ends with a backslash continues on the next line,
+    /// and `:paste` starts a block of lines that is ended by a line with only a `.`.
+    /// The commands `:encode`, `:decode`, `:wrap`, and `:unwrap` switch what is done to the lines,
+    /// `:mode` prints what is currently done, and `:copy` copies the last result to the clipboard.
+    Repl,
+
+    /// Print the table that shows which combining character every character that can be encoded is turned into,
+    /// e.g. 'A' into U+0321. Prints comma-separated values with `--format csv`.
+    Table,
+
+    /// Measure how many megabytes per second are encoded and decoded on this machine,
+    /// by encoding and decoding a text for about a second each.
+    /// The text is random printable ASCII and newlines unless a source is given.
+    Bench {
+        #[arg(long, value_name = "BYTES")]
+        /// The size of the random text. Defaults to a megabyte.
+        size: Option<usize>,
+
+        #[command(subcommand)]
+        source: Option<Source>,
     },
+
+    /// Generate a script that completes the commands and options of this program in the given shell.
+    Completions {
+        /// The shell to generate the script for.
+        shell: Shell,
+    },
+}
+
+impl Mode {
+    /// Returns the name of the command.
+    fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "gui")]
+            Self::Gui => "gui",
+            Self::Encode { .. } => "encode",
+            Self::Wrap { .. } => "wrap",
+            Self::Decode { .. } => "decode",
+            Self::Unwrap { .. } => "unwrap",
+            Self::Stats { .. } => "stats",
+            Self::Repl => "repl",
+            Self::Table => "table",
+            Self::Bench { .. } => "bench",
+            Self::Completions { .. } => "completions",
+        }
+    }
+
+    /// Returns whether the command processes every line on its own.
+    fn per_line(&self) -> bool {
+        matches!(
+            self,
+            Self::Encode { per_line: true, .. } | Self::Decode { per_line: true, .. }
+        )
+    }
+}
+
+/// An error together with a description of it that has more context than the error itself,
+/// e.g. the line of the input that caused it.
+#[derive(Debug)]
+struct DescribedError<E> {
+    error: E,
+    description: String,
+}
+
+impl<E> fmt::Display for DescribedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl<E: fmt::Debug> std::error::Error for DescribedError<E> {}
+
+/// An error caused by arguments that can not be used together.
+#[derive(Debug)]
+struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+/// Creates a [`UsageError`] with the given message.
+fn usage_error(message: impl Into<String>) -> anyhow::Error {
+    UsageError(message.into()).into()
+}
+
+/// Decodes a single grapheme cluster after checking that it is a valid encoding.
+///
+/// [`zalgo_decode`] does not check its input, and can turn text that was never encoded into garbage
+/// without returning an error.
+fn decode_validated(encoded: &str) -> Result<String, DecodeError> {
+    ZalgoString::from_encoded(encoded).map(ZalgoString::into_decoded_string)
+}
+
+/// Encodes every line of the text on its own and joins the results with newlines.
+fn encode_per_line(text: &str) -> Result<String, EncodeError> {
+    text.split('\n')
+        .map(zalgo_encode)
+        .collect::<Result<Vec<String>, _>>()
+        .map(|lines| lines.join("\n"))
+}
+
+/// Decodes every line of text that was encoded by [`encode_per_line`] and joins the results with newlines.
+///
+/// Unlike [`decode_lines`] this keeps empty lines, so an empty line is an error.
+/// A single line ending at the end of the text is ignored.
+fn decode_per_line(encoded: &str) -> Result<String> {
+    encoded
+        .strip_suffix('\n')
+        .unwrap_or(encoded)
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            decode_validated(line).map_err(|e| describe_decode_error(e, line, Some(i + 1)))
+        })
+        .collect::<Result<Vec<String>, _>>()
+        .map(|lines| lines.join("\n"))
+        .map_err(Into::into)
+}
+
+/// Returns the lines of the encoded text together with their line numbers.
+///
+/// Whitespace around the lines is removed, and lines that are empty after that are skipped.
+fn encoded_lines(encoded: &str) -> impl Iterator<Item = (usize, &str)> {
+    encoded
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim_matches(|c: char| c.is_ascii_whitespace())))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// Decodes every line of the encoded text on its own and joins the results with newlines.
+fn decode_lines(encoded: &str) -> Result<String> {
+    encoded_lines(encoded)
+        .map(|(number, line)| {
+            decode_validated(line).map_err(|e| describe_decode_error(e, line, Some(number)))
+        })
+        .collect::<Result<Vec<String>, _>>()
+        .map(|lines| lines.join("\n"))
+        .map_err(Into::into)
+}
+
+/// Describes where the text that was found first differs from the text that was expected,
+/// or returns `None` if they are equal.
+fn first_difference(expected: &str, found: &str) -> Option<String> {
+    let mut expected_chars = expected.chars();
+    let mut found_chars = found.chars();
+    for position in 0.. {
+        match (expected_chars.next(), found_chars.next()) {
+            (None, None) => return None,
+            (e, f) if e == f => (),
+            (e, f) => {
+                let describe = |c: Option<char>| {
+                    c.map_or("the end of the text".to_string(), |c| format!("{c:?}"))
+                };
+                return Some(format!(
+                    "at character {position}: expected {}, found {}",
+                    describe(e),
+                    describe(f)
+                ));
+            }
+        }
+    }
+    unreachable!("the loop only ends by returning")
+}
+
+/// Describes the carriage returns that were removed from the input and the tabs that were expanded in it,
+/// if there were any.
+fn changes_note(carriage_returns: usize, tabs: usize) -> String {
+    let mut changes = Vec::new();
+    if carriage_returns > 0 {
+        changes.push(format!("removed {carriage_returns} carriage returns"));
+    }
+    if tabs > 0 {
+        changes.push(format!("expanded {tabs} tabs"));
+    }
+    if changes.is_empty() {
+        String::new()
+    } else {
+        format!(", {} in the input", changes.join(" and "))
+    }
+}
+
+/// Encodes or decodes the input of the mode, reverses the result, and compares it to the input
+/// without producing any output. Returns a short report if the round trip reproduces the input,
+/// and otherwise an error that says where it differs.
+///
+/// The input of the encode and wrap commands is changed by the flags before it is encoded, like it is without --check.
+fn check_round_trip(mode: Mode, flags: &EncodeFlags) -> Result<String> {
+    match mode {
+        Mode::Encode { source, .. } => {
+            let raw = flags.read_unchanged(source)?;
+            let note = flags.describe_changes(&raw);
+            let text = flags.apply(raw);
+            let encoded = zalgo_encode(&text).map_err(|e| describe_encode_error(e, &text))?;
+            match first_difference(&text, &zalgo_decode(&encoded)?) {
+                Some(difference) => Err(anyhow!(
+                    "the decoded text differs from the input {difference}"
+                )),
+                None => Ok(format!(
+                    "round trip ok: encoded {} bytes into {} bytes{note}",
+                    text.len(),
+                    encoded.len(),
+                )),
+            }
+        }
+        Mode::Wrap { source } => {
+            let raw = flags.read_unchanged(source)?;
+            let note = flags.describe_changes(&raw);
+            let text = flags.apply(raw);
+            let wrapped = zalgo_wrap_python(&text).map_err(|e| describe_encode_error(e, &text))?;
+            match first_difference(&text, &zalgo_unwrap_python(&wrapped)?) {
+                Some(difference) => Err(anyhow!(
+                    "the unwrapped code differs from the input {difference}"
+                )),
+                None => Ok(format!(
+                    "round trip ok: wrapped {} bytes into {} bytes{note}",
+                    text.len(),
+                    wrapped.len(),
+                )),
+            }
+        }
+        Mode::Decode { single, source, .. } => {
+            let raw = match source {
+                Some(Source::Text { text }) if !single => text.join("\n"),
+                source => read_raw_source(source)?,
+            };
+            let encoded = raw.replace('\r', "");
+            let lines: Vec<(usize, &str)> = if single {
+                vec![(1, &encoded)]
+            } else {
+                encoded_lines(&encoded).collect()
+            };
+            for &(number, line) in &lines {
+                let decoded =
+                    zalgo_decode(line).map_err(|e| describe_decode_error(e, line, Some(number)))?;
+                let encoded_again = zalgo_encode(&decoded)
+                    .map_err(|e| anyhow!("failed to encode the decoded line {number}: {e}"))?;
+                if let Some(difference) = first_difference(line, &encoded_again) {
+                    return Err(anyhow!(
+                        "line {number} changes when it is decoded and encoded again, {difference}"
+                    ));
+                }
+            }
+            Ok(format!(
+                "round trip ok: decoded {} lines{}",
+                lines.len(),
+                changes_note(raw.matches('\r').count(), 0)
+            ))
+        }
+        _ => Err(usage_error(
+            "--check can only be used with the encode, wrap, and decode commands",
+        )),
+    }
+}
+
+/// If the unencodable character in the error is only a part of what the user sees as
+/// a single character, returns a note that names the full grapheme cluster.
+fn grapheme_note(error: &EncodeError, source: &str) -> Option<String> {
+    error
+        .grapheme(source)
+        .filter(|grapheme| grapheme.chars().nth(1).is_some())
+        .map(|grapheme| format!("note: the character is part of the grapheme cluster {grapheme:?}"))
+}
+
+/// Describes an encoding error with the line of the source that caused it,
+/// and the grapheme cluster that contains the unencodable character.
+fn describe_encode_error(error: EncodeError, source: &str) -> DescribedError<EncodeError> {
+    let mut description = error.display_with_context(source).to_string();
+    let note = match error.char() {
+        '\r' => Some(
+            "note: CRLF line endings are only turned into LF line endings without --strict"
+                .to_string(),
+        ),
+        '\t' => Some("note: tabs can be replaced by spaces with --tab-width".to_string()),
+        _ => grapheme_note(&error, source),
+    };
+    if let Some(note) = note {
+        description.push('\n');
+        description.push_str(&note);
+    }
+    DescribedError { error, description }
+}
+
+/// Describes a decoding error with the characters of the encoded text around the one that caused it.
+/// `line` is the number of the line of the input that `encoded` is, if the input was decoded line by line.
+fn describe_decode_error(
+    error: DecodeError,
+    encoded: &str,
+    line: Option<usize>,
+) -> DescribedError<DecodeError> {
+    let mut description = match line {
+        Some(number) => format!("failed to decode line {number}: {error}"),
+        None => error.to_string(),
+    };
+    // `zalgo_decode` does not say where the problem is, but checking the encoded text does.
+    let index = error
+        .index()
+        .or_else(|| ZalgoString::from_encoded(encoded).err()?.index());
+    if let Some(context) = index.and_then(|index| decode_error_context(encoded, index)) {
+        description.push('\n');
+        description.push_str(&context);
+    }
+    DescribedError { error, description }
+}
+
+/// Shows up to three characters on each side of the character at the byte index of the encoded text,
+/// with a caret under the character at the index. Printable ASCII characters are shown as they are,
+/// and all other characters by their code points, since most of them are combining characters.
+fn decode_error_context(encoded: &str, index: usize) -> Option<String> {
+    let chars: Vec<(usize, char)> = encoded.char_indices().collect();
+    let position = chars.iter().position(|&(i, _)| i == index)?;
+    let start = position.saturating_sub(3);
+    let end = (position + 4).min(chars.len());
+
+    let mut context = String::from(if start > 0 { "..." } else { "" });
+    let mut caret = String::new();
+    for (i, &(_, c)) in chars[start..end].iter().enumerate() {
+        let shown = if (' '..='~').contains(&c) {
+            format!("{c:?}")
+        } else {
+            format!("U+{:04X}", u32::from(c))
+        };
+        if !context.is_empty() {
+            context.push(' ');
+        }
+        if start + i == position {
+            caret = format!("{}{}", " ".repeat(context.len()), "^".repeat(shown.len()));
+        }
+        context.push_str(&shown);
+    }
+    if end < chars.len() {
+        context.push_str(" ...");
+    }
+    Some(format!("  |\n  | {context}\n  | {caret}"))
+}
+
+/// Turns an error from encoding the file at `path` into a description like the one from [`describe_encode_error`].
+fn describe_unencodable_file(error: UnencodableFileError, path: &Path) -> anyhow::Error {
+    match error {
+        UnencodableFileError::UnencodableContent { error, path: p } => {
+            match std::fs::read_to_string(path) {
+                Ok(source) => {
+                    let described = describe_encode_error(error, &source);
+                    DescribedError {
+                        description: format!(
+                            "failed to encode `{}`:\n{}",
+                            path.display(),
+                            described.description
+                        ),
+                        error: described.error,
+                    }
+                    .into()
+                }
+                Err(_) => UnencodableFileError::UnencodableContent { error, path: p }.into(),
+            }
+        }
+        e => e.into(),
+    }
+}
+
+/// Returns the encoding error that caused the given error, if there is one.
+fn encode_error(error: &anyhow::Error) -> Option<&EncodeError> {
+    if let Some(e) = error.downcast_ref::<DescribedError<EncodeError>>() {
+        return Some(&e.error);
+    }
+    if let Some(UnencodableFileError::UnencodableContent { error, .. }) = error.downcast_ref() {
+        return Some(error);
+    }
+    if let Some(FileError::UnencodableContent { error, .. }) = error.downcast_ref() {
+        return Some(error);
+    }
+    error.downcast_ref()
+}
+
+/// Returns the decoding error that caused the given error, if there is one.
+fn decode_error(error: &anyhow::Error) -> Option<&DecodeError> {
+    if let Some(e) = error.downcast_ref::<DescribedError<DecodeError>>() {
+        return Some(&e.error);
+    }
+    if let Some(
+        UndecodableFileError::UndecodableContent { error, .. }
+        | UndecodableFileError::NotEncoded { error, .. },
+    ) = error.downcast_ref()
+    {
+        return Some(error);
+    }
+    if let Some(FileError::UndecodableContent { error, .. } | FileError::NotEncoded { error, .. }) =
+        error.downcast_ref()
+    {
+        return Some(error);
+    }
+    error.downcast_ref()
+}
+
+/// Returns whether the given error was caused by a file that could not be read or written.
+fn is_io_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<io::Error>().is_some()
+        || matches!(error.downcast_ref(), Some(UnencodableFileError::Io { .. }))
+        || matches!(error.downcast_ref(), Some(UndecodableFileError::Io { .. }))
+        || matches!(error.downcast_ref(), Some(FileError::Io { .. }))
+}
+
+/// Returns the exit code that describes the given error, see the help text of [`Cli`].
+fn exit_code(error: &anyhow::Error) -> ExitCode {
+    if encode_error(error).is_some() {
+        ExitCode::from(3)
+    } else if decode_error(error).is_some() {
+        ExitCode::from(4)
+    } else if is_io_error(error) {
+        ExitCode::from(5)
+    } else if error.downcast_ref::<UsageError>().is_some() {
+        ExitCode::from(2)
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Describes the given error as a JSON object with a `kind` field.
+///
+/// Errors from the codec are serialized as they are, with the kind `"NotAscii"` added to encoding errors,
+/// while other errors only have a `message` field.
+fn error_json(error: &anyhow::Error) -> Value {
+    if let Some(e) = encode_error(error) {
+        let mut object = json!({ "kind": "NotAscii" });
+        if let (Some(fields), Ok(Value::Object(error_fields))) =
+            (object.as_object_mut(), serde_json::to_value(e))
+        {
+            fields.extend(error_fields);
+        }
+        object
+    } else if let Some(e) = decode_error(error) {
+        serde_json::to_value(e).unwrap_or_else(|_| json!({ "kind": "Other" }))
+    } else {
+        let kind = if is_io_error(error) { "Io" } else { "Other" };
+        json!({ "kind": kind, "message": error.to_string() })
+    }
+}
+
+/// Encodes or decodes every file in the directory tree at `in_dir` into the same relative path under `out_dir`,
+/// and passes a line that says what happened to every file to `print`. Files that are not UTF-8 text are skipped.
+///
+/// A file that can not be processed does not stop the others, but results in an error at the end.
+fn process_dir(
+    encode: bool,
+    in_dir: &Path,
+    out_dir: &Path,
+    options: &FileOptions,
+    print: impl Fn(String),
+) -> Result<()> {
+    let (verb, report) = if encode {
+        ("encoded", encode_dir(in_dir, out_dir, options)?)
+    } else {
+        ("decoded", decode_dir(in_dir, out_dir, options)?)
+    };
+
+    for path in report.succeeded() {
+        print(format!("{verb} {}", path.display()));
+    }
+    for path in report.skipped() {
+        print(format!("skipped {}", path.display()));
+    }
+    let mut failures = 0;
+    for (path, error) in report.failed() {
+        match error {
+            FileError::Io { error, .. } if error.kind() == io::ErrorKind::InvalidData => {
+                print(format!("skipped {}: it is not UTF-8 text", path.display()));
+            }
+            error => {
+                eprintln!("{error}");
+                failures += 1;
+            }
+        }
+    }
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(anyhow!("{failures} files could not be {verb}"))
+    }
+}
+
+/// Encodes or decodes every one of the files into `out_dir`, and passes a line that says what happened
+/// to every file to `print`. An encoded file is named `<stem>.zalgo` and a decoded file `<stem>`,
+/// where `<stem>` is the name of the input file without its extension.
+///
+/// A file that can not be processed does not stop the others, but results in an error at the end.
+fn process_files(
+    encode: bool,
+    paths: &[&Path],
+    out_dir: &Path,
+    options: &FileOptions,
+    print: impl Fn(String),
+) -> Result<()> {
+    if out_dir.exists() && !out_dir.is_dir() {
+        return Err(usage_error(format!(
+            "several files can only be processed into a directory, but \"{}\" is not one",
+            out_dir.display()
+        )));
+    }
+    std::fs::create_dir_all(out_dir)?;
+
+    let verb = if encode { "encoded" } else { "decoded" };
+    let mut failures = 0;
+    for path in paths {
+        let Some(stem) = path.file_stem() else {
+            eprintln!("\"{}\" is not the path of a file", path.display());
+            failures += 1;
+            continue;
+        };
+        let mut name = stem.to_os_string();
+        if encode {
+            name.push(".zalgo");
+        }
+        let destination = out_dir.join(name);
+
+        let result = if encode {
+            encode_file_with_options(path, &destination, options)
+                .map_err(|e| describe_unencodable_file(e, path))
+        } else {
+            decode_file_with_options(path, &destination, options).map_err(anyhow::Error::from)
+        };
+        match result {
+            Ok(_) => print(format!(
+                "{verb} {} into {}",
+                path.display(),
+                destination.display()
+            )),
+            Err(e) => {
+                eprintln!("{e}");
+                failures += 1;
+            }
+        }
+    }
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(anyhow!("{failures} files could not be {verb}"))
+    }
+}
+
+/// Summarizes the statistics of a file operation in a single line.
+fn summarize(verb: &str, stats: &FileStats) -> String {
+    let mut summary = format!(
+        "{verb} {} bytes into {} bytes ({} lines)",
+        stats.bytes_in, stats.bytes_out, stats.lines
+    );
+    for normalization in &stats.normalizations {
+        match normalization {
+            Normalization::ExpandedTabs { count } => {
+                summary.push_str(&format!(", replaced {count} tabs with spaces"));
+            }
+            Normalization::StrippedCarriageReturns { count } => {
+                summary.push_str(&format!(", replaced {count} CRLF line endings with LF"));
+            }
+            _ => (),
+        }
+    }
+    summary
+}
+
+/// Flags that change the input of the encode and wrap commands to make it encodable.
+#[derive(Debug, Clone, Args)]
+struct EncodeFlags {
+    #[arg(long, global = true, required = false)]
+    /// Replace the characters that can not be encoded with `?`, or with the character given by --replacement.
+    /// Only valid with the encode and wrap commands, and can not be used together with --in-place.
+    lossy: bool,
+
+    #[arg(long, global = true, value_name = "CHAR")]
+    /// Replace the characters that can not be encoded with CHAR, which must be printable ASCII.
+    /// Implies --lossy.
+    replacement: Option<char>,
+
+    #[arg(long, global = true, value_name = "N")]
+    /// Replace every tab with N spaces instead of failing to encode it.
+    /// Only valid with the encode and wrap commands.
+    tab_width: Option<usize>,
+
+    #[arg(long, global = true, required = false)]
+    /// Interpret the escape sequences `\n`, `\t`, `\\`, and `\xNN` in the text given to the text source,
+    /// e.g. to encode several lines. Only valid with the encode and wrap commands and the text source.
+    escape: bool,
+
+    #[arg(long, global = true, required = false)]
+    /// Keep the carriage returns in the input, so that CRLF line endings fail to encode
+    /// instead of being turned into LF line endings. Only valid with the encode and wrap commands.
+    strict: bool,
+}
+
+impl EncodeFlags {
+    /// Returns an error if any of the flags are given to a command other than encode and wrap,
+    /// or if the replacement character can not be encoded.
+    fn validate(&self, mode: &Mode) -> Result<()> {
+        let is_set = self.lossy
+            || self.replacement.is_some()
+            || self.tab_width.is_some()
+            || self.escape
+            || self.strict;
+        if is_set && !matches!(mode, Mode::Encode { .. } | Mode::Wrap { .. }) {
+            return Err(usage_error(
+                "--lossy, --replacement, --tab-width, --escape, and --strict can only be used with the encode and wrap commands",
+            ));
+        }
+        if self.escape
+            && !matches!(
+                mode,
+                Mode::Encode {
+                    source: Some(Source::Text { .. }),
+                    ..
+                } | Mode::Wrap {
+                    source: Some(Source::Text { .. })
+                }
+            )
+        {
+            return Err(usage_error(
+                "--escape can only be used with the text source",
+            ));
+        }
+        match self.replacement {
+            Some(c) if !(' '..='~').contains(&c) => Err(usage_error(format!(
+                "the replacement character {c:?} can not be encoded"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads the text of the source and changes it according to the flags.
+    fn read(&self, source: Option<Source>) -> Result<String> {
+        Ok(self.apply(self.read_unchanged(source)?))
+    }
+
+    /// Reads the text of the source and interprets its escape sequences if asked to,
+    /// but does not change it in any of the ways that [`Self::apply`] does.
+    fn read_unchanged(&self, source: Option<Source>) -> Result<String> {
+        let text = read_raw_source(source)?;
+        if self.escape {
+            escape::unescape(&text).map_err(usage_error)
+        } else {
+            Ok(text)
+        }
+    }
+
+    /// Describes the carriage returns that [`Self::apply`] removes from the text and the tabs that it expands.
+    fn describe_changes(&self, text: &str) -> String {
+        let carriage_returns = if self.strict {
+            0
+        } else {
+            text.matches('\r').count()
+        };
+        let tabs = if self.tab_width.is_some() {
+            text.matches('\t').count()
+        } else {
+            0
+        };
+        changes_note(carriage_returns, tabs)
+    }
+
+    /// Returns the character that replaces the characters that can not be encoded, if they should be replaced.
+    fn replacement(&self) -> Option<char> {
+        self.replacement.or(self.lossy.then_some('?'))
+    }
+
+    /// Returns the options that make the functions in the `files` module change their input like [`Self::apply`].
+    fn file_options(&self) -> FileOptions {
+        FileOptions::new()
+            .tab_handling(
+                self.tab_width
+                    .map_or(TabHandling::Error, TabHandling::ExpandTo),
+            )
+            .carriage_returns(if self.strict {
+                CarriageReturns::Error
+            } else {
+                CarriageReturns::Strip
+            })
+    }
+
+    /// Removes the carriage returns, expands the tabs, and replaces the unencodable characters in the text
+    /// as the flags say.
+    fn apply(&self, text: String) -> String {
+        let text = if self.strict {
+            text
+        } else {
+            text.replace('\r', "")
+        };
+        let text = match self.tab_width {
+            Some(width) => text.replace('\t', &" ".repeat(width)),
+            None => text,
+        };
+        match self.replacement() {
+            Some(replacement) => text
+                .chars()
+                .map(|c| {
+                    if c == '\n' || (' '..='~').contains(&c) {
+                        c
+                    } else {
+                        replacement
+                    }
+                })
+                .collect(),
+            None => text,
+        }
+    }
+}
+
+/// The format that the results are printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Print the result as it is.
+    Human,
+    /// Print a JSON object that describes the result, or the error if there was one.
+    Json,
+    /// Print comma-separated values. Only valid with the table command.
+    Csv,
+}
+
+/// The size and composition of an encoded text.
+struct PayloadStats {
+    /// Whether the input was encoded, as opposed to these being the statistics of its encoding.
+    input_encoded: bool,
+    encoded_bytes: usize,
+    decoded_bytes: usize,
+    /// The number of lines in the decoded text, where a final line without a line ending is also counted.
+    decoded_lines: usize,
+    /// The number of grapheme clusters in the encoded text.
+    grapheme_clusters: usize,
+}
+
+impl PayloadStats {
+    /// Computes the statistics of the input if it is encoded, and otherwise of its encoding.
+    fn new(input: &str) -> Result<Self> {
+        let trimmed = input.trim_matches(|c: char| c.is_ascii_whitespace());
+        let (input_encoded, encoded, decoded) = if is_zalgo_encoded(trimmed) {
+            (true, trimmed.to_owned(), zalgo_decode(trimmed)?)
+        } else {
+            let encoded = zalgo_encode(input).map_err(|e| describe_encode_error(e, input))?;
+            (false, encoded, input.to_owned())
+        };
+        Ok(Self {
+            input_encoded,
+            encoded_bytes: encoded.len(),
+            decoded_bytes: decoded.len(),
+            decoded_lines: decoded.lines().count(),
+            grapheme_clusters: encoded.graphemes(true).count(),
+        })
+    }
+
+    /// Returns the number of encoded bytes per decoded byte, or `None` if the decoded text is empty.
+    fn overhead_ratio(&self) -> Option<f64> {
+        (self.decoded_bytes > 0).then(|| self.encoded_bytes as f64 / self.decoded_bytes as f64)
+    }
+
+    /// Describes the statistics as a JSON object. The overhead ratio is left out if it is not defined.
+    fn to_json(&self) -> Value {
+        let mut object = json!({
+            "input_encoded": self.input_encoded,
+            "encoded_bytes": self.encoded_bytes,
+            "decoded_bytes": self.decoded_bytes,
+            "decoded_lines": self.decoded_lines,
+            "grapheme_clusters": self.grapheme_clusters,
+        });
+        if let (Some(fields), Some(ratio)) = (object.as_object_mut(), self.overhead_ratio()) {
+            fields.insert("overhead_ratio".into(), ratio.into());
+        }
+        object
+    }
+}
+
+impl fmt::Display for PayloadStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.input_encoded {
+            writeln!(
+                f,
+                "the input is not encoded, these are the statistics of its encoding"
+            )?;
+        }
+        writeln!(f, "encoded bytes: {}", self.encoded_bytes)?;
+        writeln!(f, "decoded bytes: {}", self.decoded_bytes)?;
+        writeln!(f, "decoded lines: {}", self.decoded_lines)?;
+        if let Some(ratio) = self.overhead_ratio() {
+            writeln!(f, "overhead ratio: {ratio:.2}")?;
+        }
+        write!(f, "grapheme clusters: {}", self.grapheme_clusters)
+    }
+}
+
+/// What a successful command produced.
+struct Outcome {
+    /// The name of the command.
+    mode: &'static str,
+    /// The text that should be printed to stdout, if there is any.
+    output: Option<String>,
+    /// The number of bytes that were read and written, if they are known.
+    bytes: Option<(u64, u64)>,
+    /// The statistics computed by the `stats` command.
+    stats: Option<PayloadStats>,
+    /// The rows of the table printed by the `table` command.
+    table: Option<Vec<TableRow>>,
+    /// The measurements made by the `bench` command.
+    bench: Option<BenchResults>,
+}
+
+impl Outcome {
+    /// Creates the outcome of the command with the given name that produced nothing else.
+    fn new(mode: &'static str) -> Self {
+        Self {
+            mode,
+            output: None,
+            bytes: None,
+            stats: None,
+            table: None,
+            bench: None,
+        }
+    }
+
+    /// Sets the text that should be printed to stdout.
+    fn output(mut self, output: String) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Sets the number of bytes that were read and written.
+    fn bytes(mut self, bytes: Option<(u64, u64)>) -> Self {
+        self.bytes = bytes;
+        self
+    }
+
+    /// Describes the outcome as a JSON object.
+    fn into_json(self) -> Value {
+        let mut object = json!({ "ok": true, "mode": self.mode, "output": self.output });
+        if let Some(fields) = object.as_object_mut() {
+            if let Some((bytes_in, bytes_out)) = self.bytes {
+                fields.insert("bytes_in".into(), bytes_in.into());
+                fields.insert("bytes_out".into(), bytes_out.into());
+            }
+            if let Some(stats) = self.stats {
+                fields.insert("stats".into(), stats.to_json());
+            }
+            if let Some(table) = self.table {
+                fields.insert(
+                    "table".into(),
+                    table.iter().map(TableRow::to_json).collect(),
+                );
+            }
+            if let Some(bench) = self.bench {
+                fields.insert("bench".into(), bench.to_json());
+            }
+        }
+        object
+    }
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -57,7 +1074,8 @@ enum Mode {
     author,
     version,
     about = "Convert an ASCII text string into a single unicode grapheme cluster and back.\nThis program can be used to encode and decode strings from both stdin and files.",
-    long_about = None
+    long_about = None,
+    after_help = "Exit codes:\n  0  success\n  1  any other failure\n  2  invalid arguments\n  3  the input contains a character that can not be encoded\n  4  the input can not be decoded\n  5  a file could not be read or written"
 )]
 struct Cli {
     #[command(subcommand)]
@@ -69,75 +1087,673 @@ struct Cli {
     /// (not everything might appear visually, but it's still there).
     /// If your OS uses a text encoding other than UTF-8 (e.g. Windows uses UTF-16)
     /// you might want to use this option instead of an OS pipe to save to a file
-    /// in order to avoid broken text. NOTE: If this option is used it must occur before any commands.
+    /// in order to avoid broken text, possibly together with --output-encoding. NOTE: If this option is used it must occur before any commands.
     out_path: Option<PathBuf>,
 
     #[arg(short, long, required = false, requires = "out_path")]
     /// Overwrite the output file if it already exists.
     /// Only valid if OUT_PATH is also provided
     force: bool,
+
+    #[arg(short, long, required = false, requires = "out_path")]
+    /// Print nothing to stdout, not even the list of processed files or the JSON output.
+    /// Only valid if OUT_PATH is also provided
+    quiet: bool,
+
+    #[arg(long, global = true, required = false)]
+    /// Check that encoding or decoding the input and then reversing the result reproduces the input,
+    /// without producing any output. Prints a short report if it does, and exits with an error if it does not.
+    /// Can not be used together with OUT_PATH, --force, or --copy.
+    check: bool,
+
+    #[arg(short, long, required = false)]
+    /// Print a summary of how many bytes and lines were processed to stderr
+    /// when both the input and the output are files.
+    verbose: bool,
+
+    #[arg(long, global = true, required = false)]
+    /// Replace the contents of the input file with the result. The file is only replaced once the
+    /// entire result has been written, so it is left untouched if the command fails.
+    /// When decoding, the file must contain a single grapheme cluster without a line ending after it,
+    /// like the files that are encoded in place.
+    /// Can not be used together with OUT_PATH, --check, or --copy.
+    in_place: bool,
+
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = ".bak", value_name = "SUFFIX")]
+    /// Keep a copy of the input file at its path with SUFFIX appended, `.bak` if left out,
+    /// before replacing it with --in-place. Only valid together with --in-place.
+    backup: Option<String>,
+
+    #[arg(long, global = true, required = false)]
+    /// Encode or wrap the input file into OUT_PATH again every time the input file changes,
+    /// and print a line with the time to stderr for every rebuild. Errors are printed without stopping.
+    /// Runs until it is interrupted with Ctrl-C. Only valid with the encode and wrap commands, a file source,
+    /// and OUT_PATH, and can not be used together with --check, --in-place, or --copy.
+    watch: bool,
+
+    #[command(flatten)]
+    encode_flags: EncodeFlags,
+
+    #[arg(long, global = true, value_enum, default_value_t = OutputEncoding::Utf8, value_name = "ENCODING")]
+    /// The encoding of the file at OUT_PATH. Windows tools often expect UTF-16 with a byte order mark.
+    /// Only valid if OUT_PATH is also provided. Input that starts with a UTF-16 byte order mark
+    /// is always read as UTF-16.
+    output_encoding: OutputEncoding,
+
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto, value_name = "WHEN")]
+    /// When to color the error messages. With `auto` they are colored if stderr is a terminal
+    /// and the NO_COLOR environment variable is not set.
+    color: ColorChoice,
+
+    #[arg(long, global = true, value_enum, default_value_t = Format::Human)]
+    /// The format of the output. With `json` the result is printed to stdout as a JSON object
+    /// like `{"ok": true, "mode": "encode", "output": "...", "bytes_in": 5, "bytes_out": 11}`,
+    /// and errors as a JSON object like `{"ok": false, "error": {"kind": "NotAscii", ...}}`.
+    /// Errors are still described on stderr.
+    format: Format,
+
+    #[arg(long, global = true, required = false)]
+    /// Do not print a line break after the output, so that the exact bytes of the result
+    /// can be piped into another program. Has no effect with `--format json`.
+    no_trailing_newline: bool,
+
+    #[arg(long, global = true, required = false)]
+    /// Also copy the result to the clipboard, whether it is printed or saved to OUT_PATH.
+    /// Requires the program to be built with the `clipboard` feature.
+    copy: bool,
 }
 
-fn main() -> Result<()> {
+impl Cli {
+    /// Returns an error if flags that can not be used together were given.
+    ///
+    /// Clap does not check conflicts between a global flag and the flags of the top-level command
+    /// when the global flag is given after a subcommand, so it is done here instead.
+    fn validate(&self) -> Result<()> {
+        if self.check && (self.out_path.is_some() || self.force || self.copy) {
+            return Err(usage_error(
+                "--check can not be used with --out-path, --force, or --copy",
+            ));
+        }
+        if self.in_place && (self.out_path.is_some() || self.check || self.copy) {
+            return Err(usage_error(
+                "--in-place can not be used with --out-path, --check, or --copy",
+            ));
+        }
+        if self.in_place && (self.encode_flags.lossy || self.encode_flags.replacement.is_some()) {
+            return Err(usage_error(
+                "--lossy and --replacement can not be used with --in-place",
+            ));
+        }
+        if self.backup.is_some() && !self.in_place {
+            return Err(usage_error(
+                "--backup can only be used together with --in-place",
+            ));
+        }
+        if self.watch {
+            if self.out_path.is_none() {
+                return Err(usage_error(
+                    "--watch can only be used together with --out-path",
+                ));
+            }
+            if self.check || self.in_place || self.copy {
+                return Err(usage_error(
+                    "--watch can not be used with --check, --in-place, or --copy",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn main() -> ExitCode {
     let config = Cli::parse();
+    let format = config.format;
+    let quiet = config.quiet;
+    let color = config.color.use_color();
+    let trailing_newline = !config.no_trailing_newline;
+
+    let result = run(config, &mut SystemClipboard).and_then(|outcome| {
+        match (quiet, format) {
+            (true, _) => (),
+            (false, Format::Human | Format::Csv) => {
+                if let Some(output) = outcome.output {
+                    write_output(&output, trailing_newline)?;
+                }
+            }
+            (false, Format::Json) => println!("{}", outcome.into_json()),
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if format == Format::Json && !quiet {
+                println!("{}", json!({ "ok": false, "error": error_json(&e) }));
+            }
+            let message = format!("Error: {e:?}");
+            if color {
+                eprintln!("{}", paint_error(&message));
+            } else {
+                eprintln!("{message}");
+            }
+            exit_code(&e)
+        }
+    }
+}
+
+/// Writes the output to stdout as it is, followed by a line break if `trailing_newline` is true.
+fn write_output(output: &str, trailing_newline: bool) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(output.as_bytes())?;
+    if trailing_newline {
+        stdout.write_all(b"\n")?;
+    }
+    stdout.flush()
+}
+
+/// Replaces the contents of the input file of the command with the result, after copying it to a backup file
+/// if the configuration asks for it. Returns the number of bytes that were read and written if they are known.
+fn edit_in_place(config: &Cli) -> Result<Option<(u64, u64)>> {
+    let path = match &config.mode {
+        Mode::Encode {
+            source: Some(Source::File { path, more_paths }),
+            ..
+        }
+        | Mode::Decode {
+            source: Some(Source::File { path, more_paths }),
+            ..
+        }
+        | Mode::Wrap {
+            source: Some(Source::File { path, more_paths }),
+        }
+        | Mode::Unwrap {
+            source: Some(Source::File { path, more_paths }),
+        } if file_path(Some(path)).is_some() && more_paths.is_empty() => path,
+        _ => {
+            return Err(usage_error(
+                "--in-place can only be used with the encode, decode, wrap, and unwrap commands and a single file",
+            ))
+        }
+    };
+
+    if let Some(suffix) = &config.backup {
+        let mut backup = path.clone().into_os_string();
+        backup.push(suffix);
+        std::fs::copy(path, backup)?;
+    }
+
+    let options = config.encode_flags.file_options();
+    let (verb, stats) = match config.mode {
+        Mode::Encode { .. } => (
+            "encoded",
+            encode_file_with_options(path, path, &options)
+                .map_err(|e| describe_unencodable_file(e, path))?,
+        ),
+        Mode::Wrap { .. } => (
+            "wrapped",
+            wrap_python_file_with_options(path, path, &options)
+                .map_err(|e| describe_unencodable_file(e, path))?,
+        ),
+        Mode::Decode { .. } => ("decoded", decode_file_with_options(path, path, &options)?),
+        Mode::Unwrap { .. } => {
+            unwrap_python_file_with_options(path, path, &options)?;
+            return Ok(None);
+        }
+        _ => unreachable!("the other commands have been rejected above"),
+    };
+    if config.verbose {
+        eprintln!("{}", summarize(verb, &stats));
+    }
+    Ok(Some((stats.bytes_in, stats.bytes_out)))
+}
+
+/// Replaces the paths of a file source that contain wildcards with the paths of the files that match them.
+#[cfg(windows)]
+fn expand_globs(mut config: Cli) -> Result<Cli> {
+    if let Mode::Encode {
+        source: Some(Source::File { path, more_paths }),
+        ..
+    }
+    | Mode::Decode {
+        source: Some(Source::File { path, more_paths }),
+        ..
+    }
+    | Mode::Wrap {
+        source: Some(Source::File { path, more_paths }),
+    }
+    | Mode::Unwrap {
+        source: Some(Source::File { path, more_paths }),
+    } = &mut config.mode
+    {
+        let mut paths = Vec::new();
+        for pattern in std::iter::once(&*path).chain(more_paths.iter()) {
+            match pattern.to_str() {
+                Some(pattern) if pattern.contains(['*', '?', '[']) => {
+                    for entry in glob::glob(pattern)? {
+                        paths.push(entry?);
+                    }
+                }
+                _ => paths.push(pattern.clone()),
+            }
+        }
+        if paths.is_empty() {
+            return Err(usage_error("no files match the given paths"));
+        }
+        *more_paths = paths.split_off(1);
+        *path = paths.swap_remove(0);
+    }
+    Ok(config)
+}
+
+/// Carries out the command in the configuration every time its input file changes.
+fn watch_file(config: Cli, clipboard: &mut impl Clipboard) -> Result<()> {
+    let (verb, path) = match &config.mode {
+        Mode::Encode {
+            source: Some(Source::File { path, more_paths }),
+            ..
+        } if file_path(Some(path)).is_some() && more_paths.is_empty() => ("encoded", path.clone()),
+        Mode::Wrap {
+            source: Some(Source::File { path, more_paths }),
+        } if file_path(Some(path)).is_some() && more_paths.is_empty() => ("wrapped", path.clone()),
+        _ => {
+            return Err(usage_error(
+                "--watch can only be used with the encode and wrap commands and a single file",
+            ))
+        }
+    };
+    let destination = config.out_path.clone().unwrap_or_default();
+    // Whether the output file may be overwritten has already been checked,
+    // and every rebuild after the first one overwrites it.
+    let config = Cli {
+        watch: false,
+        force: true,
+        ..config
+    };
+    watch::watch(&path, || {
+        run(config.clone(), clipboard)?;
+        Ok(format!(
+            "{verb} {} into {}",
+            path.display(),
+            destination.display()
+        ))
+    })
+}
+
+/// Carries out the command in the configuration, and copies the result to the clipboard if asked to.
+fn run(config: Cli, clipboard: &mut impl Clipboard) -> Result<Outcome> {
+    // The shells on Windows do not expand the wildcards in paths, so it is done here instead.
+    #[cfg(windows)]
+    let config = expand_globs(config)?;
+    let mode = config.mode.name();
+    config.validate()?;
+    config.encode_flags.validate(&config.mode)?;
+
+    if config.check {
+        return Ok(Outcome::new(mode).output(check_round_trip(config.mode, &config.encode_flags)?));
+    }
 
     if let Some(ref destination) = config.out_path {
         if destination.exists() && !config.force {
             match config.mode {
                 #[cfg(feature = "gui")]
                 Mode::Gui => (),
-                _ => return Err(anyhow!("the file \"{}\" already exists, to overwrite its contents you can supply the -f or --force arguments", destination.to_string_lossy())),
+                _ => return Err(usage_error(format!("the file \"{}\" already exists, to overwrite its contents you can supply the -f or --force arguments", destination.to_string_lossy()))),
+            }
+        }
+    }
+
+    if config.watch {
+        return watch_file(config, clipboard).map(|()| Outcome::new(mode));
+    }
+
+    if let Mode::Wrap {
+        source: Some(source),
+    }
+    | Mode::Unwrap {
+        source: Some(source),
+    } = &config.mode
+    {
+        if source.batch().is_some() {
+            return Err(usage_error(
+                "only the encode and decode commands can process a directory or several files",
+            ));
+        }
+    }
+
+    if config.format == Format::Csv && !matches!(config.mode, Mode::Table) {
+        return Err(usage_error(
+            "--format csv can only be used with the table command",
+        ));
+    }
+
+    if config.output_encoding != OutputEncoding::Utf8 && config.out_path.is_none() {
+        return Err(usage_error(
+            "--output-encoding can only be used together with --out-path",
+        ));
+    }
+
+    if let Mode::Decode {
+        escape_output: true,
+        ..
+    } = &config.mode
+    {
+        if config.in_place || config.check {
+            return Err(usage_error(
+                "--escape-output can not be used with --in-place or --check",
+            ));
+        }
+    }
+
+    if config.mode.per_line() && (config.in_place || config.check) {
+        return Err(usage_error(
+            "--per-line can not be used with --in-place or --check",
+        ));
+    }
+
+    if config.in_place {
+        return edit_in_place(&config).map(|bytes| Outcome::new(mode).bytes(bytes));
+    }
+
+    // A directory or several files are processed into another directory.
+    if let Mode::Encode {
+        source: Some(source),
+        ..
+    }
+    | Mode::Decode {
+        source: Some(source),
+        ..
+    } = &config.mode
+    {
+        if let Some(batch) = source.batch() {
+            let Some(destination) = config.out_path.as_deref() else {
+                return Err(usage_error(
+                    "a directory or several files can only be processed into a directory given by --out-path",
+                ));
+            };
+            if config.copy {
+                return Err(usage_error(
+                    "--copy can not be used when processing a directory or several files",
+                ));
             }
+            if config.mode.per_line() {
+                return Err(usage_error(
+                    "--per-line can not be used when processing a directory or several files",
+                ));
+            }
+            if config.output_encoding != OutputEncoding::Utf8 {
+                return Err(usage_error(
+                    "--output-encoding can not be used when processing a directory or several files",
+                ));
+            }
+            if config.encode_flags.replacement().is_some() {
+                return Err(usage_error(
+                    "--lossy and --replacement can not be used when processing a directory or several files",
+                ));
+            }
+            if let Mode::Decode {
+                escape_output: true,
+                ..
+            } = config.mode
+            {
+                return Err(usage_error(
+                    "--escape-output can not be used when processing a directory or several files",
+                ));
+            }
+
+            let encode = matches!(config.mode, Mode::Encode { .. });
+            let options = config.encode_flags.file_options();
+            // The list of files is not a part of the JSON output, so it is printed to stderr instead.
+            let print = |line: String| match (config.quiet, config.format) {
+                (true, _) => (),
+                (false, Format::Human | Format::Csv) => println!("{line}"),
+                (false, Format::Json) => eprintln!("{line}"),
+            };
+            match batch {
+                Batch::Dir(path) => process_dir(encode, path, destination, &options, print)?,
+                Batch::Files(paths) => {
+                    process_files(encode, &paths, destination, &options, print)?;
+                }
+            }
+            return Ok(Outcome::new(mode));
         }
     }
 
-    let output = match config.mode {
+    // When both the input and the output are files they are processed by the functions in the `files` module,
+    // unless the result must also be copied, the unencodable characters must be replaced,
+    // or the output must be written in another encoding than UTF-8.
+    if let (Some(destination), false, None, OutputEncoding::Utf8) = (
+        config.out_path.as_deref(),
+        config.copy,
+        config.encode_flags.replacement(),
+        config.output_encoding,
+    ) {
+        let options = config.encode_flags.file_options();
+        let stats = match &config.mode {
+            Mode::Encode {
+                per_line: false,
+                source: Some(Source::File { path, .. }),
+            } if file_path(Some(path)).is_some() => Some((
+                "encoded",
+                encode_file_with_options(path, destination, &options)
+                    .map_err(|e| describe_unencodable_file(e, path))?,
+            )),
+            Mode::Wrap {
+                source: Some(Source::File { path, .. }),
+            } if file_path(Some(path)).is_some() => Some((
+                "wrapped",
+                wrap_python_file_with_options(path, destination, &options)
+                    .map_err(|e| describe_unencodable_file(e, path))?,
+            )),
+            Mode::Decode {
+                single: true,
+                escape_output: false,
+                source: Some(Source::File { path, .. }),
+                ..
+            } if file_path(Some(path)).is_some() && !has_byte_order_mark(&read_prefix(path)?) => {
+                Some(("decoded", decode_file(path, destination)?))
+            }
+            _ => None,
+        };
+        if let Some((verb, stats)) = stats {
+            if config.verbose {
+                eprintln!("{}", summarize(verb, &stats));
+            }
+            return Ok(Outcome::new(mode).bytes(Some((stats.bytes_in, stats.bytes_out))));
+        }
+    }
+
+    let mut outcome = Outcome::new(mode);
+    let (input_len, output) = match config.mode {
         #[cfg(feature = "gui")]
         Mode::Gui => gui::run_gui(),
-        Mode::Encode { source } => {
-            let text = match source {
-                Source::Text { text } => text.join(" "),
-                Source::File { path } => std::fs::read_to_string(path)?.replace('\r', ""),
+        Mode::Encode { per_line, source } => {
+            let text = config.encode_flags.read(source)?;
+            let encoded = zalgo_encode(&text).map_err(|e| describe_encode_error(e, &text))?;
+            // The lines can all be encoded if the whole text can, and encoding the whole text first
+            // describes an error with its position in the text instead of in the line.
+            let encoded = if per_line {
+                encode_per_line(&text)?
+            } else {
+                encoded
             };
-            zalgo_encode(&text)?
+            (text.len(), encoded)
         }
-        Mode::Wrap { path } => {
-            let text = std::fs::read_to_string(path)?.replace('\r', "");
-            zalgo_wrap_python(&text)?
+        Mode::Wrap { source } => {
+            let text = config.encode_flags.read(source)?;
+            let wrapped = zalgo_wrap_python(&text).map_err(|e| describe_encode_error(e, &text))?;
+            (text.len(), wrapped)
         }
-        Mode::Decode { source } => {
+        Mode::Decode {
+            single,
+            per_line,
+            escape_output,
+            source,
+        } => {
             let encoded = match source {
-                Source::Text { mut text } => {
+                Some(Source::Text { mut text }) if single => {
                     if text.len() == 1 {
                         Ok(text.swap_remove(0))
                     } else {
-                        Err(anyhow!("can only decode one grapheme cluster at a time"))
+                        Err(usage_error(
+                            "can only decode one grapheme cluster at a time",
+                        ))
                     }?
                 }
-                Source::File { path } => std::fs::read_to_string(path)?.replace('\r', ""),
+                Some(Source::Text { text }) => text.join("\n"),
+                Some(Source::File { ref path, .. }) if file_path(Some(path)).is_some() => {
+                    read_input(Some(path))?
+                }
+                // The encoded text that is piped in usually ends with the line ending
+                // that was printed after it.
+                _ => {
+                    let input = read_input(None)?;
+                    match input.strip_suffix('\n') {
+                        Some(encoded) => encoded.to_string(),
+                        None => input,
+                    }
+                }
             };
 
-            zalgo_decode(&encoded)?
+            let decoded = if single {
+                decode_validated(&encoded).map_err(|e| describe_decode_error(e, &encoded, None))?
+            } else if per_line {
+                decode_per_line(&encoded)?
+            } else {
+                decode_lines(&encoded)?
+            };
+            let decoded = if escape_output {
+                escape::escape(&decoded)
+            } else {
+                decoded
+            };
+            (encoded.len(), decoded)
         }
-        Mode::Unwrap { path } => {
-            let contents = std::fs::read_to_string(path)?;
-            let mut chars = contents.chars();
-            for _ in 0..3 {
-                chars.next();
-            }
-            for _ in 0..88 {
-                chars.next_back();
+        Mode::Unwrap { source } => {
+            let contents = read_source(source)?;
+            let unwrapped = zalgo_unwrap_python(&contents).map_err(|e| DescribedError {
+                description: format!("failed to unwrap the python code: {e}"),
+                error: e,
+            })?;
+            (contents.len(), unwrapped)
+        }
+        Mode::Stats { source } => {
+            let text = read_source(source)?;
+            let payload_stats = PayloadStats::new(&text)?;
+            let description = payload_stats.to_string();
+            // The size of the description says nothing about the payload, so the encoded size is reported instead.
+            outcome = outcome.bytes(Some((
+                text.len() as u64,
+                payload_stats.encoded_bytes as u64,
+            )));
+            outcome.stats = Some(payload_stats);
+            (text.len(), description)
+        }
+        Mode::Repl => {
+            if config.out_path.is_some() || config.copy {
+                return Err(usage_error(
+                    "the results of the repl command can not be saved or copied with --out-path or --copy, use :copy instead",
+                ));
             }
-            let encoded: String = chars.collect();
-            zalgo_decode(&encoded)?
+            let stdin = io::stdin();
+            let prompt = stdin.is_terminal();
+            repl::run_repl(stdin.lock(), io::stdout(), io::stderr(), clipboard, prompt)?;
+            return Ok(Outcome::new(mode));
+        }
+        Mode::Table => {
+            let rows = conversion_table();
+            let rendered = match config.format {
+                Format::Csv => render_table_csv(&rows),
+                _ => render_table(&rows),
+            };
+            outcome.table = Some(rows);
+            (0, rendered)
+        }
+        Mode::Bench { size, source } => {
+            let text = match (size, source) {
+                (Some(_), Some(_)) => {
+                    return Err(usage_error("--size can not be used when a source is given"))
+                }
+                (size, None) => PrintableAsciiAndNewline
+                    .sample_string(&mut rand::thread_rng(), size.unwrap_or(DEFAULT_BENCH_SIZE)),
+                (None, source) => read_source(source)?,
+            };
+            let results = BenchResults::measure(&text, BENCH_DURATION)
+                .map_err(|e| describe_encode_error(e, &text))?;
+            let description = results.to_string();
+            outcome.bench = Some(results);
+            (text.len(), description)
+        }
+        Mode::Completions { shell } => {
+            let mut script = Vec::new();
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                env!("CARGO_BIN_NAME"),
+                &mut script,
+            );
+            (0, String::from_utf8(script)?)
         }
     };
 
+    let outcome = match outcome.bytes {
+        Some(_) => outcome,
+        None => outcome.bytes(Some((input_len as u64, output.len() as u64))),
+    };
+    if config.copy {
+        clipboard.set_contents(output.clone())?;
+    }
     match config.out_path {
-        Some(dst) => Ok(std::fs::write(dst, output)?),
-        None => {
-            println!("{output}");
-            Ok(())
+        Some(dst) => {
+            std::fs::write(dst, config.output_encoding.encode(&output))?;
+            Ok(outcome)
         }
+        None => Ok(outcome.output(output)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clipboard::MockClipboard;
+
+    fn run_with_mock(args: &[&str]) -> (Outcome, MockClipboard) {
+        let config =
+            Cli::try_parse_from(std::iter::once("zalgo_codec").chain(args.iter().copied()))
+                .unwrap();
+        let mut clipboard = MockClipboard::default();
+        (run(config, &mut clipboard).unwrap(), clipboard)
+    }
+
+    #[test]
+    fn copy() {
+        let encoded = zalgo_encode("Zalgo").unwrap();
+        let (outcome, clipboard) = run_with_mock(&["encode", "text", "Zalgo", "--copy"]);
+        assert_eq!(clipboard.0.as_deref(), Some(encoded.as_str()));
+        // The result is still printed.
+        assert_eq!(outcome.output.as_deref(), Some(encoded.as_str()));
+    }
+
+    #[test]
+    fn copy_with_out_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("decoded.txt");
+        let (outcome, clipboard) = run_with_mock(&[
+            "--out-path",
+            path.to_str().unwrap(),
+            "--copy",
+            "decode",
+            "text",
+            &zalgo_encode("Zalgo").unwrap(),
+        ]);
+        assert_eq!(clipboard.0.as_deref(), Some("Zalgo"));
+        assert_eq!(outcome.output, None);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "Zalgo");
+    }
+
+    #[test]
+    fn no_copy() {
+        let (_, clipboard) = run_with_mock(&["encode", "text", "Zalgo"]);
+        assert_eq!(clipboard.0, None);
     }
 }
@@ -0,0 +1,28 @@
+//! A distribution of random text that can be encoded, used by the tests and by the `bench` command of the binary.
+
+extern crate alloc;
+
+use alloc::string::String;
+use rand::{
+    distributions::{DistString, Distribution},
+    seq::SliceRandom,
+    Rng,
+};
+
+/// Samples printable ASCII characters and newlines.
+pub struct PrintableAsciiAndNewline;
+
+impl Distribution<char> for PrintableAsciiAndNewline {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        *b" !\"#$%&'()*,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVXYZ[\\]^_`abcdefghijklmnopqrstuvxyz{|}~\n".choose(rng).unwrap() as char
+    }
+}
+
+impl DistString for PrintableAsciiAndNewline {
+    fn append_string<R: Rng + ?Sized>(&self, rng: &mut R, string: &mut String, len: usize) {
+        string.reserve(len);
+        for _ in 0..len {
+            string.push(self.sample(rng));
+        }
+    }
+}
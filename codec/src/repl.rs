@@ -0,0 +1,172 @@
+//! An interactive mode where every line that is entered is encoded, decoded, wrapped, or unwrapped.
+
+use crate::{clipboard::Clipboard, describe_encode_error};
+
+use std::io::{BufRead, Write};
+
+use anyhow::{anyhow, Result};
+use zalgo_codec::{zalgo_decode, zalgo_encode, zalgo_unwrap_python, zalgo_wrap_python};
+
+/// The line that ends the input in paste mode.
+const PASTE_END: &str = ".";
+
+/// What the REPL does with the text that is entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReplMode {
+    Encode,
+    Decode,
+    Wrap,
+    Unwrap,
+}
+
+impl ReplMode {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Encode => "encode",
+            Self::Decode => "decode",
+            Self::Wrap => "wrap",
+            Self::Unwrap => "unwrap",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Encode, Self::Decode, Self::Wrap, Self::Unwrap]
+            .into_iter()
+            .find(|mode| mode.name() == name)
+    }
+
+    fn apply(self, text: &str) -> Result<String> {
+        match self {
+            Self::Encode => zalgo_encode(text).map_err(|e| describe_encode_error(e, text).into()),
+            Self::Decode => Ok(zalgo_decode(
+                text.trim_matches(|c: char| c.is_ascii_whitespace()),
+            )?),
+            Self::Wrap => {
+                zalgo_wrap_python(text).map_err(|e| describe_encode_error(e, text).into())
+            }
+            Self::Unwrap => Ok(zalgo_unwrap_python(text)?),
+        }
+    }
+}
+
+/// Reads lines from `input` until it ends, and writes the result of applying the current mode
+/// to every one of them to `output`. Errors are written to `errors` and do not end the loop.
+///
+/// A line that ends with a backslash continues on the next line, and the line `:paste` starts a block
+/// of lines that is ended by a line with only a `.`. The lines `:encode`, `:decode`, `:wrap`, and `:unwrap`
+/// switch the mode, `:mode` prints the current mode, and `:copy` copies the last result to the clipboard.
+///
+/// If `prompt` is true the name of the current mode is written to `output` before every line is read.
+pub fn run_repl(
+    input: impl BufRead,
+    mut output: impl Write,
+    mut errors: impl Write,
+    clipboard: &mut impl Clipboard,
+    prompt: bool,
+) -> Result<()> {
+    let mut lines = input.lines();
+    let mut mode = ReplMode::Encode;
+    let mut last_result: Option<String> = None;
+
+    loop {
+        if prompt {
+            write!(output, "{}> ", mode.name())?;
+            output.flush()?;
+        }
+        let Some(line) = lines.next() else {
+            if prompt {
+                writeln!(output)?;
+            }
+            return Ok(());
+        };
+        let line = line?;
+
+        let text = match line.trim() {
+            ":mode" => {
+                writeln!(output, "{}", mode.name())?;
+                continue;
+            }
+            ":copy" => {
+                let copied = last_result
+                    .clone()
+                    .ok_or_else(|| anyhow!("there is no result to copy yet"))
+                    .and_then(|result| clipboard.set_contents(result));
+                if let Err(e) = copied {
+                    writeln!(errors, "{e}")?;
+                }
+                continue;
+            }
+            ":paste" => {
+                let mut pasted = Vec::new();
+                for line in lines.by_ref() {
+                    let line = line?;
+                    if line == PASTE_END {
+                        break;
+                    }
+                    pasted.push(line);
+                }
+                pasted.join("\n")
+            }
+            command => {
+                if let Some(new_mode) = command.strip_prefix(':').and_then(ReplMode::from_name) {
+                    mode = new_mode;
+                    continue;
+                }
+                let mut text = line;
+                while text.ends_with('\\') {
+                    text.pop();
+                    text.push('\n');
+                    match lines.next() {
+                        Some(next) => text.push_str(&next?),
+                        None => break,
+                    }
+                }
+                text
+            }
+        };
+
+        match mode.apply(&text) {
+            Ok(result) => {
+                writeln!(output, "{result}")?;
+                last_result = Some(result);
+            }
+            Err(e) => writeln!(errors, "{e}")?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clipboard::MockClipboard;
+
+    /// Runs the REPL on the input and returns what it wrote to the output and the errors.
+    fn transcript(input: &str, clipboard: &mut MockClipboard) -> (String, String) {
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        run_repl(input.as_bytes(), &mut output, &mut errors, clipboard, false).unwrap();
+        (
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(errors).unwrap(),
+        )
+    }
+
+    #[test]
+    fn copy_last_result() {
+        let mut clipboard = MockClipboard::default();
+        let (_, errors) = transcript(":copy\nZalgo\n:copy\n", &mut clipboard);
+        assert_eq!(errors, "there is no result to copy yet\n");
+        assert_eq!(clipboard.0, Some(zalgo_encode("Zalgo").unwrap()));
+    }
+
+    #[test]
+    fn switch_modes() {
+        let encoded = zalgo_encode("Zalgo").unwrap();
+        let (output, errors) = transcript(
+            &format!(":mode\n:decode\n:mode\n{encoded}\n:encode\nZalgo\n"),
+            &mut MockClipboard::default(),
+        );
+        assert_eq!(output, format!("encode\ndecode\nZalgo\n{encoded}\n"));
+        assert!(errors.is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! The table that shows which combining character every encodable character is turned into.
+
+use serde_json::{json, Value};
+use zalgo_codec::zalgo_encode;
+
+/// The character that the combining characters are shown on top of.
+const DOTTED_CIRCLE: char = '\u{25CC}';
+
+/// A row of the conversion table.
+pub struct TableRow {
+    /// The character that is encoded.
+    character: char,
+    /// The combining character that it is encoded into.
+    combining: char,
+}
+
+impl TableRow {
+    /// Returns a name for the character that is visible in a terminal.
+    fn name(&self) -> String {
+        match self.character {
+            ' ' => "SPACE".to_string(),
+            '\n' => "LF".to_string(),
+            c => c.to_string(),
+        }
+    }
+
+    /// Returns the combining character on top of a dotted circle.
+    fn rendered(&self) -> String {
+        format!("{DOTTED_CIRCLE}{}", self.combining)
+    }
+
+    /// Describes the row as a JSON object.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "character": self.character,
+            "name": self.name(),
+            "code_point": u32::from(self.character),
+            "combining_code_point": u32::from(self.combining),
+            "rendered": self.rendered(),
+        })
+    }
+}
+
+/// Returns a row for every character that can be encoded, in the order of their code points.
+///
+/// The combining characters are found by encoding the characters, so the table always agrees with the codec.
+pub fn conversion_table() -> Vec<TableRow> {
+    ('\n'..='\n')
+        .chain(' '..='~')
+        .map(|character| {
+            let encoded = zalgo_encode(&character.to_string())
+                .expect("printable ASCII characters and newlines can be encoded");
+            TableRow {
+                character,
+                combining: encoded
+                    .chars()
+                    .nth(1)
+                    .expect("a character is encoded into a single combining character"),
+            }
+        })
+        .collect()
+}
+
+/// Formats the table with aligned columns.
+pub fn render_table(rows: &[TableRow]) -> String {
+    let mut table = format!(
+        "{:<9} {:<10} {:<9} {}",
+        "character", "code point", "combining", "rendered"
+    );
+    for row in rows {
+        // Every code point in the table has four hexadecimal digits.
+        table.push_str(&format!(
+            "\n{:<9} U+{:04X}     U+{:04X}    {}",
+            row.name(),
+            u32::from(row.character),
+            u32::from(row.combining),
+            row.rendered()
+        ));
+    }
+    table
+}
+
+/// Formats the table as comma-separated values.
+pub fn render_table_csv(rows: &[TableRow]) -> String {
+    let mut table = String::from("character,code_point,combining_code_point,rendered");
+    for row in rows {
+        let name = match row.name() {
+            name if name.contains([',', '"']) => format!("\"{}\"", name.replace('"', "\"\"")),
+            name => name,
+        };
+        table.push_str(&format!(
+            "\n{name},U+{:04X},U+{:04X},{}",
+            u32::from(row.character),
+            u32::from(row.combining),
+            row.rendered()
+        ));
+    }
+    table
+}
@@ -0,0 +1,117 @@
+//! Converts the text that the CLI reads and writes between UTF-8 and the other encodings of Unicode,
+//! since e.g. a lot of tooling on Windows expects UTF-16.
+
+use std::io;
+
+use clap::ValueEnum;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// The encoding that the output file is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputEncoding {
+    /// UTF-8 without a byte order mark.
+    #[default]
+    Utf8,
+    /// UTF-8 with a byte order mark.
+    Utf8Bom,
+    /// UTF-16 in little-endian byte order with a byte order mark.
+    #[value(name = "utf16le")]
+    Utf16Le,
+    /// UTF-16 in big-endian byte order with a byte order mark.
+    #[value(name = "utf16be")]
+    Utf16Be,
+}
+
+impl OutputEncoding {
+    /// Returns the bytes of the text in this encoding, starting with the byte order mark if it has one.
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Self::Utf8 => text.as_bytes().to_vec(),
+            Self::Utf8Bom => [UTF8_BOM, text.as_bytes()].concat(),
+            Self::Utf16Le => UTF16LE_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                .collect(),
+            Self::Utf16Be => UTF16BE_BOM
+                .iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+                .collect(),
+        }
+    }
+}
+
+/// Returns whether the bytes start with a byte order mark of UTF-8 or UTF-16.
+pub fn has_byte_order_mark(bytes: &[u8]) -> bool {
+    [UTF8_BOM, UTF16LE_BOM, UTF16BE_BOM]
+        .iter()
+        .any(|bom| bytes.starts_with(bom))
+}
+
+/// Turns the bytes into text. The bytes are UTF-16 if they start with a UTF-16 byte order mark, and UTF-8 otherwise.
+/// The byte order mark is not a part of the text.
+///
+/// Invalid text results in an error of the kind [`io::ErrorKind::InvalidData`], like the one from [`io::read_to_string`].
+pub fn decode_bytes(bytes: Vec<u8>) -> io::Result<String> {
+    if let Some(rest) = bytes.strip_prefix(UTF16LE_BOM) {
+        decode_utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(UTF16BE_BOM) {
+        decode_utf16(rest, u16::from_be_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        String::from_utf8(rest.to_vec()).map_err(invalid_data)
+    } else {
+        String::from_utf8(bytes).map_err(invalid_data)
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> io::Result<String> {
+    if bytes.len() % 2 != 0 {
+        return Err(invalid_data("the UTF-16 input has an odd number of bytes"));
+    }
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(invalid_data)
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "Zalgo \u{1f47b}\n";
+        for encoding in [
+            OutputEncoding::Utf8,
+            OutputEncoding::Utf8Bom,
+            OutputEncoding::Utf16Le,
+            OutputEncoding::Utf16Be,
+        ] {
+            let bytes = encoding.encode(text);
+            assert_eq!(
+                has_byte_order_mark(&bytes),
+                encoding != OutputEncoding::Utf8
+            );
+            assert_eq!(decode_bytes(bytes).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn utf16le() {
+        assert_eq!(
+            OutputEncoding::Utf16Le.encode("E\u{301}"),
+            [0xFF, 0xFE, b'E', 0x00, 0x01, 0x03]
+        );
+        assert!(decode_bytes(vec![0xFF, 0xFE, b'E']).is_err());
+    }
+}
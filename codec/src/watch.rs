@@ -0,0 +1,202 @@
+//! Reruns a command every time its input file changes.
+
+use std::{
+    io::{self, Write},
+    path::Path,
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// How long the input file must be left alone after it changes before the command is rerun,
+/// so that an editor that saves a file in several steps only causes a single rebuild.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Calls `rebuild` once, and then again every time the file at `path` changes, until the program is interrupted.
+///
+/// `rebuild` returns a line that describes what it did, which is printed to stderr after a timestamp.
+/// Its errors are printed in the same way, but do not stop the watching.
+pub fn watch(path: &Path, mut rebuild: impl FnMut() -> Result<String>) -> Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    // Many editors save a file by replacing it, which ends a watch on the file itself,
+    // so the directory that contains it is watched instead.
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    let mut log = io::stderr();
+    report(&mut log, rebuild())?;
+    watch_events(path, &receiver, DEBOUNCE, rebuild, log)
+}
+
+/// Waits for events that change the file at `path`, and calls `rebuild` once no more events
+/// have arrived for the `debounce` duration. Returns when the sender of the events is dropped.
+fn watch_events(
+    path: &Path,
+    events: &Receiver<notify::Result<Event>>,
+    debounce: Duration,
+    mut rebuild: impl FnMut() -> Result<String>,
+    mut log: impl Write,
+) -> Result<()> {
+    loop {
+        match events.recv() {
+            Ok(Ok(event)) if changes(&event, path) => (),
+            Ok(Ok(_)) => continue,
+            Ok(Err(e)) => {
+                writeln!(log, "[{}] Error: {e}", timestamp())?;
+                continue;
+            }
+            Err(_) => return Ok(()),
+        }
+
+        let disconnected = loop {
+            match events.recv_timeout(debounce) {
+                Ok(_) => (),
+                Err(RecvTimeoutError::Timeout) => break false,
+                Err(RecvTimeoutError::Disconnected) => break true,
+            }
+        };
+
+        report(&mut log, rebuild())?;
+        if disconnected {
+            return Ok(());
+        }
+    }
+}
+
+/// Returns whether the event says that the file at `path` was created or modified.
+fn changes(event: &Event, path: &Path) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name() == path.file_name())
+}
+
+/// Writes the outcome of a rebuild to the log after a timestamp.
+fn report(mut log: impl Write, outcome: Result<String>) -> io::Result<()> {
+    match outcome {
+        Ok(status) => writeln!(log, "[{}] {status}", timestamp()),
+        Err(e) => writeln!(log, "[{}] Error: {e:#}", timestamp()),
+    }
+}
+
+/// Returns the current time of day in UTC as `hh:mm:ss`.
+fn timestamp() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+        % (24 * 60 * 60);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use notify::event::{CreateKind, ModifyKind};
+    use std::{path::PathBuf, thread};
+
+    const TEST_DEBOUNCE: Duration = Duration::from_millis(50);
+
+    fn modified(path: &str) -> notify::Result<Event> {
+        Ok(Event::new(EventKind::Modify(ModifyKind::Any)).add_path(PathBuf::from(path)))
+    }
+
+    /// Runs the watch loop on the events and returns the lines it logged.
+    fn run(
+        events: impl FnOnce(mpsc::Sender<notify::Result<Event>>) + Send + 'static,
+        rebuild: impl FnMut() -> Result<String>,
+    ) -> Vec<String> {
+        let (sender, receiver) = mpsc::channel();
+        let sending = thread::spawn(move || events(sender));
+        let mut log = Vec::new();
+        watch_events(
+            Path::new("notes.txt"),
+            &receiver,
+            TEST_DEBOUNCE,
+            rebuild,
+            &mut log,
+        )
+        .unwrap();
+        sending.join().unwrap();
+        String::from_utf8(log)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn debounce() {
+        let mut rebuilds = 0;
+        let log = run(
+            |sender| {
+                for _ in 0..3 {
+                    sender.send(modified("/src/notes.txt")).unwrap();
+                }
+                thread::sleep(TEST_DEBOUNCE * 10);
+                sender
+                    .send(Ok(Event::new(EventKind::Create(CreateKind::File))
+                        .add_path(PathBuf::from("/src/notes.txt"))))
+                    .unwrap();
+            },
+            || {
+                rebuilds += 1;
+                Ok(format!("rebuild {rebuilds}"))
+            },
+        );
+        assert_eq!(rebuilds, 2);
+        assert_eq!(log.len(), 2);
+        assert!(log[0].ends_with("] rebuild 1"));
+        assert!(log[1].ends_with("] rebuild 2"));
+    }
+
+    #[test]
+    fn ignore_other_files() {
+        let log = run(
+            |sender| {
+                sender.send(modified("/src/notes.zalgo")).unwrap();
+                sender
+                    .send(Ok(
+                        Event::new(EventKind::Any).add_path(PathBuf::from("notes.txt"))
+                    ))
+                    .unwrap();
+            },
+            || Ok("rebuilt".into()),
+        );
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn errors_do_not_stop_watching() {
+        let mut rebuilds = 0;
+        let log = run(
+            |sender| {
+                sender.send(modified("notes.txt")).unwrap();
+                thread::sleep(TEST_DEBOUNCE * 10);
+                sender.send(modified("notes.txt")).unwrap();
+            },
+            || {
+                rebuilds += 1;
+                match rebuilds {
+                    1 => Err(anyhow!("the file could not be encoded")),
+                    _ => Ok("rebuilt".into()),
+                }
+            },
+        );
+        assert_eq!(log.len(), 2);
+        assert!(log[0].ends_with("] Error: the file could not be encoded"));
+        assert!(log[1].ends_with("] rebuilt"));
+    }
+}
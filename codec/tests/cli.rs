@@ -0,0 +1,1119 @@
+use assert_cmd::Command;
+use serde_json::Value;
+use zalgo_codec::{zalgo_encode, zalgo_wrap_python};
+
+fn cli() -> Command {
+    let mut command = Command::cargo_bin("zalgo_codec").unwrap();
+    // The error messages end with a backtrace if these are set, which the tests of the messages do not expect.
+    command
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE");
+    command
+}
+
+#[test]
+fn encode_stdin() {
+    let expected = format!("{}\n", zalgo_encode("Zalgo\n").unwrap());
+    cli()
+        .arg("encode")
+        .write_stdin("Zalgo\n")
+        .assert()
+        .success()
+        .stdout(expected.clone());
+    cli()
+        .args(["encode", "file", "-"])
+        .write_stdin("Zalgo\n")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn encode_stdin_strips_carriage_returns() {
+    cli()
+        .arg("encode")
+        .write_stdin("fn main() {\r\n}\r\n")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", zalgo_encode("fn main() {\n}\n").unwrap()));
+}
+
+#[test]
+fn wrap_stdin() {
+    let code = "print(\"Hello, world!\")\n";
+    cli()
+        .arg("wrap")
+        .write_stdin(code)
+        .assert()
+        .success()
+        .stdout(format!("{}\n", zalgo_wrap_python(code).unwrap()));
+}
+
+#[test]
+fn decode_stdin() {
+    cli()
+        .args(["decode", "file", "-"])
+        .write_stdin(format!("{}\n", zalgo_encode("Zalgo").unwrap()))
+        .assert()
+        .success()
+        .stdout("Zalgo\n");
+}
+
+#[test]
+fn encode_decode_pipe() {
+    let text = "fn add(x: i32, y: i32) -> i32 {\n    x + y\n}";
+    let encoded = cli().arg("encode").write_stdin(text).unwrap();
+    assert!(encoded.status.success());
+    cli()
+        .arg("decode")
+        .write_stdin(encoded.stdout)
+        .assert()
+        .success()
+        .stdout(format!("{text}\n"));
+}
+
+#[test]
+fn decode_file_with_several_clusters() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("encoded.txt");
+    let lines: Vec<String> = ["Zalgo", "fn main() {}", "He comes"]
+        .iter()
+        .map(|text| zalgo_encode(text).unwrap())
+        .collect();
+    std::fs::write(&path, format!("\n{}\n\n", lines.join("\n"))).unwrap();
+
+    cli()
+        .args(["decode", "file"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("Zalgo\nfn main() {}\nHe comes\n");
+    cli()
+        .args(["decode", "--single", "file"])
+        .arg(&path)
+        .assert()
+        .failure();
+}
+
+#[test]
+fn decode_several_arguments() {
+    cli()
+        .args(["decode", "text"])
+        .args([
+            zalgo_encode("Zalgo").unwrap(),
+            zalgo_encode("He comes").unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("Zalgo\nHe comes\n");
+}
+
+#[test]
+fn decode_stray_line() {
+    let encoded = format!("{}\nZalgo\n", zalgo_encode("Zalgo").unwrap());
+    let output = cli().arg("decode").write_stdin(encoded).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 2"));
+}
+
+#[test]
+fn check_clean_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("clean.txt");
+    std::fs::write(&path, "fn main() {}\n").unwrap();
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--check")
+        .assert()
+        .success()
+        .stdout("round trip ok: encoded 13 bytes into 27 bytes\n");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn check_crlf_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crlf.txt");
+    std::fs::write(&path, "fn main() {\r\n}\r\n").unwrap();
+    cli()
+        .args(["--check", "encode", "file"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(
+            "round trip ok: encoded 14 bytes into 29 bytes, removed 2 carriage returns in the input\n",
+        );
+}
+
+#[test]
+fn check_strict_crlf_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crlf.txt");
+    std::fs::write(&path, "Zalgo\r\n").unwrap();
+    let output = cli()
+        .args(["--check", "encode", "file"])
+        .arg(&path)
+        .arg("--strict")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("without --strict"));
+}
+
+#[test]
+fn check_tab_width() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tabs.txt");
+    std::fs::write(&path, "\tZalgo\r\n").unwrap();
+    cli()
+        .args(["--check", "encode", "file"])
+        .arg(&path)
+        .args(["--tab-width", "4"])
+        .assert()
+        .success()
+        .stdout(
+            "round trip ok: encoded 10 bytes into 21 bytes, removed 1 carriage returns and expanded 1 tabs in the input\n",
+        );
+    cli()
+        .args(["--check", "wrap", "file"])
+        .arg(&path)
+        .assert()
+        .code(3);
+}
+
+#[test]
+fn check_corrupted_encoded_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("corrupted.txt");
+    // The character U+0391 is not a combining character, but decodes into '1'.
+    let corrupted = zalgo_encode("Zalgo")
+        .unwrap()
+        .replacen('\u{341}', "\u{391}", 1);
+    std::fs::write(&path, corrupted).unwrap();
+    let output = cli()
+        .args(["decode", "file"])
+        .arg(&path)
+        .arg("--check")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at character 2"));
+}
+
+#[test]
+fn check_conflicts_with_out_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_path = dir.path().join("out.txt");
+    for check_first in [true, false] {
+        let mut command = cli();
+        if check_first {
+            command.arg("--check");
+        }
+        command
+            .arg("-o")
+            .arg(&out_path)
+            .args(["encode", "text", "Zalgo"]);
+        if !check_first {
+            command.arg("--check");
+        }
+        command.assert().code(2);
+    }
+    assert!(!out_path.exists());
+}
+
+#[test]
+fn encode_and_decode_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(src.join("nested")).unwrap();
+    std::fs::write(src.join("zalgo.txt"), "Zalgo").unwrap();
+    std::fs::write(src.join("nested/main.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(src.join("binary.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let encoded = dir.path().join("encoded");
+    cli()
+        .arg("-o")
+        .arg(&encoded)
+        .args(["encode", "dir"])
+        .arg(&src)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(encoded.join("zalgo.txt")).unwrap(),
+        zalgo_encode("Zalgo").unwrap()
+    );
+    assert_eq!(
+        std::fs::read_to_string(encoded.join("nested/main.rs")).unwrap(),
+        zalgo_encode("fn main() {}\n").unwrap()
+    );
+    assert!(!encoded.join("binary.bin").exists());
+
+    let decoded = dir.path().join("decoded");
+    cli()
+        .arg("-o")
+        .arg(&decoded)
+        .args(["decode", "dir"])
+        .arg(&encoded)
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(decoded.join("zalgo.txt")).unwrap(),
+        "Zalgo"
+    );
+    assert_eq!(
+        std::fs::read_to_string(decoded.join("nested/main.rs")).unwrap(),
+        "fn main() {}\n"
+    );
+}
+
+#[test]
+fn encode_dir_with_unencodable_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("price.txt"), "5€").unwrap();
+    std::fs::write(src.join("zalgo.txt"), "Zalgo").unwrap();
+
+    let encoded = dir.path().join("encoded");
+    cli()
+        .arg("-o")
+        .arg(&encoded)
+        .args(["encode", "dir"])
+        .arg(&src)
+        .assert()
+        .failure();
+    // The other files are still encoded.
+    assert!(encoded.join("zalgo.txt").exists());
+    assert!(!encoded.join("price.txt").exists());
+}
+
+#[test]
+fn dir_needs_out_path() {
+    let dir = tempfile::tempdir().unwrap();
+    cli()
+        .args(["encode", "dir"])
+        .arg(dir.path())
+        .assert()
+        .code(2);
+}
+
+/// Runs the command and parses what it printed to stdout as JSON.
+/// Checks that the command only describes an error on stderr if it failed.
+fn json_output(command: &mut Command) -> Value {
+    let output = command.output().unwrap();
+    assert_eq!(output.status.success(), output.stderr.is_empty());
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn json_encode() {
+    let output = json_output(cli().args(["--format", "json", "encode", "text", "Zalgo"]));
+    let encoded = zalgo_encode("Zalgo").unwrap();
+    assert_eq!(output["ok"], true);
+    assert_eq!(output["mode"], "encode");
+    assert_eq!(output["output"], encoded.as_str());
+    assert_eq!(output["bytes_in"], 5);
+    assert_eq!(output["bytes_out"], encoded.len() as u64);
+}
+
+#[test]
+fn json_unencodable_input() {
+    let output = json_output(cli().args(["--format", "json", "encode", "text", "Zalgö"]));
+    assert_eq!(output["ok"], false);
+    assert_eq!(output["error"]["kind"], "NotAscii");
+    assert_eq!(output["error"]["char"], "ö");
+    assert_eq!(output["error"]["line"], 1);
+    assert_eq!(output["error"]["column"], 5);
+    assert_eq!(output["error"]["index"], 4);
+}
+
+#[test]
+fn json_undecodable_input() {
+    let output = json_output(cli().args(["--format", "json", "decode", "text", "Zalgo"]));
+    assert_eq!(output["ok"], false);
+    assert_eq!(output["error"]["kind"], "MissingLeadingE");
+}
+
+#[test]
+fn decode_validates_input() {
+    // `zalgo_decode` turns this into "c" without an error.
+    for args in [&["decode", "text"][..], &["decode", "--single", "text"]] {
+        cli().args(args).arg("aw\u{f6}").assert().code(4).stdout("");
+    }
+}
+
+#[test]
+fn exit_code_success() {
+    cli().args(["encode", "text", "Zalgo"]).assert().code(0);
+}
+
+#[test]
+fn exit_code_usage_error() {
+    // Rejected by the argument parser.
+    cli()
+        .args(["--force", "encode", "text", "Zalgo"])
+        .assert()
+        .code(2);
+    // Rejected after parsing.
+    cli()
+        .args(["decode", "--single", "text", "a", "b"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn exit_code_unencodable_input() {
+    let assert = cli().args(["encode", "text", "Zalgö"]).assert().code(3);
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).into_owned();
+    assert!(stderr.contains("on line 1 at column 5"), "{stderr}");
+}
+
+#[test]
+fn exit_code_undecodable_input() {
+    cli().args(["decode", "text", "Zalgo"]).assert().code(4);
+}
+
+#[test]
+fn exit_code_io_error() {
+    let dir = tempfile::tempdir().unwrap();
+    cli()
+        .args(["encode", "file"])
+        .arg(dir.path().join("missing.txt"))
+        .assert()
+        .code(5);
+}
+
+#[test]
+fn quiet() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("encoded.txt");
+    cli()
+        .arg("-o")
+        .arg(&path)
+        .args(["--quiet", "--format", "json", "encode", "text", "Zalgo"])
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        zalgo_encode("Zalgo").unwrap()
+    );
+    // There is nothing to be quiet about without an output file.
+    cli()
+        .args(["--quiet", "encode", "text", "Zalgo"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn bash_completions() {
+    let output = cli().args(["completions", "bash"]).unwrap();
+    assert!(output.status.success());
+    let script = String::from_utf8(output.stdout).unwrap();
+    for command in ["encode", "decode", "wrap", "unwrap"] {
+        assert!(script.contains(command), "{command} is missing");
+    }
+}
+
+#[test]
+fn completions_for_unknown_shell() {
+    cli().args(["completions", "tcsh"]).assert().code(2);
+}
+
+#[test]
+fn stats_of_encoded_text() {
+    let encoded = zalgo_encode("Zalgo").unwrap();
+    cli()
+        .args(["stats", "text", &encoded])
+        .assert()
+        .success()
+        .stdout(
+            "encoded bytes: 11\ndecoded bytes: 5\ndecoded lines: 1\noverhead ratio: 2.20\ngrapheme clusters: 1\n",
+        );
+
+    let output = json_output(cli().args(["--format", "json", "stats", "text", &encoded]));
+    let stats = &output["stats"];
+    assert_eq!(output["mode"], "stats");
+    assert_eq!(stats["input_encoded"], true);
+    assert_eq!(stats["encoded_bytes"], 11);
+    assert_eq!(stats["decoded_bytes"], 5);
+    assert_eq!(stats["decoded_lines"], 1);
+    assert_eq!(stats["overhead_ratio"], 2.2);
+    assert_eq!(stats["grapheme_clusters"], 1);
+}
+
+#[test]
+fn stats_of_plain_text() {
+    cli()
+        .arg("stats")
+        .write_stdin("Zalgo\nHe comes\n")
+        .assert()
+        .success()
+        .stdout(
+            "the input is not encoded, these are the statistics of its encoding\nencoded bytes: 31\ndecoded bytes: 15\ndecoded lines: 2\noverhead ratio: 2.07\ngrapheme clusters: 1\n",
+        );
+
+    let output = json_output(cli().args(["--format", "json", "stats", "text", "Zalgo"]));
+    let stats = &output["stats"];
+    assert_eq!(stats["input_encoded"], false);
+    assert_eq!(stats["encoded_bytes"], 11);
+    assert_eq!(stats["decoded_bytes"], 5);
+    assert_eq!(stats["decoded_lines"], 1);
+    assert_eq!(stats["grapheme_clusters"], 1);
+    assert_eq!(output["bytes_in"], 5);
+    assert_eq!(output["bytes_out"], 11);
+}
+
+#[test]
+fn stats_of_empty_text() {
+    cli()
+        .args(["stats", "text", ""])
+        .assert()
+        .success()
+        .stdout(
+            "the input is not encoded, these are the statistics of its encoding\nencoded bytes: 1\ndecoded bytes: 0\ndecoded lines: 0\ngrapheme clusters: 1\n",
+        );
+
+    let output = json_output(cli().args(["--format", "json", "stats", "text", ""]));
+    let stats = &output["stats"];
+    assert_eq!(stats["encoded_bytes"], 1);
+    assert_eq!(stats["decoded_bytes"], 0);
+    assert!(stats.get("overhead_ratio").is_none());
+    assert_eq!(output["bytes_in"], 0);
+    assert_eq!(output["bytes_out"], 1);
+}
+
+#[test]
+fn repl_transcript() {
+    let encoded = zalgo_encode("Zalgo").unwrap();
+    cli()
+        .arg("repl")
+        .write_stdin(format!("Zalgo\n:decode\n{encoded}\n:mode\n"))
+        .assert()
+        .success()
+        .stdout(format!("{encoded}\nZalgo\ndecode\n"));
+}
+
+#[test]
+fn repl_continues_after_errors() {
+    let output = cli().arg("repl").write_stdin("Zalgö\nZalgo\n").unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}\n", zalgo_encode("Zalgo").unwrap())
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("on line 1 at column 5"), "{stderr}");
+    assert!(stderr.contains('^'), "{stderr}");
+}
+
+#[test]
+fn repl_multi_line_input() {
+    let expected = format!("{}\n", zalgo_encode("fn main() {\n}").unwrap());
+    cli()
+        .arg("repl")
+        .write_stdin("fn main() {\\\n}\n")
+        .assert()
+        .success()
+        .stdout(expected.clone());
+    cli()
+        .arg("repl")
+        .write_stdin(":paste\nfn main() {\n}\n.\n")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn unwrap_wrapped_file() {
+    let code = "#!/usr/bin/env python3\nprint(\"Hello, world!\")\n";
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("hello.py");
+    let wrapped = dir.path().join("wrapped.py");
+    std::fs::write(&source, code).unwrap();
+    cli()
+        .arg("-o")
+        .arg(&wrapped)
+        .args(["wrap", "file"])
+        .arg(&source)
+        .assert()
+        .success();
+
+    // Text editors tend to add a line ending at the end of the file.
+    let contents = std::fs::read_to_string(&wrapped).unwrap();
+    for contents in [contents.clone(), format!("{contents}\n")] {
+        std::fs::write(&wrapped, contents).unwrap();
+        cli()
+            .args(["unwrap", "file"])
+            .arg(&wrapped)
+            .assert()
+            .success()
+            .stdout(format!("{code}\n"));
+    }
+}
+
+#[test]
+fn unwrap_mangled_wrapper() {
+    let wrapped = zalgo_wrap_python("print(\"Hello, world!\")\n").unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("wrapped.py");
+    std::fs::write(&path, wrapped.replacen("exec", "eval", 1)).unwrap();
+    cli()
+        .args(["unwrap", "file"])
+        .arg(&path)
+        .assert()
+        .code(4)
+        .stdout("");
+}
+
+#[test]
+fn wrap_and_unwrap_text() {
+    let wrapped = zalgo_wrap_python("print(\"hi\")").unwrap();
+    cli()
+        .args(["wrap", "text", "print(\"hi\")"])
+        .assert()
+        .success()
+        .stdout(format!("{wrapped}\n"));
+    cli()
+        .args(["unwrap", "text", &wrapped])
+        .assert()
+        .success()
+        .stdout("print(\"hi\")\n");
+}
+
+#[test]
+fn wrap_unwrap_pipe() {
+    let code = "def add(x, y):\n    return x + y\n";
+    let wrapped = cli().arg("wrap").write_stdin(code).unwrap();
+    assert!(wrapped.status.success());
+    cli()
+        .arg("unwrap")
+        .write_stdin(wrapped.stdout)
+        .assert()
+        .success()
+        .stdout(format!("{code}\n"));
+}
+
+#[test]
+fn in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "Zalgo\nHe comes\n").unwrap();
+
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--in-place")
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        zalgo_encode("Zalgo\nHe comes\n").unwrap()
+    );
+
+    cli()
+        .args(["decode", "file"])
+        .arg(&path)
+        .arg("--in-place")
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Zalgo\nHe comes\n");
+}
+
+#[test]
+fn failed_in_place_leaves_original() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("price.txt");
+    std::fs::write(&path, "5€").unwrap();
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--in-place")
+        .assert()
+        .code(3);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "5€");
+}
+
+#[test]
+fn in_place_with_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "Zalgo").unwrap();
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .args(["--in-place", "--backup"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("notes.txt.bak")).unwrap(),
+        "Zalgo"
+    );
+
+    cli()
+        .args(["decode", "file"])
+        .arg(&path)
+        .args(["--in-place", "--backup=.orig"])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("notes.txt.orig")).unwrap(),
+        zalgo_encode("Zalgo").unwrap()
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Zalgo");
+}
+
+#[test]
+fn in_place_conflicts_with_out_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "Zalgo").unwrap();
+    cli()
+        .arg("-o")
+        .arg(dir.path().join("out.txt"))
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--in-place")
+        .assert()
+        .code(2);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Zalgo");
+}
+
+#[test]
+fn backup_needs_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "Zalgo").unwrap();
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--backup")
+        .assert()
+        .code(2);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn lossy() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("emoji.txt");
+    std::fs::write(&path, "Zalgo \u{1f47b}\n").unwrap();
+
+    cli().args(["encode", "file"]).arg(&path).assert().code(3);
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--lossy")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", zalgo_encode("Zalgo ?\n").unwrap()));
+    cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .args(["--replacement", "_"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", zalgo_encode("Zalgo _\n").unwrap()));
+}
+
+#[test]
+fn lossy_conflicts_with_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("emoji.txt");
+    std::fs::write(&path, "Zalgo \u{1f47b}\n").unwrap();
+    for flags in [&["--lossy"][..], &["--replacement", "_"]] {
+        cli()
+            .args(flags)
+            .args(["encode", "file"])
+            .arg(&path)
+            .arg("--in-place")
+            .assert()
+            .code(2);
+        cli()
+            .arg("--in-place")
+            .args(["encode", "file"])
+            .arg(&path)
+            .args(flags)
+            .assert()
+            .code(2);
+    }
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "Zalgo \u{1f47b}\n");
+}
+
+#[test]
+fn tab_width() {
+    cli()
+        .args(["encode", "--tab-width", "2"])
+        .write_stdin("\tZalgo\n")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", zalgo_encode("  Zalgo\n").unwrap()));
+}
+
+#[test]
+fn strict_rejects_crlf() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("crlf.txt");
+    std::fs::write(&path, "Zalgo\r\n").unwrap();
+
+    let output = cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--strict")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("without --strict"));
+}
+
+#[test]
+fn normalization_flags_need_encode_or_wrap() {
+    cli()
+        .args(["decode", "--lossy"])
+        .write_stdin("")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn watch_needs_file_source_and_out_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_path = dir.path().join("out.txt");
+
+    cli()
+        .arg("--out-path")
+        .arg(&out_path)
+        .args(["encode", "text", "Zalgo", "--watch"])
+        .assert()
+        .code(2);
+    assert!(!out_path.exists());
+}
+
+#[test]
+fn watch_needs_out_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    std::fs::write(&path, "Zalgo").unwrap();
+    let output = cli()
+        .args(["encode", "file"])
+        .arg(&path)
+        .arg("--watch")
+        .timeout(std::time::Duration::from_secs(10))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--out-path"));
+}
+
+#[test]
+fn per_line_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    for text in ["Zalgo\n\nHe comes\n", "Zalgo\n\nHe comes"] {
+        let path = dir.path().join("notes.txt");
+        let encoded_path = dir.path().join("notes.zalgo");
+        let decoded_path = dir.path().join("notes.decoded.txt");
+        std::fs::write(&path, text).unwrap();
+
+        cli()
+            .arg("--out-path")
+            .arg(&encoded_path)
+            .arg("--force")
+            .args(["encode", "--per-line", "file"])
+            .arg(&path)
+            .assert()
+            .success();
+        let encoded = std::fs::read_to_string(&encoded_path).unwrap();
+        let expected: Vec<String> = text
+            .split('\n')
+            .map(|line| zalgo_encode(line).unwrap())
+            .collect();
+        assert_eq!(encoded, expected.join("\n"));
+        assert_eq!(encoded.lines().nth(1), Some("E"));
+
+        cli()
+            .arg("--out-path")
+            .arg(&decoded_path)
+            .arg("--force")
+            .args(["decode", "--per-line", "file"])
+            .arg(&encoded_path)
+            .assert()
+            .success();
+        assert_eq!(std::fs::read_to_string(&decoded_path).unwrap(), text);
+
+        // The line ending that is printed after the output does not change the result.
+        cli()
+            .args(["decode", "--per-line"])
+            .write_stdin(format!("{encoded}\n"))
+            .assert()
+            .success()
+            .stdout(format!("{text}\n"));
+    }
+}
+
+#[test]
+fn per_line_rejects_empty_lines() {
+    let encoded = zalgo_encode("Zalgo").unwrap();
+    cli()
+        .args(["decode", "--per-line"])
+        .write_stdin(format!("{encoded}\n\n{encoded}"))
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn utf16_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("encoded.txt");
+
+    cli()
+        .arg("--out-path")
+        .arg(&path)
+        .args(["--output-encoding", "utf16le", "encode", "text", "Zalgo"])
+        .assert()
+        .success();
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(bytes[..2], [0xFF, 0xFE]);
+    let expected: Vec<u8> = zalgo_encode("Zalgo")
+        .unwrap()
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    assert_eq!(bytes[2..], expected);
+
+    cli()
+        .args(["decode", "file"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("Zalgo\n");
+    cli()
+        .args(["decode", "--single", "file"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout("Zalgo\n");
+}
+
+#[test]
+fn output_encoding_needs_out_path() {
+    cli()
+        .args(["--output-encoding", "utf16be", "encode", "text", "Zalgo"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn table() {
+    let output = cli().arg("table").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let row = |name: &str| {
+        stdout
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some(name))
+            .unwrap()
+            .to_string()
+    };
+    assert!(row("A").contains("U+0041     U+0321"));
+    assert!(row("LF").contains("U+000A     U+036F"));
+    assert_eq!(stdout.lines().count(), 97);
+
+    let output = cli().args(["table", "--format", "csv"]).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout
+        .lines()
+        .any(|line| line.starts_with("A,U+0041,U+0321,")));
+    assert!(stdout.lines().any(|line| line.starts_with("\",\",U+002C,")));
+
+    let json = json_output(cli().args(["table", "--format", "json"]));
+    let rows = json["table"].as_array().unwrap();
+    assert_eq!(rows.len(), 96);
+    assert_eq!(rows[0]["character"], "\n");
+    assert_eq!(rows[0]["combining_code_point"], 0x36F);
+}
+
+#[test]
+fn csv_only_for_table() {
+    cli()
+        .args(["encode", "--format", "csv", "text", "Zalgo"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn caret_annotated_encode_error() {
+    let text = "Zalgo\nHe c\u{f6}mes";
+    let error = zalgo_encode(text).unwrap_err();
+    cli()
+        .args(["--color", "never", "encode"])
+        .write_stdin(text)
+        .assert()
+        .code(3)
+        .stderr(format!(
+            "Error: {error}\n  |\n2 | He c\u{f6}mes\n  |     ^\n"
+        ));
+}
+
+#[test]
+fn caret_annotated_decode_error() {
+    let output = cli()
+        .args([
+            "--color",
+            "never",
+            "decode",
+            "--single",
+            "text",
+            "E\u{33a}\u{e9}",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.ends_with(&format!(
+            "\n  |\n  | 'E' U+033A U+00E9\n  | {}^^^^^^\n",
+            " ".repeat(11)
+        )),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn colored_errors() {
+    let output = cli()
+        .args(["--color", "always", "encode", "text", "Zalg\u{f6}"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("\x1b[1;31mError:\x1b[0m"));
+
+    // `auto` does not color the errors when stderr is not a terminal.
+    let output = cli()
+        .args(["encode", "text", "Zalg\u{f6}"])
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&output.stderr).contains('\x1b'));
+}
+
+#[test]
+fn escape() {
+    let encoded = zalgo_encode("a\nb").unwrap();
+    cli()
+        .args(["encode", "--escape", "text", r"a\nb"])
+        .assert()
+        .success()
+        .stdout(format!("{encoded}\n"));
+    cli()
+        .args(["decode", "text", &encoded])
+        .assert()
+        .success()
+        .stdout("a\nb\n");
+    cli()
+        .args(["decode", "--escape-output", "text", &encoded])
+        .assert()
+        .success()
+        .stdout("a\\nb\n");
+
+    cli()
+        .args(["encode", "--escape", "--tab-width", "2", "text", r"\tZalgo"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", zalgo_encode("  Zalgo").unwrap()));
+    cli()
+        .args(["encode", "--escape", "text", r"Zalgo\q"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn several_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_dir = dir.path().join("encoded");
+    let paths: Vec<_> = ["a.txt", "b.txt", "c.txt"]
+        .into_iter()
+        .map(|name| dir.path().join(name))
+        .collect();
+    std::fs::write(&paths[0], "Zalgo\n").unwrap();
+    std::fs::write(&paths[1], "Zalg\u{f6}\n").unwrap();
+    std::fs::write(&paths[2], "He comes\n").unwrap();
+
+    let output = cli()
+        .arg("--out-path")
+        .arg(&out_dir)
+        .args(["encode", "file"])
+        .args(&paths)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("b.txt"));
+    assert_eq!(
+        std::fs::read_to_string(out_dir.join("a.zalgo")).unwrap(),
+        zalgo_encode("Zalgo\n").unwrap()
+    );
+    assert!(!out_dir.join("b.zalgo").exists());
+    assert_eq!(
+        std::fs::read_to_string(out_dir.join("c.zalgo")).unwrap(),
+        zalgo_encode("He comes\n").unwrap()
+    );
+
+    let decoded_dir = dir.path().join("decoded");
+    cli()
+        .arg("--out-path")
+        .arg(&decoded_dir)
+        .args(["decode", "file"])
+        .arg(out_dir.join("a.zalgo"))
+        .arg(out_dir.join("c.zalgo"))
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(decoded_dir.join("a")).unwrap(),
+        "Zalgo\n"
+    );
+}
+
+#[test]
+fn several_files_need_out_path() {
+    cli()
+        .args(["encode", "file", "a.txt", "b.txt"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn bench() {
+    let json = json_output(cli().args(["--format", "json", "bench", "--size", "100"]));
+    let bench = &json["bench"];
+    assert_eq!(bench["input_bytes"], 100);
+    assert_eq!(bench["encoded_bytes"], 201);
+    assert!(bench["encode_bytes_per_second"].as_f64().unwrap() > 0.0);
+    assert!(bench["decode_bytes_per_second"].as_f64().unwrap() > 0.0);
+    assert!((bench["expansion_ratio"].as_f64().unwrap() - 2.01).abs() < 1e-9);
+
+    cli()
+        .args(["bench", "--size", "100", "text", "Zalgo"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn pipe_without_trailing_newline() {
+    let text = "Zalgo\n\nHe comes\n\n";
+    let encoded = cli()
+        .args(["--no-trailing-newline", "encode"])
+        .write_stdin(text)
+        .output()
+        .unwrap();
+    assert!(encoded.status.success());
+    assert_eq!(encoded.stdout, zalgo_encode(text).unwrap().into_bytes());
+
+    let decoded = cli()
+        .args(["--no-trailing-newline", "decode", "--single"])
+        .write_stdin(encoded.stdout)
+        .output()
+        .unwrap();
+    assert!(decoded.status.success());
+    assert_eq!(decoded.stdout, text.as_bytes());
+}
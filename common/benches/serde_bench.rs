@@ -0,0 +1,37 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use serde::{
+    de::value::{Error, StrDeserializer, StringDeserializer},
+    Deserialize,
+};
+use zalgo_codec_common::ZalgoString;
+
+fn bench_deserialize(c: &mut Criterion) {
+    let string: String = (b' '..=b'~')
+        .chain([b'\n'])
+        .map(char::from)
+        .cycle()
+        .take(1_000_000)
+        .collect();
+    let encoded = ZalgoString::new(&string).unwrap().into_string();
+
+    let mut group = c.benchmark_group("deserialize");
+    // The deserializer only lends out the string, so it must be copied after it has been validated.
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            ZalgoString::deserialize(StrDeserializer::<Error>::new(black_box(&encoded))).unwrap()
+        })
+    });
+    // The deserializer hands over the string, so it is validated and kept without copying it.
+    group.bench_function("owned", |b| {
+        b.iter_batched(
+            || encoded.clone(),
+            |s| ZalgoString::deserialize(StringDeserializer::<Error>::new(black_box(s))).unwrap(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_deserialize);
+criterion_main!(benches);
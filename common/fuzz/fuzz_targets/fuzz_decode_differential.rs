@@ -0,0 +1,48 @@
+#![no_main]
+
+//! Checks that the different ways of decoding a string agree with each other.
+//!
+//! Run with the seeds in `seeds/decode_differential` as an additional corpus:
+//! `cargo fuzz run decode_differential corpus/decode_differential seeds/decode_differential`
+
+use libfuzzer_sys::fuzz_target;
+use zalgo_codec_common::{
+    is_normalization_stable, is_zalgo_encoded, zalgo_decode, zalgo_decode_normalized,
+    zalgo_encode, ZalgoString,
+};
+
+fuzz_target!(|data: &str| {
+    // Text that can be encoded is decoded back into itself by every decoder.
+    if let Ok(encoded) = zalgo_encode(data) {
+        let zs = ZalgoString::new(data).unwrap();
+        assert_eq!(zs.as_str(), encoded);
+        assert_eq!(zalgo_decode(&encoded).unwrap(), data);
+        assert_eq!(zs.decoded_bytes().collect::<Vec<u8>>(), data.as_bytes());
+        assert_eq!(zs.decoded_chars().collect::<String>(), data);
+        assert_eq!(zs.clone().into_decoded_bytes(), data.as_bytes());
+        assert_eq!(zs.into_decoded_string(), data);
+    }
+
+    // `zalgo_decode` does not validate its input, so it can succeed where `ZalgoString::from_encoded` fails.
+    // But every string that passes validation must be decoded in the same way by all decoders.
+    let decoded = zalgo_decode(data);
+    match ZalgoString::from_encoded(data) {
+        Ok(zs) => {
+            assert!(is_zalgo_encoded(data));
+            let decoded = decoded.expect("a validated string must be decodable");
+            assert_eq!(zs.decoded_bytes().collect::<Vec<u8>>(), decoded.as_bytes());
+            assert_eq!(
+                zs.decoded_bytes().rev().collect::<Vec<u8>>(),
+                decoded.bytes().rev().collect::<Vec<u8>>()
+            );
+            assert_eq!(zs.decoded_chars().collect::<String>(), decoded);
+            assert_eq!(zs.decoded_len(), decoded.len());
+            if is_normalization_stable(data) {
+                assert_eq!(zalgo_decode_normalized(data).unwrap(), decoded);
+            }
+            assert_eq!(zs.clone().into_decoded_bytes(), decoded.as_bytes());
+            assert_eq!(zs.into_decoded_string(), decoded);
+        }
+        Err(_) => assert!(!is_zalgo_encoded(data)),
+    }
+});
@@ -0,0 +1,31 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zalgo_codec_common::ZalgoString;
+
+fuzz_target!(|input: (ZalgoString, ZalgoString, usize)| {
+    let (mut zs1, zs2, truncate_to) = input;
+    let decoded1 = zs1.clone().into_decoded_string();
+    let decoded2 = zs2.clone().into_decoded_string();
+
+    assert_eq!(zs1.decoded_len(), decoded1.len());
+    assert_eq!(zs1.decoded_chars().collect::<String>(), decoded1);
+    assert_eq!(
+        zs1.decoded_chars().rev().collect::<String>(),
+        decoded1.chars().rev().collect::<String>()
+    );
+    assert_eq!(
+        zs1.decoded_bytes().collect::<Vec<u8>>(),
+        decoded1.as_bytes()
+    );
+
+    zs1.push_zalgo_str(&zs2);
+    let mut concatenated = format!("{decoded1}{decoded2}");
+    assert_eq!(zs1.clone().into_decoded_string(), concatenated);
+
+    let decoded_len = truncate_to % (concatenated.len() + 1);
+    zs1.truncate(2 * decoded_len + 1);
+    concatenated.truncate(decoded_len);
+    assert_eq!(zs1.decoded_len(), decoded_len);
+    assert_eq!(zs1.into_decoded_string(), concatenated);
+});
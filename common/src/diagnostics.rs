@@ -0,0 +1,190 @@
+//! Contains the implementations of [`miette::Diagnostic`] for the error types of the crate,
+//! as well as the [`EncodeErrorWithSource`] type.
+
+use crate::{DecodeError, EncodeError};
+
+use alloc::{boxed::Box, string::String};
+use core::fmt;
+
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+
+/// Returns a suggestion for how to make the unencodable character in the error go away.
+fn encode_help(error: &EncodeError) -> &'static str {
+    match error.char() {
+        '\r' => "carriage returns can not be encoded, consider converting the line endings to LF",
+        '\t' => "tabs can not be encoded, consider replacing them with spaces",
+        _ => "only printable ASCII characters and newlines can be encoded",
+    }
+}
+
+/// Returns a span that covers the unencodable character in the source string.
+fn encode_label(error: &EncodeError) -> LabeledSpan {
+    LabeledSpan::new_with_span(
+        Some(String::from("can not be encoded")),
+        (error.index(), error.char().len_utf8()),
+    )
+}
+
+/// Labels the unencodable character with a span.
+/// Since the error does not contain the source string, see [`EncodeErrorWithSource`]
+/// for a type that can also display the source.
+impl Diagnostic for EncodeError {
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(encode_help(self)))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(core::iter::once(encode_label(self))))
+    }
+}
+
+/// Labels the offending character with a span if the error was caused by a structural
+/// problem with the input.
+impl Diagnostic for DecodeError {
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new(
+            "only strings that were produced by `zalgo_encode` can be decoded",
+        ))
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let index = self.index()?;
+        let len = self.char().map_or(1, char::len_utf8);
+        Some(Box::new(core::iter::once(LabeledSpan::new_with_span(
+            Some(String::from("here")),
+            (index, len),
+        ))))
+    }
+}
+
+/// An [`EncodeError`] together with the string that caused it.
+///
+/// This type implements [`Diagnostic`] with [`source_code`](Diagnostic::source_code),
+/// so `miette` can display the offending line along with the error.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{zalgo_encode, EncodeErrorWithSource};
+/// let source = "Zålgo";
+/// let err = EncodeErrorWithSource::new(zalgo_encode(source).unwrap_err(), source);
+/// assert_eq!(err.error().index(), 1);
+/// assert_eq!(err.source_str(), source);
+/// ```
+#[derive(Debug)]
+pub struct EncodeErrorWithSource {
+    error: EncodeError,
+    source: String,
+}
+
+impl EncodeErrorWithSource {
+    /// Attaches the string that caused the error to it.
+    #[inline]
+    #[must_use = "this associated method does not modify its inputs and just returns a new value"]
+    pub fn new(error: EncodeError, source: impl Into<String>) -> Self {
+        Self {
+            error,
+            source: source.into(),
+        }
+    }
+
+    /// Returns a reference to the inner [`EncodeError`].
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn error(&self) -> &EncodeError {
+        &self.error
+    }
+
+    /// Returns the string that caused the error.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn source_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the inner [`EncodeError`] and the string that caused it.
+    #[inline]
+    #[must_use = "`self` will be dropped if the result is not used"]
+    pub fn into_parts(self) -> (EncodeError, String) {
+        (self.error, self.source)
+    }
+}
+
+impl fmt::Display for EncodeErrorWithSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl core::error::Error for EncodeErrorWithSource {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl Diagnostic for EncodeErrorWithSource {
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.help()
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.error.labels()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{zalgo_encode, ZalgoString};
+    use alloc::{string::ToString, vec::Vec};
+
+    #[test]
+    fn test_encode_error_with_source() {
+        let source = "Zalgo\nHe comes\u{2764}";
+        let err = EncodeErrorWithSource::new(zalgo_encode(source).unwrap_err(), source);
+
+        let labels: Vec<LabeledSpan> = err.labels().unwrap().collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].offset(), 14);
+        assert_eq!(labels[0].len(), 3);
+        assert_eq!(labels[0].label(), Some("can not be encoded"));
+
+        let span = err
+            .source_code()
+            .unwrap()
+            .read_span(labels[0].inner(), 0, 0)
+            .unwrap();
+        assert_eq!(span.data(), "\u{2764}".as_bytes());
+
+        assert_eq!(
+            err.help().unwrap().to_string(),
+            "only printable ASCII characters and newlines can be encoded"
+        );
+        assert_eq!(err.to_string(), err.error().to_string());
+    }
+
+    #[test]
+    fn test_encode_error_help() {
+        let err = zalgo_encode("CRLF\r\n").unwrap_err();
+        assert!(err.help().unwrap().to_string().contains("line endings"));
+        assert!(err.source_code().is_none());
+        let err = zalgo_encode("\tindented").unwrap_err();
+        assert!(err.help().unwrap().to_string().contains("tabs"));
+    }
+
+    #[test]
+    fn test_decode_error_labels() {
+        let err = ZalgoString::from_encoded("E\u{300}ab").unwrap_err();
+        let labels: Vec<LabeledSpan> = err.labels().unwrap().collect();
+        assert_eq!(labels[0].offset(), 3);
+        assert_eq!(labels[0].len(), 1);
+
+        let err = crate::zalgo_decode("").unwrap_err();
+        assert!(err.labels().is_none());
+        assert!(err.help().is_some());
+    }
+}
@@ -1,11 +1,18 @@
 //! Contains the definition of the error type used by the encoding functions in the crate.
+//!
+//! The errors record where in the code they were created, since [`EncodeError::new`] and [`DecodeError::new`]
+//! are `#[track_caller]`. Closures do not propagate the location of their caller,
+//! so the functions that convert or label these errors use a `match` instead of `map_err`.
+//!
+//! The errors that wrap another error, like [`ArrayEncodeError`] and the errors of the `files` module,
+//! are transparent apart from the path that the file errors add. That is, their source is the source of the wrapped error.
 
-use core::{fmt, str::Utf8Error};
+use core::{fmt, panic::Location, str::Utf8Error};
 
 #[cfg(feature = "std")]
 use std::backtrace::Backtrace;
 
-use alloc::string::FromUtf8Error;
+use alloc::{borrow::Cow, string::FromUtf8Error};
 
 #[derive(Debug)]
 /// The error returned by [`zalgo_encode`](crate::zalgo_encode), [`ZalgoString::new`](crate::ZalgoString::new), and [`zalgo_wrap_python`](crate::zalgo_wrap_python)
@@ -15,6 +22,8 @@ pub struct EncodeError {
     line: usize,
     column: usize,
     index: usize,
+    context: Option<Cow<'static, str>>,
+    location: &'static Location<'static>,
     #[cfg(feature = "std")]
     backtrace: Backtrace,
 }
@@ -28,6 +37,7 @@ impl EncodeError {
     /// and just constructs a new `Error` instance.
     #[inline]
     #[must_use = "this associated method does not modify its inputs and just returns a new value"]
+    #[track_caller]
     pub(crate) fn new(
         unencodable_character: char,
         line: usize,
@@ -39,11 +49,53 @@ impl EncodeError {
             line,
             column,
             index,
+            context: None,
+            location: Location::caller(),
             #[cfg(feature = "std")]
             backtrace: Backtrace::capture(),
         }
     }
 
+    /// Moves the position of the error forward by the given number of lines and bytes,
+    /// for when the string that was encoded came after that many whole lines of a larger string.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub(crate) fn offset(mut self, lines: usize, bytes: usize) -> Self {
+        self.line += lines;
+        self.index += bytes;
+        self
+    }
+
+    /// Attaches a label to the error that describes what was being encoded when it happened,
+    /// e.g. the name of a field or a file. The label is prepended to the message when the error is displayed.
+    ///
+    /// If the error already has a label it is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::zalgo_encode;
+    /// let err = zalgo_encode("Zålgo").unwrap_err().with_context("username");
+    /// assert_eq!(err.context(), Some("username"));
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "username: can not encode 'å' character at string index 1, on line 1 at column 2",
+    /// );
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn with_context(mut self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.context = Some(label.into());
+        self
+    }
+
+    /// Returns the label that was attached to the error with [`with_context`](EncodeError::with_context), if any.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
     /// Returns the 1-indexed line number of the line on which the unencodable byte occured.
     ///
     /// # Examples
@@ -118,6 +170,130 @@ impl EncodeError {
         self.index
     }
 
+    /// Returns the kind of problem that caused the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{zalgo_encode, EncodeErrorKind};
+    /// assert_eq!(zalgo_encode("Zålgo").map_err(|e| e.kind()), Err(EncodeErrorKind::NotAscii));
+    /// assert_eq!(zalgo_encode("CRLF\r\n").map_err(|e| e.kind()), Err(EncodeErrorKind::UnencodableAscii));
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn kind(&self) -> EncodeErrorKind {
+        if self.unencodable_character.is_ascii() {
+            EncodeErrorKind::UnencodableAscii
+        } else {
+            EncodeErrorKind::NotAscii
+        }
+    }
+
+    /// Returns the location in the source code where the function of this crate that created the error was called.
+    ///
+    /// Unlike the backtrace this is available without the `std` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::zalgo_encode;
+    /// let err = zalgo_encode("Zålgo").unwrap_err();
+    /// assert_eq!(err.location().line(), line!() - 1);
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns a value that displays the error message followed by the line of `source`
+    /// that contains the unencodable character, with a caret pointing at the character.
+    ///
+    /// `source` should be the string that failed to encode. If it isn't,
+    /// only the error message is displayed.
+    /// Lines that are too long to display are truncated around the unencodable character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::zalgo_encode;
+    /// let source = "fn main() {\n    let x = \"\u{e9}\";\n}";
+    /// let err = zalgo_encode(source).unwrap_err();
+    /// assert_eq!(
+    ///     err.display_with_context(source).to_string(),
+    ///     "can not encode '\u{e9}' character at string index 25, on line 2 at column 14
+    ///   |
+    /// 2 |     let x = \"\u{e9}\";
+    ///   |              ^",
+    /// );
+    /// ```
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn display_with_context<'a>(&'a self, source: &'a str) -> impl fmt::Display + 'a {
+        DisplayWithContext {
+            error: self,
+            source,
+        }
+    }
+
+    /// Returns the grapheme cluster in `source` that contains the unencodable character.
+    ///
+    /// A single character that you see in a text editor can consist of several unicode characters,
+    /// and only one of them may be the cause of the error. This method finds the full grapheme cluster
+    /// that the unencodable character is a part of.
+    ///
+    /// `source` should be the string that failed to encode. If it isn't, this returns [`None`].
+    ///
+    /// # Examples
+    ///
+    /// The grapheme cluster `á` can consist of a normal `a` and a combining acute accent, `U+301`.
+    /// The error is caused by the accent, but this method returns the full grapheme cluster:
+    /// ```
+    /// # use zalgo_codec_common::zalgo_encode;
+    /// let source = "Zalgo: a\u{301}";
+    /// let err = zalgo_encode(source).unwrap_err();
+    /// assert_eq!(err.char(), '\u{301}');
+    /// assert_eq!(err.grapheme(source), Some("a\u{301}"));
+    /// ```
+    #[cfg(feature = "segmentation")]
+    #[must_use = "the method returns a new value and does not modify `self` or the input"]
+    pub fn grapheme<'a>(&self, source: &'a str) -> Option<&'a str> {
+        self.grapheme_range(source).map(|range| &source[range])
+    }
+
+    /// Returns the byte range in `source` of the grapheme cluster that contains the unencodable character.
+    ///
+    /// See [`grapheme`](EncodeError::grapheme) for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::zalgo_encode;
+    /// let source = "I \u{2764}\u{fe0f} u";
+    /// let err = zalgo_encode(source).unwrap_err();
+    /// assert_eq!(err.grapheme_range(source), Some(2..8));
+    /// ```
+    #[cfg(feature = "segmentation")]
+    #[must_use = "the method returns a new value and does not modify `self` or the input"]
+    pub fn grapheme_range(&self, source: &str) -> Option<core::ops::Range<usize>> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        if !source
+            .get(self.index..)
+            .is_some_and(|rest| rest.starts_with(self.char()))
+        {
+            return None;
+        }
+
+        // Every character on the line before the unencodable one is encodable, and therefore a single byte.
+        // The start of a line is always the start of a grapheme cluster, so we can start segmenting from there.
+        let line_start = self.index.checked_sub(self.column.saturating_sub(1))?;
+        source
+            .get(line_start..)?
+            .grapheme_indices(true)
+            .map(|(start, grapheme)| line_start + start..line_start + start + grapheme.len())
+            .find(|range| range.contains(&self.index))
+    }
+
     #[cfg(feature = "std")]
     /// Returns a reference to a [`Backtrace`] that was captured when the error was created.
     ///
@@ -131,6 +307,9 @@ impl EncodeError {
 
 impl fmt::Display for EncodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(context) = self.context() {
+            write!(f, "{context}: ")?;
+        }
         write!(
             f,
             "can not encode {:?} character at string index {}, on line {} at column {}",
@@ -144,30 +323,195 @@ impl fmt::Display for EncodeError {
 
 impl core::error::Error for EncodeError {}
 
-/// The error returned by [`zalgo_decode`](super::zalgo_decode) if a string can not be decoded.
+/// Writes the kind of the error, the unencodable character, its position, and the label of the error if it has one.
+/// The backtrace is not written.
+#[cfg(feature = "defmt")]
+impl defmt::Format for EncodeError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "EncodeError {{ kind: {}, char: {=char}, line: {=usize}, column: {=usize}, index: {=usize}, context: {} }}",
+            self.kind(),
+            self.char(),
+            self.line(),
+            self.column(),
+            self.index(),
+            self.context(),
+        );
+    }
+}
+
+/// Converts the error into an [`std::io::Error`] of the kind [`InvalidData`](std::io::ErrorKind::InvalidData)
+/// that wraps it.
+#[cfg(feature = "std")]
+impl From<EncodeError> for std::io::Error {
+    fn from(e: EncodeError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Displays an [`EncodeError`] together with a snippet of the source string that caused it.
+/// Created by [`EncodeError::display_with_context`].
+struct DisplayWithContext<'a> {
+    error: &'a EncodeError,
+    source: &'a str,
+}
+
+impl DisplayWithContext<'_> {
+    /// The maximum number of characters that are shown on either side of the caret.
+    const CONTEXT: usize = 40;
+}
+
+impl fmt::Display for DisplayWithContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        let index = self.error.index();
+        // Every character on the line before the unencodable one is encodable,
+        // and therefore a single byte. This lets us find the start of the line from the column.
+        let Some(before) = index
+            .checked_sub(self.error.column().saturating_sub(1))
+            .and_then(|start| self.source.get(start..index))
+        else {
+            return Ok(());
+        };
+        let Some(after) = self
+            .source
+            .get(index..)
+            .and_then(|rest| rest.split('\n').next())
+        else {
+            return Ok(());
+        };
+        if !before.is_ascii() || before.contains('\n') || !after.starts_with(self.error.char()) {
+            // The source is not the string that caused the error.
+            return Ok(());
+        }
+
+        let (prefix, before) = if before.len() > Self::CONTEXT {
+            ("...", &before[before.len() - Self::CONTEXT..])
+        } else {
+            ("", before)
+        };
+
+        let line = self.error.line();
+        let width = line.ilog10() as usize + 1;
+        write!(f, "\n{:width$} |\n{line} | {prefix}{before}", "")?;
+        for c in after.chars().take(Self::CONTEXT) {
+            if c.is_control() {
+                write!(f, "{}", c.escape_debug())?;
+            } else {
+                write!(f, "{c}")?;
+            }
+        }
+        if after.chars().nth(Self::CONTEXT).is_some() {
+            write!(f, "...")?;
+        }
+        write!(
+            f,
+            "\n{:width$} | {:caret$}^",
+            "",
+            "",
+            caret = prefix.len() + before.len()
+        )
+    }
+}
+
+/// Serializes the error as a struct with the fields `char`, `line`, `column`, and `index`,
+/// e.g. `{"char": "é", "line": 1, "column": 4, "index": 3}` in JSON.
+/// If the error has a label attached with [`EncodeError::with_context`] it is serialized
+/// in an additional `context` field.
+///
+/// The backtrace is not serialized.
+#[cfg(feature = "serde")]
+impl serde::Serialize for EncodeError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state =
+            serializer.serialize_struct("EncodeError", 4 + usize::from(self.context.is_some()))?;
+        state.serialize_field("char", &self.char())?;
+        state.serialize_field("line", &self.line())?;
+        state.serialize_field("column", &self.column())?;
+        state.serialize_field("index", &self.index())?;
+        match self.context() {
+            Some(context) => state.serialize_field("context", context)?,
+            None => state.skip_field("context")?,
+        }
+        state.end()
+    }
+}
+
+/// The error returned by [`zalgo_decode`](super::zalgo_decode) and [`ZalgoString::from_encoded`](crate::ZalgoString::from_encoded)
+/// if a string can not be decoded.
 #[derive(Debug)]
 pub struct DecodeError {
-    kind: DecodeErrorKind,
+    cause: DecodeErrorCause,
+    location: &'static Location<'static>,
     #[cfg(feature = "std")]
     backtrace: Backtrace,
 }
 
 impl DecodeError {
-    pub(crate) fn new(possible_error: Option<FromUtf8Error>) -> Self {
+    #[inline]
+    #[must_use = "this associated method does not modify its inputs and just returns a new value"]
+    #[track_caller]
+    pub(crate) fn new(cause: DecodeErrorCause) -> Self {
         Self {
             #[cfg(feature = "std")]
             backtrace: Backtrace::capture(),
-            kind: match possible_error {
-                Some(e) => DecodeErrorKind::InvalidUtf8(e),
-                None => DecodeErrorKind::EmptyInput,
-            },
+            location: Location::caller(),
+            cause,
+        }
+    }
+
+    /// Returns the kind of problem that caused the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{zalgo_decode, DecodeErrorKind};
+    /// assert_eq!(zalgo_decode("").map_err(|e| e.kind()), Err(DecodeErrorKind::EmptyInput));
+    /// assert_eq!(zalgo_decode("E\u{360}").map_err(|e| e.kind()), Err(DecodeErrorKind::InvalidUtf8));
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn kind(&self) -> DecodeErrorKind {
+        match self.cause {
+            DecodeErrorCause::EmptyInput => DecodeErrorKind::EmptyInput,
+            DecodeErrorCause::InvalidUtf8(_) => DecodeErrorKind::InvalidUtf8,
+            DecodeErrorCause::MissingLeadingE { .. } => DecodeErrorKind::MissingLeadingE,
+            DecodeErrorCause::DanglingByte { .. } => DecodeErrorKind::DanglingByte,
+            DecodeErrorCause::NotCombiningChar { .. } => DecodeErrorKind::NotCombiningChar,
+            DecodeErrorCause::NotPrintableAscii { .. } => DecodeErrorKind::NotPrintableAscii,
+            DecodeErrorCause::NotWrappedPython => DecodeErrorKind::NotWrappedPython,
         }
     }
 
     /// Returns whether the error happened because the given string was empty,
-    /// and not because the decoding resulted in invalid UTF-8.
+    /// and not because of some problem with its contents.
+    #[deprecated(
+        since = "0.14.0",
+        note = "compare the result of `kind` with `DecodeErrorKind::EmptyInput` instead"
+    )]
     pub fn cause_was_empty_string(&self) -> bool {
-        matches!(self.kind, DecodeErrorKind::EmptyInput)
+        self.kind() == DecodeErrorKind::EmptyInput
+    }
+
+    /// Returns the location in the source code where the function of this crate that created the error was called.
+    ///
+    /// Unlike the backtrace this is available without the `std` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::zalgo_decode;
+    /// let err = zalgo_decode("").unwrap_err();
+    /// assert_eq!(err.location().line(), line!() - 1);
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn location(&self) -> &'static Location<'static> {
+        self.location
     }
 
     #[cfg(feature = "std")]
@@ -182,59 +526,339 @@ impl DecodeError {
     /// If the error happened because the decoding resulted in invalid UTF-8,
     /// this function returns the [`Utf8Error`] that was created in the process.
     pub fn to_utf8_error(&self) -> Option<Utf8Error> {
-        match &self.kind {
-            DecodeErrorKind::InvalidUtf8(e) => Some(e.utf8_error()),
-            DecodeErrorKind::EmptyInput => None,
+        match &self.cause {
+            DecodeErrorCause::InvalidUtf8(e) => Some(e.utf8_error()),
+            _ => None,
         }
     }
 
     /// If the error happened because the decoding resulted in invalid UTF-8,
     /// this function converts this error into the [`FromUtf8Error`] that was created in the process.
     pub fn into_from_utf8_error(self) -> Option<FromUtf8Error> {
-        match self.kind {
-            DecodeErrorKind::InvalidUtf8(e) => Some(e),
-            DecodeErrorKind::EmptyInput => None,
+        match self.cause {
+            DecodeErrorCause::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// If the error was caused by a structural problem with the input,
+    /// this function returns the byte index in the input where the problem was found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::ZalgoString;
+    /// assert_eq!(ZalgoString::from_encoded("E\u{33a}Z").map_err(|e| e.index()), Err(Some(3)));
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn index(&self) -> Option<usize> {
+        match self.cause {
+            DecodeErrorCause::MissingLeadingE { .. } => Some(0),
+            DecodeErrorCause::DanglingByte { index }
+            | DecodeErrorCause::NotCombiningChar { index, .. }
+            | DecodeErrorCause::NotPrintableAscii { index, .. } => Some(index),
+            DecodeErrorCause::EmptyInput
+            | DecodeErrorCause::InvalidUtf8(_)
+            | DecodeErrorCause::NotWrappedPython => None,
+        }
+    }
+
+    /// If the error was caused by a specific character in the input,
+    /// this function returns that character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::ZalgoString;
+    /// assert_eq!(ZalgoString::from_encoded("Zalgo").map_err(|e| e.char()), Err(Some('Z')));
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn char(&self) -> Option<char> {
+        match self.cause {
+            DecodeErrorCause::MissingLeadingE { found } => Some(found),
+            DecodeErrorCause::NotCombiningChar { character, .. }
+            | DecodeErrorCause::NotPrintableAscii { character, .. } => Some(character),
+            DecodeErrorCause::EmptyInput
+            | DecodeErrorCause::InvalidUtf8(_)
+            | DecodeErrorCause::DanglingByte { .. }
+            | DecodeErrorCause::NotWrappedPython => None,
         }
     }
 }
 
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "could not decode the string because {}", self.kind)
+        write!(f, "could not decode the string because {}", self.cause)
+    }
+}
+
+/// Writes the kind of the error, and the offending character and its index if the error was caused by one.
+/// The backtrace is not written.
+#[cfg(feature = "defmt")]
+impl defmt::Format for DecodeError {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "DecodeError {{ kind: {}, char: {}, index: {} }}",
+            self.kind(),
+            self.char(),
+            self.index(),
+        );
+    }
+}
+
+/// Converts the error into an [`std::io::Error`] of the kind [`InvalidData`](std::io::ErrorKind::InvalidData)
+/// that wraps it.
+#[cfg(feature = "std")]
+impl From<DecodeError> for std::io::Error {
+    fn from(e: DecodeError) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, e)
     }
 }
 
 impl core::error::Error for DecodeError {
     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
-        match self.kind {
-            DecodeErrorKind::InvalidUtf8(ref e) => Some(e),
-            DecodeErrorKind::EmptyInput => None,
+        match self.cause {
+            DecodeErrorCause::InvalidUtf8(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes the error as a struct with a `kind` field that names the cause of the error,
+/// followed by the fields that are relevant for that kind:
+///
+/// | `kind` | other fields |
+/// |---|---|
+/// | `"EmptyInput"` | |
+/// | `"InvalidUtf8"` | `valid_up_to`, `error_len` |
+/// | `"MissingLeadingE"` | `char`, `index` |
+/// | `"DanglingByte"` | `index` |
+/// | `"NotCombiningChar"` | `char`, `index` |
+/// | `"NotPrintableAscii"` | `char`, `index` |
+/// | `"NotWrappedPython"` | |
+///
+/// E.g. `{"kind": "InvalidUtf8", "valid_up_to": 0, "error_len": 1}` in JSON.
+/// The `error_len` field is serialized as a none value if the input ended unexpectedly,
+/// see [`Utf8Error::error_len`] for more information.
+///
+/// The backtrace is not serialized.
+#[cfg(feature = "serde")]
+impl serde::Serialize for DecodeError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        match &self.cause {
+            DecodeErrorCause::EmptyInput => {
+                let mut state = serializer.serialize_struct("DecodeError", 1)?;
+                state.serialize_field("kind", "EmptyInput")?;
+                state.end()
+            }
+            DecodeErrorCause::NotWrappedPython => {
+                let mut state = serializer.serialize_struct("DecodeError", 1)?;
+                state.serialize_field("kind", "NotWrappedPython")?;
+                state.end()
+            }
+            DecodeErrorCause::InvalidUtf8(e) => {
+                let mut state = serializer.serialize_struct("DecodeError", 3)?;
+                state.serialize_field("kind", "InvalidUtf8")?;
+                state.serialize_field("valid_up_to", &e.utf8_error().valid_up_to())?;
+                state.serialize_field("error_len", &e.utf8_error().error_len())?;
+                state.end()
+            }
+            DecodeErrorCause::DanglingByte { index } => {
+                let mut state = serializer.serialize_struct("DecodeError", 2)?;
+                state.serialize_field("kind", "DanglingByte")?;
+                state.serialize_field("index", index)?;
+                state.end()
+            }
+            DecodeErrorCause::MissingLeadingE { found } => {
+                serialize_char_error(serializer, "MissingLeadingE", *found, 0)
+            }
+            DecodeErrorCause::NotCombiningChar { index, character } => {
+                serialize_char_error(serializer, "NotCombiningChar", *character, *index)
+            }
+            DecodeErrorCause::NotPrintableAscii { index, character } => {
+                serialize_char_error(serializer, "NotPrintableAscii", *character, *index)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn serialize_char_error<S: serde::Serializer>(
+    serializer: S,
+    kind: &'static str,
+    character: char,
+    index: usize,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+
+    let mut state = serializer.serialize_struct("DecodeError", 3)?;
+    state.serialize_field("kind", kind)?;
+    state.serialize_field("char", &character)?;
+    state.serialize_field("index", &index)?;
+    state.end()
+}
+
+/// The kind of problem that caused an [`EncodeError`].
+///
+/// Returned by [`EncodeError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum EncodeErrorKind {
+    /// The input contained a character that is not ASCII.
+    NotAscii,
+    /// The input contained an ASCII character that is neither printable nor a newline,
+    /// e.g. a tab or a carriage return.
+    UnencodableAscii,
+}
+
+/// The kind of problem that caused a [`DecodeError`].
+///
+/// Returned by [`DecodeError::kind`]. The details of the problem, like the
+/// offending character, are available through the methods on [`DecodeError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum DecodeErrorKind {
+    /// The given string was empty.
+    EmptyInput,
+    /// Decoding the string resulted in invalid UTF-8.
+    InvalidUtf8,
+    /// The string did not begin with an "E".
+    MissingLeadingE,
+    /// The string has an even length in bytes, so the final byte is half of a byte pair.
+    DanglingByte,
+    /// The string contains a character that is not in the range U+0300–U+036F.
+    NotCombiningChar,
+    /// The string contains a combining character that does not decode
+    /// into a printable ASCII character or a newline.
+    NotPrintableAscii,
+    /// The string is not Python code that was wrapped by [`zalgo_wrap_python`](crate::zalgo_wrap_python).
+    NotWrappedPython,
+}
+
+/// The error returned by [`ZalgoArrayString::new`](crate::ZalgoArrayString::new) and the methods
+/// that push onto a [`ZalgoArrayString`](crate::ZalgoArrayString) if they can not encode their input,
+/// or if the result would not fit in the capacity of the string.
+#[derive(Debug)]
+pub enum ArrayEncodeError {
+    /// The input contains a character that can not be encoded.
+    Unencodable {
+        /// The underlying error.
+        error: EncodeError,
+    },
+    /// The encoded string would be longer than the capacity of the `ZalgoArrayString`.
+    CapacityExceeded {
+        /// The capacity in bytes that would be needed to hold the encoded string.
+        required: usize,
+        /// The capacity in bytes of the `ZalgoArrayString`.
+        capacity: usize,
+    },
+}
+
+impl From<EncodeError> for ArrayEncodeError {
+    #[inline]
+    fn from(error: EncodeError) -> Self {
+        Self::Unencodable { error }
+    }
+}
+
+impl fmt::Display for ArrayEncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unencodable { error } => write!(f, "{error}"),
+            Self::CapacityExceeded { required, capacity } => write!(
+                f,
+                "the encoded string needs a capacity of {required} bytes, but the capacity is {capacity} bytes"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ArrayEncodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Unencodable { error } => error.source(),
+            Self::CapacityExceeded { .. } => None,
         }
     }
 }
 
-/// The kind of error the caused the decoding failure.
+/// The cause of a decoding failure, along with the details of the problem.
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum DecodeErrorKind {
+pub(crate) enum DecodeErrorCause {
     /// The given string was empty.
     EmptyInput,
     /// Decoding the string resulted in invalid UTF-8.
     InvalidUtf8(FromUtf8Error),
+    /// The string did not begin with an "E".
+    MissingLeadingE { found: char },
+    /// The string has an even length in bytes, so the final byte is half of a byte pair.
+    DanglingByte { index: usize },
+    /// The string contains a character that is not in the range U+0300–U+036F.
+    NotCombiningChar { index: usize, character: char },
+    /// The string contains a combining character that does not decode
+    /// into a printable ASCII character or a newline.
+    NotPrintableAscii { index: usize, character: char },
+    /// The string is not Python code that was wrapped by [`zalgo_wrap_python`](crate::zalgo_wrap_python).
+    NotWrappedPython,
 }
 
-impl fmt::Display for DecodeErrorKind {
+impl fmt::Display for DecodeErrorCause {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::EmptyInput => write!(f, "the string was empty"),
             Self::InvalidUtf8(e) => write!(f, "decoding resulted in invalid utf8: {e}"),
+            Self::MissingLeadingE { found } => {
+                write!(f, "it begins with {found:?} instead of 'E'")
+            }
+            Self::DanglingByte { index } => write!(
+                f,
+                "it has an even length, which leaves half of a byte pair dangling at index {index}"
+            ),
+            Self::NotCombiningChar { index, character } => write!(
+                f,
+                "the character {character:?} at index {index} is not a combining character in the range U+0300–U+036F"
+            ),
+            Self::NotPrintableAscii { index, character } => write!(
+                f,
+                "the character {character:?} at index {index} does not decode into a printable ASCII character or newline"
+            ),
+            Self::NotWrappedPython => write!(
+                f,
+                "it does not contain a line that was produced by `zalgo_wrap_python`"
+            ),
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{DecodeError, EncodeError};
-    use alloc::{string::String, vec};
+    use super::{DecodeError, DecodeErrorCause, DecodeErrorKind, EncodeError, EncodeErrorKind};
+    use crate::ZalgoString;
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+    };
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn test_defmt_format() {
+        // Formatting with defmt needs a global logger, which only exists on the target device,
+        // so this only checks that the types can be formatted.
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<EncodeError>();
+        assert_format::<DecodeError>();
+        assert_format::<EncodeErrorKind>();
+        assert_format::<DecodeErrorKind>();
+        assert_format::<ZalgoString>();
+    }
 
     #[test]
     fn test_error() {
@@ -245,19 +869,262 @@ mod test {
         assert_eq!(err.index(), 6);
     }
 
+    #[test]
+    fn test_context() {
+        let err = crate::zalgo_encode("Z\u{e5}lgo").unwrap_err();
+        assert_eq!(err.context(), None);
+        let message = err.to_string();
+
+        let first = err.with_context("first name");
+        assert_eq!(first.context(), Some("first name"));
+        assert_eq!(first.to_string(), format!("first name: {message}"));
+
+        let last = crate::zalgo_encode_named(String::from("last name"), "Z\u{e5}lgo").unwrap_err();
+        assert_eq!(last.context(), Some("last name"));
+        assert_eq!((first.char(), first.index()), (last.char(), last.index()));
+        assert_ne!(first.to_string(), last.to_string());
+
+        let err = first.with_context("nickname");
+        assert_eq!(err.to_string(), format!("nickname: {message}"));
+        assert!(err
+            .display_with_context("Z\u{e5}lgo")
+            .to_string()
+            .starts_with("nickname: can not encode"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_into_io_error() {
+        let err = crate::zalgo_encode("Z\u{e5}lgo")
+            .unwrap_err()
+            .with_context("username");
+        let message = err.to_string();
+        let io_err = std::io::Error::from(err);
+        assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(io_err.to_string(), message);
+        assert_eq!(
+            io_err
+                .into_inner()
+                .unwrap()
+                .downcast::<EncodeError>()
+                .unwrap()
+                .context(),
+            Some("username")
+        );
+
+        let io_err = std::io::Error::from(crate::zalgo_decode("").unwrap_err());
+        assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
     #[test]
     fn test_decode_error() {
-        let err = DecodeError::new(None);
+        let err = DecodeError::new(DecodeErrorCause::EmptyInput);
         assert_eq!(err.to_utf8_error(), None);
-        assert!(err.cause_was_empty_string());
+        assert_eq!(err.kind(), DecodeErrorKind::EmptyInput);
         assert_eq!(err.into_from_utf8_error(), None);
-        let err = DecodeError::new(String::from_utf8(vec![255, 255, 255, 255, 255, 255]).err());
+        let err = DecodeError::new(DecodeErrorCause::InvalidUtf8(
+            String::from_utf8(vec![255, 255, 255, 255, 255, 255]).unwrap_err(),
+        ));
         assert_eq!(err.to_utf8_error().unwrap().error_len(), Some(1));
         assert_eq!(err.to_utf8_error().unwrap().valid_up_to(), 0);
-        assert!(!err.cause_was_empty_string());
+        assert_ne!(err.kind(), DecodeErrorKind::EmptyInput);
         assert_eq!(
             err.into_from_utf8_error().unwrap().into_bytes(),
             vec![255; 6]
         );
     }
+
+    #[test]
+    fn test_structural_decode_errors() {
+        let err = ZalgoString::from_encoded("").unwrap_err();
+        assert_eq!(err.kind(), DecodeErrorKind::EmptyInput);
+        assert_eq!(err.index(), None);
+        assert_eq!(err.char(), None);
+
+        let err = ZalgoString::from_encoded("A\u{300}").unwrap_err();
+        assert_eq!(err.index(), Some(0));
+        assert_eq!(err.char(), Some('A'));
+        assert!(err.to_string().contains("begins with 'A' instead of 'E'"));
+
+        let err = ZalgoString::from_encoded("E\u{300}a").unwrap_err();
+        assert_eq!(err.index(), Some(3));
+        assert_eq!(err.char(), None);
+        assert!(err.to_string().contains("even length"));
+
+        let err = ZalgoString::from_encoded("E\u{300}ab").unwrap_err();
+        assert_eq!(err.index(), Some(3));
+        assert_eq!(err.char(), Some('a'));
+        assert!(err.to_string().contains("not a combining character"));
+
+        let err = ZalgoString::from_encoded("E\u{300}\u{360}").unwrap_err();
+        assert_eq!(err.index(), Some(3));
+        assert_eq!(err.char(), Some('\u{360}'));
+        assert!(err.to_string().contains("printable ASCII"));
+
+        for err in [
+            ZalgoString::from_encoded("A\u{300}").unwrap_err(),
+            ZalgoString::from_encoded("E\u{300}a").unwrap_err(),
+            ZalgoString::from_encoded("E\u{300}ab").unwrap_err(),
+            ZalgoString::from_encoded("E\u{300}\u{360}").unwrap_err(),
+        ] {
+            assert_ne!(err.kind(), DecodeErrorKind::EmptyInput);
+            assert_eq!(err.to_utf8_error(), None);
+        }
+    }
+
+    #[test]
+    fn test_encode_error_kinds() {
+        for (source, kind) in [
+            ("Z\u{e5}lgo", EncodeErrorKind::NotAscii),
+            ("I \u{2764}\u{fe0f} u", EncodeErrorKind::NotAscii),
+            ("a\u{301}", EncodeErrorKind::NotAscii),
+            ("CRLF\r\n", EncodeErrorKind::UnencodableAscii),
+            ("\tindented", EncodeErrorKind::UnencodableAscii),
+            ("\0", EncodeErrorKind::UnencodableAscii),
+        ] {
+            assert_eq!(crate::zalgo_encode(source).unwrap_err().kind(), kind);
+            assert_eq!(ZalgoString::new(source).unwrap_err().kind(), kind);
+            assert_eq!(crate::zalgo_wrap_python(source).unwrap_err().kind(), kind);
+        }
+    }
+
+    #[test]
+    fn test_decode_error_kinds() {
+        assert_eq!(
+            crate::zalgo_decode("").unwrap_err().kind(),
+            DecodeErrorKind::EmptyInput
+        );
+        assert_eq!(
+            crate::zalgo_decode("E\u{360}").unwrap_err().kind(),
+            DecodeErrorKind::InvalidUtf8
+        );
+        for (source, kind) in [
+            ("", DecodeErrorKind::EmptyInput),
+            ("Zalgo", DecodeErrorKind::MissingLeadingE),
+            ("E\u{300}a", DecodeErrorKind::DanglingByte),
+            ("E\u{300}ab", DecodeErrorKind::NotCombiningChar),
+            ("E\u{300}\u{360}", DecodeErrorKind::NotPrintableAscii),
+        ] {
+            assert_eq!(ZalgoString::from_encoded(source).unwrap_err().kind(), kind);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_encode_error() {
+        let err = crate::zalgo_encode("abc\u{e9}").unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            "{\"char\":\"\u{e9}\",\"line\":1,\"column\":4,\"index\":3}"
+        );
+        let err = err.with_context("name");
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            "{\"char\":\"\u{e9}\",\"line\":1,\"column\":4,\"index\":3,\"context\":\"name\"}"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_decode_error() {
+        let err = crate::zalgo_decode("").unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"kind":"EmptyInput"}"#
+        );
+
+        let err = crate::zalgo_decode("E\u{360}").unwrap_err();
+        assert!(err.to_utf8_error().is_some());
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"kind":"InvalidUtf8","valid_up_to":0,"error_len":1}"#
+        );
+
+        let err = ZalgoString::from_encoded("Zalgo").unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"kind":"MissingLeadingE","char":"Z","index":0}"#
+        );
+
+        let err = ZalgoString::from_encoded("E\u{300}a").unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"kind":"DanglingByte","index":3}"#
+        );
+
+        let err = ZalgoString::from_encoded("E\u{300}ab").unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"kind":"NotCombiningChar","char":"a","index":3}"#
+        );
+    }
+
+    #[test]
+    fn test_display_with_context() {
+        let source = "a\nbc\td";
+        let err = crate::zalgo_encode(source).unwrap_err();
+        assert_eq!(
+            err.display_with_context(source).to_string(),
+            "can not encode '\\t' character at string index 4, on line 2 at column 3\n  |\n2 | bc\\td\n  |   ^"
+        );
+
+        // If the source is not the one that caused the error only the message is shown.
+        assert_eq!(
+            err.display_with_context("Zalgo").to_string(),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_display_with_context_long_line() {
+        let source = format!("{}\u{e9}{}", "a".repeat(100), "b".repeat(100));
+        let err = crate::zalgo_encode(&source).unwrap_err();
+        assert_eq!(
+            err.display_with_context(&source).to_string(),
+            format!(
+                "{err}\n  |\n1 | ...{}\u{e9}{}...\n  | {}^",
+                "a".repeat(40),
+                "b".repeat(39),
+                " ".repeat(43),
+            )
+        );
+
+        let source = format!("{}\u{e9}", "a".repeat(1000));
+        let err = crate::zalgo_encode(&source).unwrap_err();
+        assert!(err
+            .display_with_context(&source)
+            .to_string()
+            .ends_with(&format!(
+                "\n1 | ...{}\u{e9}\n  | {}^",
+                "a".repeat(40),
+                " ".repeat(43)
+            )));
+    }
+
+    #[cfg(feature = "segmentation")]
+    #[test]
+    fn test_grapheme() {
+        let source = "Zalgo: a\u{301}";
+        let err = crate::zalgo_encode(source).unwrap_err();
+        assert_eq!(err.char(), '\u{301}');
+        assert_eq!(err.grapheme(source), Some("a\u{301}"));
+        assert_eq!(err.grapheme_range(source), Some(7..10));
+
+        let source = "I\n\u{2764}\u{fe0f}";
+        let err = crate::zalgo_encode(source).unwrap_err();
+        assert_eq!(err.char(), '\u{2764}');
+        assert_eq!(err.grapheme(source), Some("\u{2764}\u{fe0f}"));
+
+        let source = "Z\u{e5}lgo";
+        let err = crate::zalgo_encode(source).unwrap_err();
+        assert_eq!(err.grapheme(source), Some("\u{e5}"));
+        assert_eq!(err.grapheme_range(source), Some(1..3));
+
+        // Carriage return and line feed form a single grapheme cluster.
+        let source = "CRLF\r\n";
+        let err = crate::zalgo_encode(source).unwrap_err();
+        assert_eq!(err.grapheme(source), Some("\r\n"));
+
+        assert_eq!(err.grapheme("Zalgo"), None);
+    }
 }
@@ -0,0 +1,577 @@
+//! Contains asynchronous versions of the functions in the [`files`](super) module that are built on [`tokio`].
+//!
+//! They share the chunked encoder and decoder with the blocking functions, so they produce the same output
+//! and return the same errors, but read and write through [`tokio::fs`] and the
+//! [`AsyncRead`] and [`AsyncWrite`] traits instead of blocking the thread they run on.
+//! The output files are written atomically in the same way as by the blocking functions.
+//!
+//! # Example
+//!
+//! ```
+//! # use zalgo_codec_common::files::async_io::{decode_file, encode_file};
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = tempfile::tempdir()?;
+//! # let original = dir.path().join("original.txt");
+//! # let encoded = dir.path().join("encoded.txt");
+//! # let decoded = dir.path().join("decoded.txt");
+//! tokio::fs::write(&original, "He comes").await?;
+//! encode_file(&original, &encoded).await?;
+//! decode_file(&encoded, &decoded).await?;
+//! assert_eq!(tokio::fs::read_to_string(&decoded).await?, "He comes");
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{
+    atomic::{already_exists, temp_path},
+    chunked::{ChunkError, Decoding, Encoding},
+    python, FileError, FileOptions, FileStats, UndecodableFileError, UnencodableFileError,
+    CHUNK_SIZE,
+};
+
+use alloc::vec::Vec;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+};
+
+/// Encodes the contents of the given file and stores the result in another file,
+/// in the same way as [`files::encode_file`](super::encode_file).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::encode_file`](super::encode_file).
+pub async fn encode_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<FileStats, UnencodableFileError> {
+    encode_file_with_options(in_file, out_file, &FileOptions::new()).await
+}
+
+/// Encodes the contents of the given file and stores the result in another file,
+/// in the same way as [`files::encode_file_with_options`](super::encode_file_with_options).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::encode_file_with_options`](super::encode_file_with_options).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "encode_file",
+        level = "debug",
+        skip_all,
+        fields(in_file = %in_file.as_ref().display(), out_file = %out_file.as_ref().display()),
+    )
+)]
+pub async fn encode_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<FileStats, UnencodableFileError> {
+    let (in_file, out_file) = (in_file.as_ref(), out_file.as_ref());
+    let in_error = |e: io::Error| UnencodableFileError::from(e).with_path(in_file);
+    let out_error = |e: io::Error| UnencodableFileError::from(e).with_path(out_file);
+
+    let reader = File::open(in_file).await.map_err(in_error)?;
+    let mut writer = AtomicFile::create(out_file, options)
+        .await
+        .map_err(out_error)?;
+    if options.preserve_metadata {
+        let metadata = reader.metadata().await.map_err(in_error)?;
+        writer.copy_metadata(&metadata).await.map_err(out_error)?;
+    }
+    let stats = encode(reader, writer.file(), options, Some((in_file, out_file))).await?;
+    writer.persist().await.map_err(out_error)?;
+    #[cfg(feature = "tracing")]
+    stats.trace();
+    Ok(stats)
+}
+
+/// Decodes the contents of a file that has been encoded with [`encode_file`] and stores the result in another file,
+/// in the same way as [`files::decode_file`](super::decode_file).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::decode_file`](super::decode_file).
+pub async fn decode_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<FileStats, UndecodableFileError> {
+    decode_file_with_options(in_file, out_file, &FileOptions::new()).await
+}
+
+/// Decodes the contents of a file that has been encoded with [`encode_file`] and stores the result in another file,
+/// in the same way as [`files::decode_file_with_options`](super::decode_file_with_options).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::decode_file_with_options`](super::decode_file_with_options).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "decode_file",
+        level = "debug",
+        skip_all,
+        fields(in_file = %in_file.as_ref().display(), out_file = %out_file.as_ref().display()),
+    )
+)]
+pub async fn decode_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<FileStats, UndecodableFileError> {
+    let (in_file, out_file) = (in_file.as_ref(), out_file.as_ref());
+    let in_error = |e: io::Error| UndecodableFileError::from(e).with_path(in_file);
+    let out_error = |e: io::Error| UndecodableFileError::from(e).with_path(out_file);
+
+    let reader = File::open(in_file).await.map_err(in_error)?;
+    let mut writer = AtomicFile::create(out_file, options)
+        .await
+        .map_err(out_error)?;
+    if options.preserve_metadata {
+        let metadata = reader.metadata().await.map_err(in_error)?;
+        writer.copy_metadata(&metadata).await.map_err(out_error)?;
+    }
+    let stats = decode(reader, writer.file(), Some((in_file, out_file))).await?;
+    writer.persist().await.map_err(out_error)?;
+    #[cfg(feature = "tracing")]
+    stats.trace();
+    Ok(stats)
+}
+
+/// Encodes the contents of the given Python source file and stores the result in another file,
+/// in the same way as [`files::wrap_python_file`](super::wrap_python_file).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::wrap_python_file`](super::wrap_python_file).
+pub async fn wrap_python_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<FileStats, UnencodableFileError> {
+    wrap_python_file_with_options(in_file, out_file, &FileOptions::new()).await
+}
+
+/// Encodes the contents of the given Python source file and stores the result in another file,
+/// in the same way as [`files::wrap_python_file_with_options`](super::wrap_python_file_with_options).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::wrap_python_file_with_options`](super::wrap_python_file_with_options).
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "wrap_python_file",
+        level = "debug",
+        skip_all,
+        fields(in_file = %in_file.as_ref().display(), out_file = %out_file.as_ref().display()),
+    )
+)]
+pub async fn wrap_python_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<FileStats, UnencodableFileError> {
+    let (in_file, out_file) = (in_file.as_ref(), out_file.as_ref());
+    let in_error = |e: io::Error| UnencodableFileError::from(e).with_path(in_file);
+    let out_error = |e: io::Error| UnencodableFileError::from(e).with_path(out_file);
+
+    let mut file = File::open(in_file).await.map_err(in_error)?;
+    let metadata = file.metadata().await.map_err(in_error)?;
+    let mut head = Vec::new();
+    (&mut file)
+        .take(python::PREAMBLE_LIMIT)
+        .read_to_end(&mut head)
+        .await
+        .map_err(in_error)?;
+    let (preamble, rest) = python::split_preamble(&head);
+
+    let mut encoded = Vec::new();
+    let result = encode(
+        rest.chain(file),
+        &mut encoded,
+        options,
+        Some((in_file, out_file)),
+    )
+    .await;
+    let mut stats = python::include_preamble(result, preamble)?;
+
+    let mut writer = AtomicFile::create(out_file, options)
+        .await
+        .map_err(out_error)?;
+    // A Python script always keeps its permissions, so that an executable script stays executable.
+    if options.preserve_metadata {
+        writer.copy_metadata(&metadata).await.map_err(out_error)?;
+    } else {
+        #[cfg(unix)]
+        writer
+            .set_permissions(metadata.permissions())
+            .await
+            .map_err(out_error)?;
+    }
+    let parts = python::wrapped_parts(preamble, &encoded);
+    for part in parts {
+        writer.file().write_all(part).await.map_err(out_error)?;
+    }
+    writer.persist().await.map_err(out_error)?;
+    stats.bytes_out = parts.iter().map(|part| part.len() as u64).sum();
+    #[cfg(feature = "tracing")]
+    stats.trace();
+    Ok(stats)
+}
+
+/// Encodes everything that can be read from `reader` and writes the result to `writer`,
+/// in the same way as [`files::encode_stream`](super::encode_stream).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::encode_stream`](super::encode_stream).
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub async fn encode_stream<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    writer: W,
+    options: &FileOptions,
+) -> Result<FileStats, FileError> {
+    let stats = encode(reader, writer, options, None).await?;
+    #[cfg(feature = "tracing")]
+    stats.trace();
+    Ok(stats)
+}
+
+/// Decodes everything that can be read from `reader` and writes the result to `writer`,
+/// in the same way as [`files::decode_stream`](super::decode_stream).
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`files::decode_stream`](super::decode_stream).
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub async fn decode_stream<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    reader: R,
+    writer: W,
+    _options: &FileOptions,
+) -> Result<FileStats, FileError> {
+    let stats = decode(reader, writer, None).await?;
+    #[cfg(feature = "tracing")]
+    stats.trace();
+    Ok(stats)
+}
+
+/// The asynchronous counterpart of [`encode_chunked`](super::chunked::encode_chunked).
+///
+/// If `paths` contains the paths to the input and output files, errors from reading and writing
+/// are attributed to the correct one of them.
+async fn encode<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    mut reader: R,
+    mut writer: W,
+    options: &FileOptions,
+    paths: Option<(&Path, &Path)>,
+) -> Result<FileStats, UnencodableFileError> {
+    let in_error = |e: UnencodableFileError| match paths {
+        Some((in_file, _)) => e.with_path(in_file),
+        None => e,
+    };
+    let out_error = |e: io::Error| match paths {
+        Some((_, out_file)) => UnencodableFileError::from(e).with_path(out_file),
+        None => e.into(),
+    };
+
+    let mut encoding = Encoding::new(CHUNK_SIZE, options);
+    writer
+        .write_all(Encoding::PREFIX)
+        .await
+        .map_err(out_error)?;
+
+    loop {
+        let read = match reader.read(encoding.unfilled()).await {
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(in_error(e.into())),
+        };
+        match encoding.encode(read) {
+            Ok(encoded) => writer.write_all(encoded).await.map_err(out_error)?,
+            Err(ChunkError::Failed(e)) => return Err(in_error(e)),
+            Err(ChunkError::Char(mut partial)) => {
+                let result = reader.read_exact(partial.missing()).await.map(|_| ());
+                let character = partial.complete(result).map_err(|e| in_error(e.into()))?;
+                return Err(in_error(encoding.char_error(character)));
+            }
+        }
+
+        if read == 0 {
+            break;
+        }
+    }
+
+    writer.flush().await.map_err(out_error)?;
+    Ok(encoding.finish())
+}
+
+/// The asynchronous counterpart of [`decode_chunked`](super::chunked::decode_chunked).
+///
+/// If `paths` contains the paths to the input and output files, errors from reading and writing
+/// are attributed to the correct one of them.
+async fn decode<R: AsyncRead + Unpin, W: AsyncWrite + Unpin>(
+    mut reader: R,
+    mut writer: W,
+    paths: Option<(&Path, &Path)>,
+) -> Result<FileStats, UndecodableFileError> {
+    let in_error = |e: UndecodableFileError| match paths {
+        Some((in_file, _)) => e.with_path(in_file),
+        None => e,
+    };
+    let out_error = |e: io::Error| match paths {
+        Some((_, out_file)) => UndecodableFileError::from(e).with_path(out_file),
+        None => e.into(),
+    };
+
+    let mut decoding = Decoding::new(CHUNK_SIZE);
+
+    loop {
+        let read = match reader.read(decoding.unfilled()).await {
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(in_error(e.into())),
+        };
+        match decoding.decode(read) {
+            Ok(decoded) => writer.write_all(decoded).await.map_err(out_error)?,
+            Err(ChunkError::Failed(e)) => return Err(in_error(e)),
+            Err(ChunkError::Char(mut partial)) => {
+                let result = reader.read_exact(partial.missing()).await.map(|_| ());
+                let character = partial.complete(result).map_err(|e| in_error(e.into()))?;
+                return Err(in_error(decoding.char_error(character)));
+            }
+        }
+
+        if read == 0 {
+            break;
+        }
+    }
+
+    writer.flush().await.map_err(out_error)?;
+    Ok(decoding.finish())
+}
+
+/// The asynchronous counterpart of [`AtomicFile`](super::atomic::AtomicFile).
+struct AtomicFile {
+    /// Only `None` after the file has been closed during `persist` or `drop`.
+    file: Option<File>,
+    temp_path: PathBuf,
+    destination: PathBuf,
+    overwrite: bool,
+    /// The modification time that the file gets when it is persisted.
+    modified: Option<SystemTime>,
+}
+
+impl AtomicFile {
+    async fn create(destination: &Path, options: &FileOptions) -> io::Result<Self> {
+        if !options.overwrite && tokio::fs::try_exists(destination).await? {
+            return Err(already_exists(destination));
+        }
+
+        loop {
+            let temp_path = temp_path(destination)?;
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&temp_path)
+                .await
+            {
+                Ok(file) => {
+                    return Ok(Self {
+                        file: Some(file),
+                        temp_path,
+                        destination: destination.to_path_buf(),
+                        overwrite: options.overwrite,
+                        modified: None,
+                    })
+                }
+                // A file with that name was left behind by an earlier process with the same id, try the next name.
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn persist(mut self) -> io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush().await?;
+            // `tokio` can not set the times of a file, so it is done on the standard library file, which is quick.
+            if let Some(modified) = self.modified {
+                let std_file = file.into_std().await;
+                std_file.set_modified(modified)?;
+                file = File::from_std(std_file);
+            }
+            file.sync_all().await?;
+        }
+
+        if self.overwrite {
+            tokio::fs::rename(&self.temp_path, &self.destination).await
+        } else {
+            match tokio::fs::hard_link(&self.temp_path, &self.destination).await {
+                Ok(()) => tokio::fs::remove_file(&self.temp_path).await,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    Err(already_exists(&self.destination))
+                }
+                Err(e) => Err(e),
+            }
+        }?;
+
+        // The temporary file is gone, so there is nothing left for `drop` to clean up.
+        self.temp_path = PathBuf::new();
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    async fn set_permissions(&mut self, permissions: fs::Permissions) -> io::Result<()> {
+        self.file().set_permissions(permissions).await
+    }
+
+    async fn copy_metadata(&mut self, source: &fs::Metadata) -> io::Result<()> {
+        #[cfg(unix)]
+        self.set_permissions(source.permissions()).await?;
+        self.modified = Some(source.modified()?);
+        Ok(())
+    }
+
+    fn file(&mut self) -> &mut File {
+        self.file
+            .as_mut()
+            .expect("the file is only closed when the `AtomicFile` is persisted or dropped")
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // The file must be closed before it can be removed on some platforms.
+        drop(self.file.take());
+        if !self.temp_path.as_os_str().is_empty() {
+            // `drop` can not be asynchronous, and removing a file is quick.
+            // There is nothing sensible to do if this fails.
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{files, files::test::text, zalgo_encode, DecodeErrorKind};
+    use alloc::{format, string::String};
+
+    #[tokio::test]
+    async fn test_matches_blocking_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original.py");
+        let contents = format!(
+            "#!/usr/bin/env python3\n{}\r\n\tprint('Zalgo')\n",
+            text(3 * CHUNK_SIZE)
+        );
+        fs::write(&original, &contents).unwrap();
+
+        for (name, blocking, asynchronous) in [
+            (
+                "encoded",
+                files::encode_file(&original, dir.path().join("encoded_blocking")),
+                encode_file(&original, dir.path().join("encoded_async")).await,
+            ),
+            (
+                "wrapped",
+                files::wrap_python_file(&original, dir.path().join("wrapped_blocking")),
+                wrap_python_file(&original, dir.path().join("wrapped_async")).await,
+            ),
+        ] {
+            assert_eq!(blocking.unwrap(), asynchronous.unwrap());
+            assert_eq!(
+                fs::read(dir.path().join(format!("{name}_blocking"))).unwrap(),
+                fs::read(dir.path().join(format!("{name}_async"))).unwrap(),
+            );
+        }
+
+        let encoded = dir.path().join("encoded_async");
+        let decoded = dir.path().join("decoded");
+        let stats = decode_file(&encoded, &decoded).await.unwrap();
+        assert_eq!(
+            stats,
+            files::decode_file(&encoded, dir.path().join("decoded_blocking")).unwrap()
+        );
+        assert_eq!(
+            fs::read_to_string(&decoded).unwrap(),
+            contents.replace("\r\n", "\n").replace('\t', "    ")
+        );
+
+        // Nothing is left behind in the directory by the temporary files.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_file = dir.path().join("in.txt");
+        let out_file = dir.path().join("out.txt");
+        fs::write(&in_file, format!("{}\u{e5}", text(CHUNK_SIZE))).unwrap();
+        fs::write(&out_file, "existing").unwrap();
+
+        let Err(UnencodableFileError::UnencodableContent { error: e, path }) =
+            encode_file(&in_file, &out_file).await
+        else {
+            panic!("'\u{e5}' should not be encodable");
+        };
+        assert_eq!((e.char(), e.index()), ('\u{e5}', CHUNK_SIZE));
+        assert_eq!(path.as_deref(), Some(in_file.as_path()));
+
+        let Err(UndecodableFileError::NotEncoded { error: e, .. }) =
+            decode_file(&in_file, &out_file).await
+        else {
+            panic!("the file is not encoded");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::MissingLeadingE);
+
+        fs::write(&in_file, "Zalgo").unwrap();
+        let options = FileOptions::new().overwrite(false);
+        let e = encode_file_with_options(&in_file, &out_file, &options)
+            .await
+            .unwrap_err();
+        assert_eq!(e.path(), Some(out_file.as_path()));
+        assert!(matches!(
+            encode_file(dir.path().join("missing"), &out_file).await,
+            Err(UnencodableFileError::Io { .. })
+        ));
+        assert_eq!(fs::read_to_string(&out_file).unwrap(), "existing");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_streams() {
+        let contents = "fn main() {\r\n    println!(\"Zalgo\");\r\n}\r\n";
+        let mut encoded = Vec::new();
+        let stats = encode_stream(contents.as_bytes(), &mut encoded, &FileOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(encoded.clone()).unwrap(),
+            zalgo_encode(&contents.replace("\r\n", "\n")).unwrap()
+        );
+        assert_eq!(stats.lines, 3);
+
+        let mut decoded = Vec::new();
+        decode_stream(encoded.as_slice(), &mut decoded, &FileOptions::new())
+            .await
+            .unwrap();
+        assert_eq!(decoded, contents.replace("\r\n", "\n").as_bytes());
+
+        let Err(FileError::UnencodableContent {
+            error: e,
+            path: None,
+        }) = encode_stream("Z\u{e5}lgo".as_bytes(), Vec::new(), &FileOptions::new()).await
+        else {
+            panic!("'\u{e5}' should not be encodable");
+        };
+        assert_eq!((e.char(), e.index()), ('\u{e5}', 1));
+    }
+}
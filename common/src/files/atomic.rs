@@ -0,0 +1,177 @@
+//! Contains [`AtomicFile`], which makes sure that the output files of the functions in the [`files`](super) module
+//! are either written completely or not at all.
+
+use super::FileOptions;
+
+use alloc::format;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::SystemTime,
+};
+
+/// Used to give the temporary files that are created by the same process different names.
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// An output file that is written to a temporary file in the same directory as its destination,
+/// and only moved to the destination when [`persist`](AtomicFile::persist) is called.
+///
+/// The temporary file is removed if the `AtomicFile` is dropped without being persisted,
+/// so an existing file at the destination is never clobbered by a failed operation.
+#[derive(Debug)]
+pub(super) struct AtomicFile {
+    /// Only `None` after the file has been closed during `persist` or `drop`.
+    file: Option<File>,
+    temp_path: PathBuf,
+    destination: PathBuf,
+    overwrite: bool,
+    /// Whether a write to the file has failed.
+    failed: bool,
+    /// The modification time that the file gets when it is persisted.
+    modified: Option<SystemTime>,
+}
+
+impl AtomicFile {
+    /// Creates a temporary file next to `destination`.
+    ///
+    /// Returns an error of the kind [`AlreadyExists`](io::ErrorKind::AlreadyExists) right away
+    /// if the destination exists and the options do not allow overwriting it.
+    pub(super) fn create(destination: &Path, options: &FileOptions) -> io::Result<Self> {
+        if !options.overwrite && destination.try_exists()? {
+            return Err(already_exists(destination));
+        }
+
+        loop {
+            let temp_path = temp_path(destination)?;
+            match File::create_new(&temp_path) {
+                Ok(file) => {
+                    return Ok(Self {
+                        file: Some(file),
+                        temp_path,
+                        destination: destination.to_path_buf(),
+                        overwrite: options.overwrite,
+                        failed: false,
+                        modified: None,
+                    })
+                }
+                // A file with that name was left behind by an earlier process with the same id, try the next name.
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Moves the temporary file to the destination.
+    pub(super) fn persist(mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            // Setting the time must be done after the last write, which would change it again.
+            if let Some(modified) = self.modified {
+                file.set_modified(modified)?;
+            }
+            file.sync_all()?;
+        }
+
+        if self.overwrite {
+            fs::rename(&self.temp_path, &self.destination)
+        } else {
+            // Unlike a rename, creating a hard link fails if the destination exists,
+            // so a file that was created after the check in `create` is not clobbered either.
+            match fs::hard_link(&self.temp_path, &self.destination) {
+                Ok(()) => fs::remove_file(&self.temp_path),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    Err(already_exists(&self.destination))
+                }
+                Err(e) => Err(e),
+            }
+        }?;
+
+        // The temporary file is gone, so there is nothing left for `drop` to clean up.
+        self.temp_path = PathBuf::new();
+        Ok(())
+    }
+
+    /// Returns whether a write to the file has failed,
+    /// which tells errors from writing the output apart from errors from reading the input.
+    pub(super) fn failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Sets the permissions of the file that is moved to the destination.
+    #[cfg(unix)]
+    pub(super) fn set_permissions(&self, permissions: fs::Permissions) -> io::Result<()> {
+        self.file
+            .as_ref()
+            .expect("the file is only closed when the `AtomicFile` is persisted or dropped")
+            .set_permissions(permissions)
+    }
+
+    /// Gives the file the permissions of the source file on Unix,
+    /// and the modification time of the source file when it is persisted.
+    pub(super) fn copy_metadata(&mut self, source: &fs::Metadata) -> io::Result<()> {
+        #[cfg(unix)]
+        self.set_permissions(source.permissions())?;
+        self.modified = Some(source.modified()?);
+        Ok(())
+    }
+
+    fn file(&mut self) -> &mut File {
+        self.file
+            .as_mut()
+            .expect("the file is only closed when the `AtomicFile` is persisted or dropped")
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let result = self.file().write(buf);
+        self.failed |= result.is_err();
+        result
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let result = self.file().flush();
+        self.failed |= result.is_err();
+        result
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // The file must be closed before it can be removed on some platforms.
+        drop(self.file.take());
+        if !self.temp_path.as_os_str().is_empty() {
+            // There is nothing sensible to do if this fails.
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Returns a path for a temporary file next to `destination` that has not been returned before by this process.
+pub(super) fn temp_path(destination: &Path) -> io::Result<PathBuf> {
+    let Some(file_name) = destination.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("`{}` is not a path to a file", destination.display()),
+        ));
+    };
+    let directory = destination.parent().unwrap_or(Path::new(""));
+
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(format!(
+        ".{}-{}.tmp",
+        process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    Ok(directory.join(temp_name))
+}
+
+pub(super) fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("`{}` already exists", path.display()),
+    )
+}
@@ -0,0 +1,230 @@
+//! Contains [`encode_files`] and [`decode_files`], which process a list of files into one output directory.
+
+use super::{
+    decode_file_with_options, encode_file_with_options, FileError, FileOptions, FileStats,
+};
+
+use alloc::{format, vec::Vec};
+use std::{
+    ffi::{OsStr, OsString},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The path to every input file together with the result of processing it.
+type BatchResults = Vec<(PathBuf, Result<FileStats, FileError>)>;
+
+/// Encodes each of the given files with [`encode_file_with_options`] and stores the results in the output directory.
+///
+/// The name of each output file is the name of its input file followed by the suffix in the options,
+/// which is `.zalgo` by default, see [`FileOptions::suffix`].
+/// The output directory is created if it does not exist.
+///
+/// A file that can not be encoded does not stop the process. Instead the returned list contains
+/// the path to every input file together with the result of encoding it, in the order they were given.
+///
+/// # Errors
+///
+/// Returns an error if the output directory can not be created.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{encode_files, FileOptions};
+/// # let dir = tempfile::tempdir()?;
+/// # let out_dir = dir.path().join("encoded");
+/// let script = dir.path().join("build.txt");
+/// std::fs::write(&script, "He comes")?;
+/// let results = encode_files([&script], &out_dir, &FileOptions::new())?;
+/// assert!(results.iter().all(|(_, result)| result.is_ok()));
+/// assert!(out_dir.join("build.txt.zalgo").is_file());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(out_dir = %out_dir.as_ref().display()))
+)]
+pub fn encode_files<I, P, Q>(
+    inputs: I,
+    out_dir: Q,
+    options: &FileOptions,
+) -> Result<BatchResults, io::Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    fn encode(
+        in_file: &Path,
+        out_file: &Path,
+        options: &FileOptions,
+    ) -> Result<FileStats, FileError> {
+        Ok(encode_file_with_options(in_file, out_file, options)?)
+    }
+
+    fn encoded_name(in_file: &Path, suffix: &str) -> io::Result<OsString> {
+        let mut name = file_name(in_file)?.to_os_string();
+        name.push(suffix);
+        Ok(name)
+    }
+
+    process_files(inputs, out_dir.as_ref(), options, encoded_name, encode)
+}
+
+/// Decodes each of the given files with [`decode_file_with_options`] and stores the results in the output directory.
+///
+/// This is the inverse of [`encode_files`]: the name of each output file is the name of its input file
+/// without the suffix in the options. An input file whose name does not end with the suffix is recorded as a failure.
+///
+/// # Errors
+///
+/// Returns an error if the output directory can not be created.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(out_dir = %out_dir.as_ref().display()))
+)]
+pub fn decode_files<I, P, Q>(
+    inputs: I,
+    out_dir: Q,
+    options: &FileOptions,
+) -> Result<BatchResults, io::Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    fn decode(
+        in_file: &Path,
+        out_file: &Path,
+        options: &FileOptions,
+    ) -> Result<FileStats, FileError> {
+        Ok(decode_file_with_options(in_file, out_file, options)?)
+    }
+
+    fn decoded_name(in_file: &Path, suffix: &str) -> io::Result<OsString> {
+        file_name(in_file)?
+            .to_str()
+            .and_then(|name| name.strip_suffix(suffix))
+            .filter(|name| !name.is_empty())
+            .map(OsString::from)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("the file name does not end with `{suffix}`"),
+                )
+            })
+    }
+
+    process_files(inputs, out_dir.as_ref(), options, decoded_name, decode)
+}
+
+/// Calls `process` for every input file with the path in `out_dir` whose file name is given by `out_name`.
+fn process_files<I, P>(
+    inputs: I,
+    out_dir: &Path,
+    options: &FileOptions,
+    out_name: fn(&Path, &str) -> io::Result<OsString>,
+    process: fn(&Path, &Path, &FileOptions) -> Result<FileStats, FileError>,
+) -> Result<BatchResults, io::Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    fs::create_dir_all(out_dir)?;
+
+    Ok(inputs
+        .into_iter()
+        .map(|in_file| {
+            let in_file = in_file.as_ref();
+            let result = match out_name(in_file, &options.suffix) {
+                Ok(name) => process(in_file, &out_dir.join(name), options),
+                Err(e) => Err(FileError::from(e).with_path(in_file)),
+            };
+            (in_file.to_path_buf(), result)
+        })
+        .collect())
+}
+
+/// Returns the last component of the path, or an error if it does not name a file.
+fn file_name(path: &Path) -> io::Result<&OsStr> {
+    path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the path does not name a file"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{files::test::text, EncodeErrorKind};
+
+    #[test]
+    fn test_batch_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let encoded = dir.path().join("encoded");
+        let decoded = dir.path().join("decoded");
+        let inputs = [
+            dir.path().join("first.txt"),
+            dir.path().join("second.rs"),
+            dir.path().join("unencodable.txt"),
+        ];
+        fs::write(&inputs[0], "Zalgo\n").unwrap();
+        fs::write(&inputs[1], text(1000)).unwrap();
+        fs::write(&inputs[2], b"Zalgo\x07").unwrap();
+
+        let results = encode_files(&inputs, &encoded, &FileOptions::new()).unwrap();
+        assert_eq!(results.len(), 3);
+        for ((path, result), input) in results.iter().zip(&inputs) {
+            assert_eq!(path, input);
+            assert_eq!(result.is_ok(), input != &inputs[2]);
+        }
+        let (_, Err(FileError::UnencodableContent { error: e, path })) = &results[2] else {
+            panic!("the file with the unencodable byte should fail");
+        };
+        assert_eq!(e.kind(), EncodeErrorKind::UnencodableAscii);
+        assert_eq!(path.as_deref(), Some(inputs[2].as_path()));
+
+        let mut names: Vec<_> = fs::read_dir(&encoded)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["first.txt.zalgo", "second.rs.zalgo"]);
+
+        let results = decode_files(
+            [
+                encoded.join("first.txt.zalgo"),
+                encoded.join("second.rs.zalgo"),
+            ],
+            &decoded,
+            &FileOptions::new(),
+        )
+        .unwrap();
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        for input in &inputs[..2] {
+            assert_eq!(
+                fs::read(decoded.join(input.file_name().unwrap())).unwrap(),
+                fs::read(input).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_file = dir.path().join("in.txt");
+        fs::write(&in_file, "Zalgo").unwrap();
+        let options = FileOptions::new().suffix(".z");
+
+        let results = encode_files([&in_file], dir.path(), &options).unwrap();
+        assert!(results[0].1.is_ok());
+        assert!(dir.path().join("in.txt.z").is_file());
+
+        let results =
+            decode_files([&in_file, &dir.path().join("..")], dir.path(), &options).unwrap();
+        for (_, result) in results {
+            let Err(FileError::Io { error: e, .. }) = result else {
+                panic!("the output file can not be named");
+            };
+            assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+}
@@ -0,0 +1,906 @@
+//! Contains the chunked encoder and decoder that the functions in the [`files`](super) module are built on.
+//!
+//! The chunks are processed by [`Encoding`] and [`Decoding`], which do not do any I/O themselves,
+//! so that the blocking functions in this module and the asynchronous ones can share them.
+
+use super::{
+    progress::ProgressReporter, CarriageReturns, FileOptions, FileStats, Normalization,
+    TabHandling, UndecodableFileError, UnencodableFileError,
+};
+use crate::{decode_byte_pair, encode_byte, DecodeError, DecodeErrorCause, EncodeError};
+
+use alloc::{vec, vec::Vec};
+use core::str;
+use std::io::{self, Read, Write};
+
+/// Encodes everything that can be read from `reader` and writes the result to `writer`,
+/// reading at most `chunk_size` bytes at a time.
+///
+/// Tabs and carriage returns are handled as determined by the `options`,
+/// and the changes that were made to the input are included in the returned statistics.
+/// The progress is reported to the `reporter` after every chunk.
+#[track_caller]
+pub(super) fn encode_chunked<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    chunk_size: usize,
+    options: &FileOptions,
+    reporter: &mut ProgressReporter<'_>,
+) -> Result<FileStats, UnencodableFileError> {
+    let mut encoding = Encoding::new(chunk_size, options);
+    writer.write_all(Encoding::PREFIX)?;
+
+    loop {
+        let read = match reader.read(encoding.unfilled()) {
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        match encoding.encode(read) {
+            Ok(encoded) => writer.write_all(encoded)?,
+            Err(ChunkError::Failed(e)) => return Err(e),
+            Err(ChunkError::Char(mut partial)) => {
+                let result = reader.read_exact(partial.missing());
+                let character = partial.complete(result)?;
+                return Err(encoding.char_error(character));
+            }
+        }
+        reporter.report(encoding.bytes_read, encoding.bytes_written);
+
+        if read == 0 {
+            break;
+        }
+    }
+
+    writer.flush()?;
+    Ok(encoding.finish())
+}
+
+/// Decodes everything that can be read from `reader` and writes the result to `writer`,
+/// reading at most `chunk_size` bytes at a time.
+///
+/// The progress is reported to the `reporter` after every chunk.
+#[track_caller]
+pub(super) fn decode_chunked<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    chunk_size: usize,
+    reporter: &mut ProgressReporter<'_>,
+) -> Result<FileStats, UndecodableFileError> {
+    let mut decoding = Decoding::new(chunk_size);
+
+    loop {
+        let read = match reader.read(decoding.unfilled()) {
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        match decoding.decode(read) {
+            Ok(decoded) => writer.write_all(decoded)?,
+            Err(ChunkError::Failed(e)) => return Err(e),
+            Err(ChunkError::Char(mut partial)) => {
+                let result = reader.read_exact(partial.missing());
+                let character = partial.complete(result)?;
+                return Err(decoding.char_error(character));
+            }
+        }
+        reporter.report(decoding.bytes_read, decoding.bytes_written);
+
+        if read == 0 {
+            break;
+        }
+    }
+
+    writer.flush()?;
+    Ok(decoding.finish())
+}
+
+/// The reason that [`Encoding::encode`] or [`Decoding::decode`] could not process a chunk.
+pub(super) enum ChunkError<E> {
+    /// The chunk could not be processed.
+    Failed(E),
+    /// The chunk could not be processed because of the character that begins in it.
+    /// The rest of the character has to be read from the input with the help of [`PartialChar::missing`]
+    /// before the error can be created by the `char_error` method of the encoding or decoding.
+    Char(PartialChar),
+}
+
+/// Encodes an input that is read in chunks into a buffer that it owns.
+///
+/// The caller writes [`Encoding::PREFIX`] to the output, and then repeatedly reads into [`Encoding::unfilled`]
+/// and writes the output of [`Encoding::encode`] until a read returns zero bytes.
+pub(super) struct Encoding {
+    encoder: ChunkEncoder,
+    buffer: Vec<u8>,
+    encoded: Vec<u8>,
+    /// The number of bytes at the start of `buffer` that were held back from the previous chunk.
+    held_back: usize,
+    pub(super) bytes_read: u64,
+    pub(super) bytes_written: u64,
+}
+
+impl Encoding {
+    /// What every encoding begins with.
+    pub(super) const PREFIX: &'static [u8] = b"E";
+
+    pub(super) fn new(chunk_size: usize, options: &FileOptions) -> Self {
+        debug_assert!(
+            chunk_size >= 2,
+            "a chunk must have room for a held back carriage return and at least one new byte"
+        );
+        Self {
+            encoder: ChunkEncoder::new(options),
+            buffer: vec![0; chunk_size],
+            encoded: Vec::with_capacity(2 * chunk_size),
+            held_back: 0,
+            bytes_read: 0,
+            bytes_written: Self::PREFIX.len() as u64,
+        }
+    }
+
+    /// Returns the part of the buffer that the next chunk should be read into.
+    pub(super) fn unfilled(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.held_back..]
+    }
+
+    /// Encodes the `read` bytes that were just read into [`unfilled`](Encoding::unfilled),
+    /// where zero means that the end of the input has been reached,
+    /// and returns the result that should be written to the output.
+    #[track_caller]
+    pub(super) fn encode(
+        &mut self,
+        read: usize,
+    ) -> Result<&[u8], ChunkError<UnencodableFileError>> {
+        let filled = self.held_back + read;
+        let at_end = read == 0;
+
+        if self.bytes_read == 0 && looks_encoded(&self.buffer[..filled]) {
+            return Err(ChunkError::Failed(UnencodableFileError::AlreadyEncoded {
+                path: None,
+            }));
+        }
+
+        // A carriage return at the end of the chunk is held back until the next chunk is read,
+        // since that chunk could begin with a line feed that makes it a part of a CRLF line ending.
+        let end = if !at_end && self.buffer[filled - 1] == b'\r' {
+            filled - 1
+        } else {
+            filled
+        };
+
+        self.encoded.clear();
+        if let Err(i) = self
+            .encoder
+            .encode_chunk(&self.buffer[..end], &mut self.encoded)
+        {
+            return Err(partial_char(&self.buffer[i..filled]));
+        }
+        self.bytes_read += read as u64;
+        self.bytes_written += self.encoded.len() as u64;
+
+        self.buffer.copy_within(end..filled, 0);
+        self.held_back = filled - end;
+        Ok(&self.encoded)
+    }
+
+    /// Creates the error for the unencodable character that caused a [`ChunkError::Char`].
+    #[track_caller]
+    pub(super) fn char_error(&self, unencodable_character: char) -> UnencodableFileError {
+        EncodeError::new(
+            unencodable_character,
+            self.encoder.line,
+            self.encoder.column,
+            self.encoder.index,
+        )
+        .into()
+    }
+
+    /// Returns the statistics of the finished encoding.
+    pub(super) fn finish(self) -> FileStats {
+        FileStats {
+            bytes_in: self.bytes_read,
+            bytes_out: self.bytes_written,
+            lines: self.encoder.lines(),
+            normalizations: self.encoder.normalizations(),
+        }
+    }
+}
+
+/// Decodes an input that is read in chunks into a buffer that it owns.
+///
+/// The caller repeatedly reads into [`Decoding::unfilled`] and writes the output of [`Decoding::decode`]
+/// until a read returns zero bytes.
+pub(super) struct Decoding {
+    buffer: Vec<u8>,
+    decoded: Vec<u8>,
+    /// The number of bytes at the start of `buffer` that were held back from the previous chunk.
+    held_back: usize,
+    /// The index in the input of the first byte in `buffer`.
+    offset: usize,
+    /// The index in the input of the character that caused a [`ChunkError::Char`],
+    /// or `None` if it was the first character of the input.
+    char_index: Option<usize>,
+    newlines: u64,
+    last_byte: Option<u8>,
+    pub(super) bytes_read: u64,
+    pub(super) bytes_written: u64,
+}
+
+impl Decoding {
+    pub(super) fn new(chunk_size: usize) -> Self {
+        debug_assert!(
+            chunk_size >= 2,
+            "a chunk must have room for a held back byte and at least one new byte"
+        );
+        Self {
+            buffer: vec![0; chunk_size],
+            decoded: Vec::with_capacity(chunk_size / 2),
+            held_back: 0,
+            offset: 0,
+            char_index: None,
+            newlines: 0,
+            last_byte: None,
+            bytes_read: 0,
+            bytes_written: 0,
+        }
+    }
+
+    /// Returns the part of the buffer that the next chunk should be read into.
+    pub(super) fn unfilled(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.held_back..]
+    }
+
+    /// Decodes the `read` bytes that were just read into [`unfilled`](Decoding::unfilled),
+    /// where zero means that the end of the input has been reached,
+    /// and returns the result that should be written to the output.
+    #[track_caller]
+    pub(super) fn decode(
+        &mut self,
+        read: usize,
+    ) -> Result<&[u8], ChunkError<UndecodableFileError>> {
+        let filled = self.held_back + read;
+        let at_end = read == 0;
+
+        let mut start = 0;
+        if self.offset == 0 {
+            match self.buffer[..filled].first() {
+                None => {
+                    return Err(ChunkError::Failed(not_encoded(DecodeError::new(
+                        DecodeErrorCause::EmptyInput,
+                    ))))
+                }
+                Some(b'E') => start = 1,
+                Some(_) => {
+                    self.char_index = None;
+                    return Err(partial_char(&self.buffer[..filled]));
+                }
+            }
+        }
+
+        // A byte at the end of the chunk that is not part of a full pair is held back until the next chunk is read.
+        let end = start + (filled - start) / 2 * 2;
+        self.decoded.clear();
+        for index in (start..end).step_by(2) {
+            let (odd, even) = (self.buffer[index], self.buffer[index + 1]);
+            let Some(character) = combining_char(odd, even) else {
+                self.char_index = Some(self.offset + index);
+                return Err(partial_char(&self.buffer[index..filled]));
+            };
+            let byte = decode_byte_pair(odd, even);
+            if !((32..127).contains(&byte) || byte == b'\n') {
+                return Err(ChunkError::Failed(
+                    DecodeError::new(DecodeErrorCause::NotPrintableAscii {
+                        index: self.offset + index,
+                        character,
+                    })
+                    .into(),
+                ));
+            }
+            self.decoded.push(byte);
+        }
+        self.newlines += self.decoded.iter().filter(|&&byte| byte == b'\n').count() as u64;
+        self.last_byte = self.decoded.last().copied().or(self.last_byte);
+        self.bytes_read += read as u64;
+        self.bytes_written += self.decoded.len() as u64;
+
+        if at_end && end < filled {
+            return Err(ChunkError::Failed(
+                DecodeError::new(DecodeErrorCause::DanglingByte {
+                    index: self.offset + end,
+                })
+                .into(),
+            ));
+        }
+
+        self.buffer.copy_within(end..filled, 0);
+        self.held_back = filled - end;
+        self.offset += end;
+        Ok(&self.decoded)
+    }
+
+    /// Creates the error for the character that caused a [`ChunkError::Char`].
+    #[track_caller]
+    pub(super) fn char_error(&self, character: char) -> UndecodableFileError {
+        match self.char_index {
+            None => not_encoded(DecodeError::new(DecodeErrorCause::MissingLeadingE {
+                found: character,
+            })),
+            Some(index) => {
+                let error =
+                    DecodeError::new(DecodeErrorCause::NotCombiningChar { index, character });
+                // If not even the first character after the "E" is a combining character
+                // the input was most likely never encoded.
+                if index == 1 {
+                    not_encoded(error)
+                } else {
+                    error.into()
+                }
+            }
+        }
+    }
+
+    /// Returns the statistics of the finished decoding.
+    pub(super) fn finish(self) -> FileStats {
+        FileStats {
+            bytes_in: self.bytes_read,
+            bytes_out: self.bytes_written,
+            // A final line without a line ending is also a line.
+            lines: self.newlines + u64::from(self.last_byte.is_some_and(|byte| byte != b'\n')),
+            normalizations: Vec::new(),
+        }
+    }
+}
+
+/// Returns whether the start of an input to [`encode_chunked`] looks like the output of an earlier encoding,
+/// i.e. whether it is an "E" followed by at least one combining character and nothing but valid combining characters.
+fn looks_encoded(start: &[u8]) -> bool {
+    match start {
+        [b'E', rest @ ..] if rest.len() >= 2 => {
+            let pairs = rest.chunks_exact(2);
+            // The chunk can end in the middle of a combining character.
+            matches!(pairs.remainder(), [] | [0xcc | 0xcd])
+                && pairs.into_iter().all(|pair| {
+                    combining_char(pair[0], pair[1]).is_some() && {
+                        let byte = decode_byte_pair(pair[0], pair[1]);
+                        (32..127).contains(&byte) || byte == b'\n'
+                    }
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Wraps an error that was found at the very start of an input to [`decode_chunked`].
+fn not_encoded(error: DecodeError) -> UndecodableFileError {
+    UndecodableFileError::NotEncoded { error, path: None }
+}
+
+/// Returns the character that the two bytes are the UTF-8 encoding of,
+/// if it is a combining character in the range U+0300–U+036F.
+fn combining_char(odd: u8, even: u8) -> Option<char> {
+    if odd & 0b1110_0000 != 0b1100_0000 || even & 0b1100_0000 != 0b1000_0000 {
+        return None;
+    }
+    let character = char::from_u32(u32::from(odd & 0b1_1111) << 6 | u32::from(even & 0b11_1111))?;
+    ('\u{300}'..='\u{36f}')
+        .contains(&character)
+        .then_some(character)
+}
+
+/// Encodes chunks of bytes while keeping track of the position in the input,
+/// so that errors can report where in the input they happened.
+struct ChunkEncoder {
+    line: usize,
+    column: usize,
+    index: usize,
+    tab_handling: TabHandling,
+    carriage_returns: CarriageReturns,
+    expanded_tabs: usize,
+    stripped_carriage_returns: usize,
+}
+
+impl ChunkEncoder {
+    fn new(options: &FileOptions) -> Self {
+        Self {
+            line: 1,
+            column: 1,
+            index: 0,
+            tab_handling: options.tab_handling,
+            carriage_returns: options.carriage_returns,
+            expanded_tabs: 0,
+            stripped_carriage_returns: 0,
+        }
+    }
+
+    /// Returns the number of lines that have been encoded so far,
+    /// including a final line without a line ending.
+    fn lines(&self) -> u64 {
+        (self.line - 1 + usize::from(self.column > 1)) as u64
+    }
+
+    /// Returns the changes that have been made to the input so far.
+    fn normalizations(&self) -> Vec<Normalization> {
+        let mut normalizations = Vec::new();
+        if self.expanded_tabs > 0 {
+            normalizations.push(Normalization::ExpandedTabs {
+                count: self.expanded_tabs,
+            });
+        }
+        if self.stripped_carriage_returns > 0 {
+            normalizations.push(Normalization::StrippedCarriageReturns {
+                count: self.stripped_carriage_returns,
+            });
+        }
+        normalizations
+    }
+
+    /// Encodes the bytes of the chunk and appends the result to `out`.
+    /// Tabs and carriage returns are handled as determined by the options the encoder was created with.
+    ///
+    /// If the chunk contains a byte that can not be encoded the index of that byte in the chunk is returned,
+    /// and the position of the encoder is that of the byte.
+    fn encode_chunk(&mut self, chunk: &[u8], out: &mut Vec<u8>) -> Result<(), usize> {
+        for (i, &byte) in chunk.iter().enumerate() {
+            match byte {
+                b'\t' => match self.tab_handling {
+                    TabHandling::ExpandTo(spaces) => {
+                        for _ in 0..spaces {
+                            out.extend_from_slice(&encode_byte(b' '));
+                        }
+                        self.expanded_tabs += 1;
+                    }
+                    TabHandling::Error => return Err(i),
+                },
+                b'\r'
+                    if self.carriage_returns == CarriageReturns::Strip
+                        && chunk.get(i + 1) == Some(&b'\n') =>
+                {
+                    self.stripped_carriage_returns += 1;
+                }
+                b'\n' => {
+                    out.extend_from_slice(&encode_byte(byte));
+                    self.line += 1;
+                    // `column` is still 1-indexed since it gets incremented below.
+                    self.column = 0;
+                }
+                32..127 => out.extend_from_slice(&encode_byte(byte)),
+                _ => return Err(i),
+            }
+            self.index += 1;
+            self.column += 1;
+        }
+        Ok(())
+    }
+}
+
+/// The first bytes of a character that a chunk ends in the middle of.
+pub(super) struct PartialChar {
+    bytes: [u8; 4],
+    available: usize,
+    width: usize,
+}
+
+impl PartialChar {
+    /// Returns the part of the character that still has to be read from the input.
+    pub(super) fn missing(&mut self) -> &mut [u8] {
+        &mut self.bytes[self.available..self.width]
+    }
+
+    /// Returns the character once [`missing`](PartialChar::missing) has been filled by `read_result`,
+    /// which is the result of the read.
+    pub(super) fn complete(self, read_result: io::Result<()>) -> io::Result<char> {
+        match read_result {
+            Ok(()) => (),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Err(invalid_utf8()),
+            Err(e) => return Err(e),
+        }
+        str::from_utf8(&self.bytes[..self.width])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .ok_or_else(invalid_utf8)
+    }
+}
+
+/// Returns the character that begins with the first byte of `bytes` as a [`ChunkError::Char`],
+/// or a [`ChunkError::Failed`] if the byte can not begin a character.
+fn partial_char<E: From<io::Error>>(bytes: &[u8]) -> ChunkError<E> {
+    let width = match bytes[0].leading_ones() {
+        0 => 1,
+        n @ 2..=4 => n as usize,
+        _ => return ChunkError::Failed(invalid_utf8().into()),
+    };
+    let mut partial = PartialChar {
+        bytes: [0; 4],
+        available: width.min(bytes.len()),
+        width,
+    };
+    partial.bytes[..partial.available].copy_from_slice(&bytes[..partial.available]);
+    ChunkError::Char(partial)
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        files::{test::text, Progress},
+        zalgo_encode, DecodeErrorKind, ZalgoString,
+    };
+    use alloc::{format, string::String};
+
+    fn encode_with_options(
+        input: &str,
+        chunk_size: usize,
+        options: &FileOptions,
+    ) -> Result<(String, Vec<Normalization>), UnencodableFileError> {
+        let mut out = Vec::new();
+        let stats = encode_chunked(
+            input.as_bytes(),
+            &mut out,
+            chunk_size,
+            options,
+            &mut ProgressReporter::none(),
+        )?;
+        Ok((String::from_utf8(out).unwrap(), stats.normalizations))
+    }
+
+    fn encode_with_chunk_size(
+        input: &str,
+        chunk_size: usize,
+    ) -> Result<String, UnencodableFileError> {
+        Ok(encode_with_options(input, chunk_size, &FileOptions::new())?.0)
+    }
+
+    #[test]
+    fn test_memory_is_bounded_by_chunk_size() {
+        /// Records the largest read and write that passes through it.
+        struct Instrumented<T> {
+            inner: T,
+            largest: usize,
+        }
+
+        impl<T: Read> Read for Instrumented<T> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.largest = self.largest.max(buf.len());
+                self.inner.read(buf)
+            }
+        }
+
+        impl<T: Write> Write for Instrumented<T> {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.largest = self.largest.max(buf.len());
+                self.inner.write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.inner.flush()
+            }
+        }
+
+        const CHUNK: usize = 1000;
+        let contents = text(100 * CHUNK);
+        let mut reader = Instrumented {
+            inner: contents.as_bytes(),
+            largest: 0,
+        };
+        let mut writer = Instrumented {
+            inner: Vec::new(),
+            largest: 0,
+        };
+        encode_chunked(
+            &mut reader,
+            &mut writer,
+            CHUNK,
+            &FileOptions::new(),
+            &mut ProgressReporter::none(),
+        )
+        .unwrap();
+
+        assert!(reader.largest <= CHUNK);
+        assert!(writer.largest <= 2 * CHUNK);
+        assert_eq!(
+            String::from_utf8(writer.inner).unwrap(),
+            zalgo_encode(&contents).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_normalization_across_chunks() {
+        let contents = "fn main() {\r\n\tprintln!(\"Zalgo\");\r\n}\r\n";
+        let expected = zalgo_encode("fn main() {\n    println!(\"Zalgo\");\n}\n").unwrap();
+        for chunk_size in 2..=contents.len() + 1 {
+            assert_eq!(
+                encode_with_options(contents, chunk_size, &FileOptions::new()).unwrap(),
+                (
+                    expected.clone(),
+                    vec![
+                        Normalization::ExpandedTabs { count: 1 },
+                        Normalization::StrippedCarriageReturns { count: 3 }
+                    ]
+                ),
+                "chunk size {chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_option_combinations() {
+        let contents = "\tab\r\n\t\tcd\r\n";
+        for tab_handling in [
+            TabHandling::Error,
+            TabHandling::ExpandTo(0),
+            TabHandling::ExpandTo(2),
+        ] {
+            for carriage_returns in [CarriageReturns::Error, CarriageReturns::Strip] {
+                let options = FileOptions::new()
+                    .tab_handling(tab_handling)
+                    .carriage_returns(carriage_returns);
+                for chunk_size in 2..=contents.len() + 1 {
+                    let result = encode_with_options(contents, chunk_size, &options);
+                    match (tab_handling, carriage_returns) {
+                        (TabHandling::Error, _) => {
+                            let Err(UnencodableFileError::UnencodableContent { error: e, .. }) =
+                                result
+                            else {
+                                panic!("tabs should not be encodable with {options:?}");
+                            };
+                            assert_eq!((e.char(), e.index()), ('\t', 0));
+                        }
+                        (TabHandling::ExpandTo(_), CarriageReturns::Error) => {
+                            let Err(UnencodableFileError::UnencodableContent { error: e, .. }) =
+                                result
+                            else {
+                                panic!("carriage returns should not be encodable with {options:?}");
+                            };
+                            assert_eq!((e.char(), e.line(), e.column()), ('\r', 1, 4));
+                        }
+                        (TabHandling::ExpandTo(spaces), CarriageReturns::Strip) => {
+                            let expected = contents
+                                .replace('\t', &" ".repeat(spaces))
+                                .replace("\r\n", "\n");
+                            assert_eq!(
+                                result.unwrap(),
+                                (
+                                    zalgo_encode(&expected).unwrap(),
+                                    vec![
+                                        Normalization::ExpandedTabs { count: 3 },
+                                        Normalization::StrippedCarriageReturns { count: 2 }
+                                    ]
+                                ),
+                                "chunk size {chunk_size}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Nothing is reported if the input did not have to be changed.
+        assert_eq!(
+            encode_with_options("ab\ncd", 2, &FileOptions::new()).unwrap(),
+            (zalgo_encode("ab\ncd").unwrap(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_error_position_across_chunks() {
+        let contents = "\tab\r\ncd\u{e5}ef";
+        for chunk_size in 2..=contents.len() + 1 {
+            let Err(UnencodableFileError::UnencodableContent { error: e, .. }) =
+                encode_with_chunk_size(contents, chunk_size)
+            else {
+                panic!("encoding should fail with chunk size {chunk_size}");
+            };
+            assert_eq!(
+                (e.char(), e.line(), e.column(), e.index()),
+                ('\u{e5}', 2, 3, 7),
+                "chunk size {chunk_size}"
+            );
+        }
+
+        // A carriage return that is not a part of a line ending can not be encoded.
+        for contents in ["ab\rcd", "abcd\r"] {
+            for chunk_size in 2..=contents.len() + 1 {
+                let Err(UnencodableFileError::UnencodableContent { error: e, .. }) =
+                    encode_with_chunk_size(contents, chunk_size)
+                else {
+                    panic!("encoding should fail with chunk size {chunk_size}");
+                };
+                assert_eq!(e.char(), '\r');
+                assert_eq!(e.index(), contents.find('\r').unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_progress_is_reported_per_chunk() {
+        const CHUNK: usize = 1000;
+        let contents = text(10 * CHUNK + 17);
+        let size = contents.len() as u64;
+
+        let mut calls = Vec::new();
+        let mut encoded = Vec::new();
+        {
+            let mut callback = |p| calls.push(p);
+            let mut reporter = ProgressReporter::new(Some(size), &mut callback);
+            encode_chunked(
+                contents.as_bytes(),
+                &mut encoded,
+                CHUNK,
+                &FileOptions::new(),
+                &mut reporter,
+            )
+            .unwrap();
+        }
+        assert_eq!(calls.len(), 11);
+        for (i, progress) in calls.iter().enumerate() {
+            let bytes_read = (CHUNK as u64 * (i as u64 + 1)).min(size);
+            assert_eq!(
+                *progress,
+                Progress {
+                    bytes_read,
+                    bytes_written: 2 * bytes_read + 1,
+                    total_bytes: Some(size)
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let encode = |input: &str| {
+            encode_chunked(
+                input.as_bytes(),
+                io::sink(),
+                4,
+                &FileOptions::new(),
+                &mut ProgressReporter::none(),
+            )
+            .unwrap()
+        };
+        let decode = |input: &str| {
+            decode_chunked(
+                input.as_bytes(),
+                io::sink(),
+                4,
+                &mut ProgressReporter::none(),
+            )
+            .unwrap()
+        };
+
+        for (input, lines) in [
+            ("", 0),
+            ("\n", 1),
+            ("Zalgo", 1),
+            ("Zalgo\n", 1),
+            ("Zalgo\n\nHe comes", 3),
+            ("Zalgo\n\nHe comes\n", 3),
+        ] {
+            let size = input.len() as u64;
+            let stats = encode(input);
+            assert_eq!(
+                stats,
+                FileStats {
+                    bytes_in: size,
+                    bytes_out: 2 * size + 1,
+                    lines,
+                    normalizations: Vec::new()
+                },
+                "{input:?}"
+            );
+            assert_eq!(
+                decode(&zalgo_encode(input).unwrap()),
+                FileStats {
+                    bytes_in: 2 * size + 1,
+                    bytes_out: size,
+                    lines,
+                    normalizations: Vec::new()
+                },
+                "{input:?}"
+            );
+        }
+
+        let input = "\tZalgo\r\n\tHe comes";
+        let normalized = "    Zalgo\n    He comes";
+        let stats = encode(input);
+        assert_eq!(stats.bytes_in, input.len() as u64);
+        assert_eq!(stats.bytes_out, 2 * normalized.len() as u64 + 1);
+        assert_eq!(stats.lines, 2);
+        assert_eq!(
+            stats.normalizations,
+            [
+                Normalization::ExpandedTabs { count: 2 },
+                Normalization::StrippedCarriageReturns { count: 1 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let mut out = Vec::new();
+        let Err(UnencodableFileError::Io { error: e, .. }) = encode_chunked(
+            &b"ab\xe5"[..],
+            &mut out,
+            2,
+            &FileOptions::new(),
+            &mut ProgressReporter::none(),
+        ) else {
+            panic!("invalid UTF-8 should result in an IO error");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn decode_with_chunk_size(
+        input: &str,
+        chunk_size: usize,
+    ) -> Result<String, UndecodableFileError> {
+        let mut out = Vec::new();
+        decode_chunked(
+            input.as_bytes(),
+            &mut out,
+            chunk_size,
+            &mut ProgressReporter::none(),
+        )?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_decode_across_chunks() {
+        let contents = "fn main() {\n    println!(\"Zalgo\");\n}\n";
+        let encoded = zalgo_encode(contents).unwrap();
+        for chunk_size in 2..=encoded.len() + 1 {
+            assert_eq!(
+                decode_with_chunk_size(&encoded, chunk_size).unwrap(),
+                contents,
+                "chunk size {chunk_size}"
+            );
+        }
+        assert_eq!(decode_with_chunk_size("E", 2).unwrap(), "");
+    }
+
+    #[test]
+    fn test_decode_errors_across_chunks() {
+        let encoded = zalgo_encode("Zalgo\nHe comes").unwrap();
+        // E.g. a newline that was added by a text editor.
+        let trailing_newline = format!("{encoded}\n");
+        let corrupted = format!("{}\u{360}{}", &encoded[..7], &encoded[9..]);
+        let not_combining = format!("{}\u{e5}{}", &encoded[..7], &encoded[9..]);
+
+        for (input, kind, index) in [
+            ("", DecodeErrorKind::EmptyInput, None),
+            ("Zalgo", DecodeErrorKind::MissingLeadingE, Some(0)),
+            (
+                &trailing_newline,
+                DecodeErrorKind::DanglingByte,
+                Some(encoded.len()),
+            ),
+            (&corrupted, DecodeErrorKind::NotPrintableAscii, Some(7)),
+            (&not_combining, DecodeErrorKind::NotCombiningChar, Some(7)),
+            ("E\u{300}a", DecodeErrorKind::DanglingByte, Some(3)),
+        ] {
+            // The errors should be the same as the ones from validating the whole string at once.
+            let expected = ZalgoString::from_encoded(input).unwrap_err();
+            assert_eq!((expected.kind(), expected.index()), (kind, index));
+            for chunk_size in 2..=input.len() + 1 {
+                let Err(
+                    UndecodableFileError::UndecodableContent { error: e, .. }
+                    | UndecodableFileError::NotEncoded { error: e, .. },
+                ) = decode_with_chunk_size(input, chunk_size)
+                else {
+                    panic!("decoding {input:?} should fail with chunk size {chunk_size}");
+                };
+                assert_eq!(
+                    (e.kind(), e.index(), e.char()),
+                    (expected.kind(), expected.index(), expected.char()),
+                    "chunk size {chunk_size}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! Contains [`encode_dir`] and [`decode_dir`], which process every file in a directory tree.
+
+use super::{decode_file_with_options, encode_file_with_options, FileError, FileOptions};
+
+use alloc::{vec, vec::Vec};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Describes the outcome of [`encode_dir`] or [`decode_dir`] for every entry in the input directory tree.
+///
+/// All paths are relative to the input directory.
+#[derive(Debug, Default)]
+pub struct DirReport {
+    succeeded: Vec<PathBuf>,
+    skipped: Vec<PathBuf>,
+    failed: Vec<(PathBuf, FileError)>,
+}
+
+impl DirReport {
+    /// Returns the paths of the files that were processed successfully.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn succeeded(&self) -> &[PathBuf] {
+        &self.succeeded
+    }
+
+    /// Returns the paths of the entries that were skipped since they are neither regular files nor directories,
+    /// e.g. symbolic links when [`FileOptions::follow_symlinks`] is not set.
+    /// Directories that have already been visited through a symbolic link and the output directory are also skipped.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn skipped(&self) -> &[PathBuf] {
+        &self.skipped
+    }
+
+    /// Returns the paths of the files and directories that could not be processed, along with the reason why.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn failed(&self) -> &[(PathBuf, FileError)] {
+        &self.failed
+    }
+
+    /// Returns whether every file in the directory tree was processed successfully.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Encodes every regular file in the given directory and its subdirectories with [`encode_file_with_options`],
+/// and stores the results at the same relative paths under the output directory.
+///
+/// Files that can not be encoded do not stop the process, instead they are listed among the failures
+/// in the returned [`DirReport`]. Symbolic links are skipped unless the options say that they should be followed.
+/// If the output directory is inside the input directory it is not encoded itself.
+///
+/// # Errors
+///
+/// Returns an error if the input directory can not be read or the output directory can not be created.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{encode_dir, FileOptions};
+/// # let dir = tempfile::tempdir()?;
+/// # let in_dir = dir.path().join("src");
+/// # let out_dir = dir.path().join("encoded");
+/// std::fs::create_dir_all(in_dir.join("nested"))?;
+/// std::fs::write(in_dir.join("nested/zalgo.txt"), "He comes")?;
+/// let report = encode_dir(&in_dir, &out_dir, &FileOptions::new())?;
+/// assert!(report.is_success());
+/// assert_eq!(report.succeeded(), [std::path::Path::new("nested/zalgo.txt")]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(in_dir = %in_dir.as_ref().display(), out_dir = %out_dir.as_ref().display()),
+    )
+)]
+pub fn encode_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_dir: P,
+    out_dir: Q,
+    options: &FileOptions,
+) -> Result<DirReport, FileError> {
+    fn encode(in_file: &Path, out_file: &Path, options: &FileOptions) -> Result<(), FileError> {
+        encode_file_with_options(in_file, out_file, options)?;
+        Ok(())
+    }
+
+    process_dir(in_dir.as_ref(), out_dir.as_ref(), options, encode)
+}
+
+/// Decodes every regular file in the given directory and its subdirectories with [`decode_file_with_options`],
+/// and stores the results at the same relative paths under the output directory.
+///
+/// This is the inverse of [`encode_dir`], see it for more information.
+///
+/// # Errors
+///
+/// Returns an error if the input directory can not be read or the output directory can not be created.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(in_dir = %in_dir.as_ref().display(), out_dir = %out_dir.as_ref().display()),
+    )
+)]
+pub fn decode_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_dir: P,
+    out_dir: Q,
+    options: &FileOptions,
+) -> Result<DirReport, FileError> {
+    fn decode(in_file: &Path, out_file: &Path, options: &FileOptions) -> Result<(), FileError> {
+        decode_file_with_options(in_file, out_file, options)?;
+        Ok(())
+    }
+
+    process_dir(in_dir.as_ref(), out_dir.as_ref(), options, decode)
+}
+
+/// Walks the directory tree under `in_dir` and calls `process` for every regular file in it
+/// with the path to the corresponding file under `out_dir`.
+fn process_dir(
+    in_dir: &Path,
+    out_dir: &Path,
+    options: &FileOptions,
+    process: fn(&Path, &Path, &FileOptions) -> Result<(), FileError>,
+) -> Result<DirReport, FileError> {
+    // Make sure that the input directory can be read before anything is created.
+    let entries = fs::read_dir(in_dir).map_err(|e| FileError::from(e).with_path(in_dir))?;
+    let mut pending = vec![(PathBuf::new(), entries)];
+    let out_dir_canonical = fs::create_dir_all(out_dir)
+        .and_then(|()| fs::canonicalize(out_dir))
+        .map_err(|e| FileError::from(e).with_path(out_dir))?;
+
+    // The directories that have been entered, used to avoid cycles when symbolic links are followed.
+    let in_dir_canonical =
+        fs::canonicalize(in_dir).map_err(|e| FileError::from(e).with_path(in_dir))?;
+    let mut visited = HashSet::from([in_dir_canonical]);
+    let mut report = DirReport::default();
+
+    while let Some((relative_dir, entries)) = pending.pop() {
+        let mut entries = match entries.collect::<Result<Vec<_>, _>>() {
+            Ok(entries) => entries,
+            Err(e) => {
+                let error = FileError::from(e).with_path(&in_dir.join(&relative_dir));
+                report.failed.push((relative_dir, error));
+                continue;
+            }
+        };
+        // Process the entries in a predictable order.
+        entries.sort_by_key(fs::DirEntry::file_name);
+
+        for entry in entries {
+            let relative = relative_dir.join(entry.file_name());
+            let in_path = entry.path();
+            let out_path = out_dir.join(&relative);
+
+            let metadata = if options.follow_symlinks {
+                fs::metadata(&in_path)
+            } else {
+                fs::symlink_metadata(&in_path)
+            };
+            let file_type = match metadata {
+                Ok(metadata) => metadata.file_type(),
+                Err(e) => {
+                    report
+                        .failed
+                        .push((relative, FileError::from(e).with_path(&in_path)));
+                    continue;
+                }
+            };
+
+            if file_type.is_dir() {
+                let in_error = |e| FileError::from(e).with_path(&in_path);
+                let subdirectory =
+                    fs::canonicalize(&in_path)
+                        .map_err(in_error)
+                        .and_then(|canonical| {
+                            if canonical == out_dir_canonical || !visited.insert(canonical) {
+                                Ok(None)
+                            } else {
+                                fs::create_dir_all(&out_path)
+                                    .map_err(|e| FileError::from(e).with_path(&out_path))?;
+                                fs::read_dir(&in_path).map(Some).map_err(in_error)
+                            }
+                        });
+                match subdirectory {
+                    Ok(Some(entries)) => pending.push((relative, entries)),
+                    Ok(None) => report.skipped.push(relative),
+                    Err(e) => report.failed.push((relative, e)),
+                }
+            } else if file_type.is_file() {
+                match process(&in_path, &out_path, options) {
+                    Ok(()) => report.succeeded.push(relative),
+                    Err(e) => report.failed.push((relative, e)),
+                }
+            } else {
+                report.skipped.push(relative);
+            }
+        }
+    }
+
+    report.succeeded.sort();
+    report.skipped.sort();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{files::test::text, DecodeErrorKind};
+    use alloc::string::String;
+
+    /// Returns the relative paths and contents of all the files under `dir`.
+    fn read_tree(dir: &Path) -> Vec<(PathBuf, String)> {
+        let mut files = Vec::new();
+        let mut pending = vec![PathBuf::new()];
+        while let Some(relative_dir) = pending.pop() {
+            for entry in fs::read_dir(dir.join(&relative_dir)).unwrap() {
+                let entry = entry.unwrap();
+                let relative = relative_dir.join(entry.file_name());
+                if entry.file_type().unwrap().is_dir() {
+                    pending.push(relative);
+                } else {
+                    files.push((relative, fs::read_to_string(entry.path()).unwrap()));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_dir_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original");
+        let encoded = dir.path().join("encoded");
+        let decoded = dir.path().join("decoded");
+
+        fs::create_dir_all(original.join("src/nested")).unwrap();
+        fs::create_dir_all(original.join("empty")).unwrap();
+        fs::write(original.join("README.md"), "# Zalgo\n").unwrap();
+        fs::write(original.join("src/main.rs"), text(1000)).unwrap();
+        fs::write(original.join("src/nested/deep.rs"), text(100)).unwrap();
+        fs::write(original.join("src/nested/\u{e5}.txt"), "\u{e5}").unwrap();
+
+        let report = encode_dir(&original, &encoded, &FileOptions::new()).unwrap();
+        assert_eq!(
+            report.succeeded(),
+            [
+                Path::new("README.md"),
+                Path::new("src/main.rs"),
+                Path::new("src/nested/deep.rs")
+            ]
+        );
+        assert!(report.skipped().is_empty());
+        assert!(!report.is_success());
+        let [(path, FileError::UnencodableContent { error: e, .. })] = report.failed() else {
+            panic!("only the file with the unencodable character should fail");
+        };
+        assert_eq!(path, Path::new("src/nested/\u{e5}.txt"));
+        assert_eq!(e.char(), '\u{e5}');
+        assert!(encoded.join("empty").is_dir());
+
+        let report = decode_dir(&encoded, &decoded, &FileOptions::new()).unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.succeeded().len(), 3);
+
+        let mut expected = read_tree(&original);
+        expected.retain(|(path, _)| path != Path::new("src/nested/\u{e5}.txt"));
+        assert_eq!(read_tree(&decoded), expected);
+        assert!(decoded.join("empty").is_dir());
+
+        // Decoding the original tree fails for every file, but does not stop the process.
+        let report =
+            decode_dir(&original, dir.path().join("garbage"), &FileOptions::new()).unwrap();
+        assert_eq!(report.failed().len(), 4);
+        assert!(report
+            .failed()
+            .iter()
+            .all(|(_, e)| matches!(e, FileError::NotEncoded { .. })));
+        assert!(matches!(
+            report.failed()[0],
+            (_, FileError::NotEncoded { error: ref e, .. }) if e.kind() == DecodeErrorKind::MissingLeadingE
+        ));
+
+        assert!(matches!(
+            encode_dir(dir.path().join("missing"), &encoded, &FileOptions::new()),
+            Err(FileError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_output_inside_input() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("zalgo.txt"), "He comes").unwrap();
+
+        let report = encode_dir(dir.path(), dir.path().join("out"), &FileOptions::new()).unwrap();
+        assert_eq!(report.succeeded(), [Path::new("zalgo.txt")]);
+        assert_eq!(report.skipped(), [Path::new("out")]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original");
+        fs::create_dir_all(original.join("sub")).unwrap();
+        fs::write(original.join("sub/zalgo.txt"), "He comes").unwrap();
+        std::os::unix::fs::symlink(original.join("sub/zalgo.txt"), original.join("link.txt"))
+            .unwrap();
+        // A link back to the root would make the walk go on forever if it was followed naively.
+        std::os::unix::fs::symlink(&original, original.join("sub/cycle")).unwrap();
+
+        let report = encode_dir(&original, dir.path().join("a"), &FileOptions::new()).unwrap();
+        assert_eq!(report.succeeded(), [Path::new("sub/zalgo.txt")]);
+        assert_eq!(
+            report.skipped(),
+            [Path::new("link.txt"), Path::new("sub/cycle")]
+        );
+
+        let options = FileOptions::new().follow_symlinks(true);
+        let report = encode_dir(&original, dir.path().join("b"), &options).unwrap();
+        assert_eq!(
+            report.succeeded(),
+            [Path::new("link.txt"), Path::new("sub/zalgo.txt")]
+        );
+        assert_eq!(report.skipped(), [Path::new("sub/cycle")]);
+    }
+}
@@ -0,0 +1,348 @@
+//! Contains the errors that are returned by the functions in the [`files`](super) module.
+
+use crate::{DecodeError, EncodeError};
+
+use core::fmt;
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The error returned by the functions in the [`files`](super) module that encode files,
+/// like [`encode_file`](super::encode_file) and [`wrap_python_file`](super::wrap_python_file).
+///
+/// The functions that take paths record the path to the file that was being read or written
+/// when the error happened, and it is included in the message when the error is displayed.
+#[derive(Debug)]
+pub enum UnencodableFileError {
+    /// A file could not be read or written.
+    Io {
+        /// The underlying error.
+        error: io::Error,
+        /// The path to the file that could not be read or written, if known.
+        path: Option<PathBuf>,
+    },
+    /// The input file contains a character that can not be encoded.
+    UnencodableContent {
+        /// The underlying error.
+        error: EncodeError,
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+    /// The input file has already been encoded.
+    ///
+    /// An encoding consists of combining characters, which can not be encoded,
+    /// so encoding it again is never possible.
+    AlreadyEncoded {
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+}
+
+impl UnencodableFileError {
+    /// Returns the path to the file that was being read or written when the error happened, if known.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::files::encode_file;
+    /// # use std::path::Path;
+    /// let err = encode_file("missing.txt", "out.txt").unwrap_err();
+    /// assert_eq!(err.path(), Some(Path::new("missing.txt")));
+    /// assert!(err.to_string().starts_with("failed to encode `missing.txt`: "));
+    /// ```
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Io { path, .. }
+            | Self::UnencodableContent { path, .. }
+            | Self::AlreadyEncoded { path } => path.as_deref(),
+        }
+    }
+
+    /// Sets the path of the error if it does not already have one.
+    pub(super) fn with_path(mut self, new_path: &Path) -> Self {
+        let (Self::Io { path, .. }
+        | Self::UnencodableContent { path, .. }
+        | Self::AlreadyEncoded { path }) = &mut self;
+        path.get_or_insert_with(|| new_path.to_path_buf());
+        self
+    }
+}
+
+impl From<io::Error> for UnencodableFileError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Self::Io { error, path: None }
+    }
+}
+
+impl From<EncodeError> for UnencodableFileError {
+    #[inline]
+    fn from(error: EncodeError) -> Self {
+        Self::UnencodableContent { error, path: None }
+    }
+}
+
+impl fmt::Display for UnencodableFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.path() {
+            write!(f, "failed to encode `{}`: ", path.display())?;
+        }
+        match self {
+            Self::Io { error, .. } => write!(f, "{error}"),
+            Self::UnencodableContent { error, .. } => write!(f, "{error}"),
+            Self::AlreadyEncoded { .. } => f.write_str("the contents are already encoded"),
+        }
+    }
+}
+
+impl std::error::Error for UnencodableFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => error.source(),
+            Self::UnencodableContent { error, .. } => error.source(),
+            Self::AlreadyEncoded { .. } => None,
+        }
+    }
+}
+
+/// The error returned by the functions in the [`files`](super) module that decode files,
+/// like [`decode_file`](super::decode_file) and [`unwrap_python_file`](super::unwrap_python_file).
+///
+/// The functions that take paths record the path to the file that was being read or written
+/// when the error happened, and it is included in the message when the error is displayed.
+#[derive(Debug)]
+pub enum UndecodableFileError {
+    /// A file could not be read or written.
+    Io {
+        /// The underlying error.
+        error: io::Error,
+        /// The path to the file that could not be read or written, if known.
+        path: Option<PathBuf>,
+    },
+    /// The contents of the input file could not be decoded.
+    UndecodableContent {
+        /// The underlying error.
+        error: DecodeError,
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+    /// The input file is empty or does not begin like an encoding,
+    /// so it was most likely never encoded to begin with.
+    NotEncoded {
+        /// The error that was found at the start of the file.
+        error: DecodeError,
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+}
+
+impl UndecodableFileError {
+    /// Returns the path to the file that was being read or written when the error happened, if known.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Io { path, .. }
+            | Self::UndecodableContent { path, .. }
+            | Self::NotEncoded { path, .. } => path.as_deref(),
+        }
+    }
+
+    /// Sets the path of the error if it does not already have one.
+    pub(super) fn with_path(mut self, new_path: &Path) -> Self {
+        let (Self::Io { path, .. }
+        | Self::UndecodableContent { path, .. }
+        | Self::NotEncoded { path, .. }) = &mut self;
+        path.get_or_insert_with(|| new_path.to_path_buf());
+        self
+    }
+}
+
+impl From<io::Error> for UndecodableFileError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Self::Io { error, path: None }
+    }
+}
+
+impl From<DecodeError> for UndecodableFileError {
+    #[inline]
+    fn from(error: DecodeError) -> Self {
+        Self::UndecodableContent { error, path: None }
+    }
+}
+
+impl fmt::Display for UndecodableFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(path) = self.path() {
+            write!(f, "failed to decode `{}`: ", path.display())?;
+        }
+        match self {
+            Self::Io { error, .. } => write!(f, "{error}"),
+            Self::UndecodableContent { error, .. } => write!(f, "{error}"),
+            Self::NotEncoded { error, .. } => write!(f, "the contents are not encoded: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for UndecodableFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => error.source(),
+            Self::UndecodableContent { error, .. } | Self::NotEncoded { error, .. } => {
+                error.source()
+            }
+        }
+    }
+}
+
+/// The error returned by the functions in the [`files`](super) module that can both encode and decode files,
+/// like [`encode_dir`](super::encode_dir) and [`decode_dir`](super::decode_dir).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FileError {
+    /// A file or directory could not be read or written.
+    Io {
+        /// The underlying error.
+        error: io::Error,
+        /// The path to the file or directory that could not be read or written, if known.
+        path: Option<PathBuf>,
+    },
+    /// The input file contains a character that can not be encoded.
+    UnencodableContent {
+        /// The underlying error.
+        error: EncodeError,
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+    /// The contents of the input file could not be decoded.
+    UndecodableContent {
+        /// The underlying error.
+        error: DecodeError,
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+    /// The input file has already been encoded, see [`UnencodableFileError::AlreadyEncoded`].
+    AlreadyEncoded {
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+    /// The input file was most likely never encoded, see [`UndecodableFileError::NotEncoded`].
+    NotEncoded {
+        /// The error that was found at the start of the file.
+        error: DecodeError,
+        /// The path to the input file, if known.
+        path: Option<PathBuf>,
+    },
+}
+
+impl FileError {
+    /// Returns the path to the file or directory that was being read or written when the error happened, if known.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Io { path, .. }
+            | Self::UnencodableContent { path, .. }
+            | Self::UndecodableContent { path, .. }
+            | Self::AlreadyEncoded { path }
+            | Self::NotEncoded { path, .. } => path.as_deref(),
+        }
+    }
+
+    /// Sets the path of the error if it does not already have one.
+    pub(super) fn with_path(mut self, new_path: &Path) -> Self {
+        let (Self::Io { path, .. }
+        | Self::UnencodableContent { path, .. }
+        | Self::UndecodableContent { path, .. }
+        | Self::AlreadyEncoded { path }
+        | Self::NotEncoded { path, .. }) = &mut self;
+        path.get_or_insert_with(|| new_path.to_path_buf());
+        self
+    }
+}
+
+impl From<io::Error> for FileError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        Self::Io { error, path: None }
+    }
+}
+
+impl From<EncodeError> for FileError {
+    #[inline]
+    fn from(error: EncodeError) -> Self {
+        Self::UnencodableContent { error, path: None }
+    }
+}
+
+impl From<DecodeError> for FileError {
+    #[inline]
+    fn from(error: DecodeError) -> Self {
+        Self::UndecodableContent { error, path: None }
+    }
+}
+
+impl From<UnencodableFileError> for FileError {
+    #[inline]
+    fn from(e: UnencodableFileError) -> Self {
+        match e {
+            UnencodableFileError::Io { error, path } => Self::Io { error, path },
+            UnencodableFileError::UnencodableContent { error, path } => {
+                Self::UnencodableContent { error, path }
+            }
+            UnencodableFileError::AlreadyEncoded { path } => Self::AlreadyEncoded { path },
+        }
+    }
+}
+
+impl From<UndecodableFileError> for FileError {
+    #[inline]
+    fn from(e: UndecodableFileError) -> Self {
+        match e {
+            UndecodableFileError::Io { error, path } => Self::Io { error, path },
+            UndecodableFileError::UndecodableContent { error, path } => {
+                Self::UndecodableContent { error, path }
+            }
+            UndecodableFileError::NotEncoded { error, path } => Self::NotEncoded { error, path },
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, self.path()) {
+            (Self::Io { .. }, Some(path)) => write!(f, "failed to access `{}`: ", path.display())?,
+            (Self::UnencodableContent { .. } | Self::AlreadyEncoded { .. }, Some(path)) => {
+                write!(f, "failed to encode `{}`: ", path.display())?;
+            }
+            (Self::UndecodableContent { .. } | Self::NotEncoded { .. }, Some(path)) => {
+                write!(f, "failed to decode `{}`: ", path.display())?;
+            }
+            (_, None) => (),
+        }
+        match self {
+            Self::Io { error, .. } => write!(f, "{error}"),
+            Self::UnencodableContent { error, .. } => write!(f, "{error}"),
+            Self::UndecodableContent { error, .. } => write!(f, "{error}"),
+            Self::AlreadyEncoded { .. } => f.write_str("the contents are already encoded"),
+            Self::NotEncoded { error, .. } => write!(f, "the contents are not encoded: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => error.source(),
+            Self::UnencodableContent { error, .. } => error.source(),
+            Self::UndecodableContent { error, .. } | Self::NotEncoded { error, .. } => {
+                error.source()
+            }
+            Self::AlreadyEncoded { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,1140 @@
+//! Contains functions that encode and decode files, as well as functions that wrap and unwrap Python source files.
+//!
+//! [`encode_file`] and [`decode_file`] read and write their files in chunks,
+//! so they can handle files that are too large to fit in memory.
+//! [`encode_dir`] and [`decode_dir`] do the same for every file in a directory tree,
+//! and [`encode_files`] and [`decode_files`] for a list of files.
+//! [`encode_file_with_progress`] and [`decode_file_with_progress`] report how far along they are after every chunk.
+//! [`encode_stream`] and [`decode_stream`] work in the same way on any [`Read`](std::io::Read)er
+//! and [`Write`]r, e.g. an in-memory buffer or a network connection.
+//! The `async-io` feature adds the [`async_io`] module with asynchronous versions of these functions built on `tokio`.
+//!
+//! Every function has a `_with_options` variant that takes a [`FileOptions`],
+//! which determines how tabs and carriage returns in the input are handled
+//! and whether an existing output file may be overwritten.
+//! When they succeed they return [`FileStats`], which among other things lists
+//! the changes that were made to the input to make it encodable as [`Normalization`]s.
+//!
+//! The output is written to a temporary file in the same directory as the output file,
+//! which is moved to the output file only once everything has been written.
+//! This means that the output file is never left partially written,
+//! and that an existing output file is left as it was if a function returns an error.
+//! The errors include the path to the file that caused them.
+
+// The errors carry the path to the file that caused them, which makes them larger than clippy likes.
+// They are only ever returned from functions that do I/O, so the cost of moving them around does not matter.
+#![allow(clippy::result_large_err)]
+
+#[cfg(feature = "async-io")]
+pub mod async_io;
+mod atomic;
+mod batch;
+mod chunked;
+mod dir;
+mod error;
+mod options;
+mod progress;
+mod python;
+mod stats;
+mod stream;
+
+pub use batch::{decode_files, encode_files};
+pub use dir::{decode_dir, encode_dir, DirReport};
+pub use error::{FileError, UndecodableFileError, UnencodableFileError};
+pub use options::{CarriageReturns, FileOptions, Normalization, TabHandling};
+pub use progress::Progress;
+pub use stats::FileStats;
+pub use stream::{decode_stream, encode_stream};
+
+use crate::zalgo_unwrap_python;
+use atomic::AtomicFile;
+use chunked::{decode_chunked, encode_chunked};
+use progress::ProgressReporter;
+
+use alloc::vec::Vec;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// The number of bytes of the input file that are processed at a time by [`encode_file`] and [`decode_file`].
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Encodes the contents of the given file and stores the result in another file.
+///
+/// The file is encoded in chunks, so it never has to be loaded into memory in its entirety.
+///
+/// Tabs are replaced by four spaces and CRLF line endings by LF line endings before encoding,
+/// and an existing output file is overwritten.
+/// Use [`encode_file_with_options`] to change this.
+///
+/// Returns statistics about the operation, including the changes that were made to the contents of the file to make it encodable.
+/// The positions in an [`EncodeError`](crate::EncodeError) refer to the bytes of the input file before these changes.
+///
+/// # Errors
+///
+/// Returns an error if the input file can not be read or the output file can not be written,
+/// or if the input file contains a character that can not be encoded.
+/// If the input file looks like it has already been encoded [`UnencodableFileError::AlreadyEncoded`]
+/// is returned, since an encoding consists of combining characters that can not be encoded again.
+/// The output file is left untouched in that case.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{encode_file, Normalization};
+/// # let dir = tempfile::tempdir()?;
+/// # let in_file = dir.path().join("in.txt");
+/// # let out_file = dir.path().join("out.txt");
+/// std::fs::write(&in_file, "\tZalgo\n")?;
+/// let stats = encode_file(&in_file, &out_file)?;
+/// assert_eq!((stats.bytes_in, stats.bytes_out, stats.lines), (7, 21, 1));
+/// assert_eq!(stats.normalizations, [Normalization::ExpandedTabs { count: 1 }]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[track_caller]
+pub fn encode_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<FileStats, UnencodableFileError> {
+    encode_file_with_options(in_file, out_file, &FileOptions::new())
+}
+
+/// Encodes the contents of the given file and stores the result in another file,
+/// handling the contents and the output file as determined by the `options`.
+///
+/// See [`encode_file`] for more information.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`encode_file`].
+/// In addition, an error of the kind [`AlreadyExists`](io::ErrorKind::AlreadyExists) is returned
+/// if the output file exists and the options do not allow overwriting it.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{encode_file_with_options, FileOptions, TabHandling, UnencodableFileError};
+/// # let dir = tempfile::tempdir()?;
+/// # let in_file = dir.path().join("in.txt");
+/// # let out_file = dir.path().join("out.txt");
+/// std::fs::write(&in_file, "\tZalgo\n")?;
+/// let options = FileOptions::new().tab_handling(TabHandling::Error);
+/// assert!(matches!(
+///     encode_file_with_options(&in_file, &out_file, &options),
+///     Err(UnencodableFileError::UnencodableContent { .. }),
+/// ));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[track_caller]
+pub fn encode_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<FileStats, UnencodableFileError> {
+    encode_file_with_progress(in_file, out_file, options, |_| ())
+}
+
+/// Encodes the contents of the given file and stores the result in another file in the same way as
+/// [`encode_file_with_options`], and calls `progress` with a [`Progress`] after every chunk of the file.
+///
+/// The callback is called at most once per chunk, and the last call is made once the entire file has been read.
+/// It can not influence the operation, so if it panics the output file is left untouched.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`encode_file_with_options`].
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{encode_file_with_progress, FileOptions};
+/// # let dir = tempfile::tempdir()?;
+/// # let in_file = dir.path().join("in.txt");
+/// # let out_file = dir.path().join("out.txt");
+/// std::fs::write(&in_file, "Zalgo")?;
+/// let mut last = None;
+/// encode_file_with_progress(&in_file, &out_file, &FileOptions::new(), |p| last = Some(p))?;
+/// let last = last.unwrap();
+/// assert_eq!((last.bytes_read, last.bytes_written, last.total_bytes), (5, 11, Some(5)));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[track_caller]
+pub fn encode_file_with_progress<P: AsRef<Path>, Q: AsRef<Path>, F: FnMut(Progress)>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+    mut progress: F,
+) -> Result<FileStats, UnencodableFileError> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "encode_file",
+            level = "debug",
+            skip_all,
+            fields(in_file = %in_file.display(), out_file = %out_file.display()),
+        )
+    )]
+    #[track_caller]
+    fn inner(
+        in_file: &Path,
+        out_file: &Path,
+        options: &FileOptions,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<FileStats, UnencodableFileError> {
+        let in_error = |e: io::Error| UnencodableFileError::from(e).with_path(in_file);
+        let out_error = |e: io::Error| UnencodableFileError::from(e).with_path(out_file);
+        let file = File::open(in_file).map_err(in_error)?;
+        let mut atomic = AtomicFile::create(out_file, options).map_err(out_error)?;
+        if options.preserve_metadata {
+            let metadata = file.metadata().map_err(in_error)?;
+            atomic.copy_metadata(&metadata).map_err(out_error)?;
+        }
+        let mut reporter = ProgressReporter::new(file_size(&file), progress);
+        let reader = BufReader::new(file);
+        let mut writer = BufWriter::new(atomic);
+        let stats = match encode_chunked(reader, &mut writer, CHUNK_SIZE, options, &mut reporter) {
+            Ok(stats) => stats,
+            Err(e) => return Err(e.with_path(failed_path(&writer, in_file, out_file))),
+        };
+        persist(writer).map_err(out_error)?;
+        #[cfg(feature = "tracing")]
+        stats.trace();
+        Ok(stats)
+    }
+
+    inner(in_file.as_ref(), out_file.as_ref(), options, &mut progress)
+}
+
+/// Decodes the contents of a file that has been encoded with [`encode_file`]
+/// and stores the result in another file, overwriting it if it exists.
+///
+/// The file is decoded in chunks, so it never has to be loaded into memory in its entirety.
+/// Every chunk is validated in the same way as in [`ZalgoString::from_encoded`](crate::ZalgoString::from_encoded)
+/// before it is written to the output file.
+///
+/// # Errors
+///
+/// Returns an error if the input file can not be read or the output file can not be written,
+/// or if the contents of the input file is not a valid encoding of printable ASCII and newlines.
+/// In the latter case [`DecodeError::index`](crate::DecodeError::index) returns the byte offset in the input file where the problem was found.
+/// If the problem is found right at the start of the file, e.g. since it does not begin with an "E",
+/// the file was most likely never encoded and [`UndecodableFileError::NotEncoded`] is returned.
+/// The output file is left untouched when an error is returned.
+#[track_caller]
+pub fn decode_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<FileStats, UndecodableFileError> {
+    decode_file_with_options(in_file, out_file, &FileOptions::new())
+}
+
+/// Decodes the contents of a file that has been encoded with [`encode_file`]
+/// and stores the result in another file, which is overwritten only if the `options` allow it.
+///
+/// See [`decode_file`] for more information.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`decode_file`].
+/// In addition, an error of the kind [`AlreadyExists`](io::ErrorKind::AlreadyExists) is returned
+/// if the output file exists and the options do not allow overwriting it.
+#[track_caller]
+pub fn decode_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<FileStats, UndecodableFileError> {
+    decode_file_with_progress(in_file, out_file, options, |_| ())
+}
+
+/// Decodes the contents of a file that has been encoded with [`encode_file`] in the same way as
+/// [`decode_file_with_options`], and calls `progress` with a [`Progress`] after every chunk of the file.
+///
+/// The callback is called in the same way as in [`encode_file_with_progress`].
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`decode_file_with_options`].
+#[track_caller]
+pub fn decode_file_with_progress<P: AsRef<Path>, Q: AsRef<Path>, F: FnMut(Progress)>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+    mut progress: F,
+) -> Result<FileStats, UndecodableFileError> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "decode_file",
+            level = "debug",
+            skip_all,
+            fields(in_file = %in_file.display(), out_file = %out_file.display()),
+        )
+    )]
+    #[track_caller]
+    fn inner(
+        in_file: &Path,
+        out_file: &Path,
+        options: &FileOptions,
+        progress: &mut dyn FnMut(Progress),
+    ) -> Result<FileStats, UndecodableFileError> {
+        let in_error = |e: io::Error| UndecodableFileError::from(e).with_path(in_file);
+        let out_error = |e: io::Error| UndecodableFileError::from(e).with_path(out_file);
+        let file = File::open(in_file).map_err(in_error)?;
+        let mut atomic = AtomicFile::create(out_file, options).map_err(out_error)?;
+        if options.preserve_metadata {
+            let metadata = file.metadata().map_err(in_error)?;
+            atomic.copy_metadata(&metadata).map_err(out_error)?;
+        }
+        let mut reporter = ProgressReporter::new(file_size(&file), progress);
+        let reader = BufReader::new(file);
+        let mut writer = BufWriter::new(atomic);
+        let stats = match decode_chunked(reader, &mut writer, CHUNK_SIZE, &mut reporter) {
+            Ok(stats) => stats,
+            Err(e) => return Err(e.with_path(failed_path(&writer, in_file, out_file))),
+        };
+        persist(writer).map_err(out_error)?;
+        #[cfg(feature = "tracing")]
+        stats.trace();
+        Ok(stats)
+    }
+
+    inner(in_file.as_ref(), out_file.as_ref(), options, &mut progress)
+}
+
+/// Encodes the contents of the given Python source file in the same way as [`zalgo_wrap_python`](crate::zalgo_wrap_python)
+/// and stores the result in another file.
+///
+/// A shebang line and an encoding declaration at the start of the file are kept before the wrapper.
+/// On Unix the output file gets the same permissions as the input file, so an executable script stays executable.
+///
+/// Tabs, carriage returns and the output file are handled in the same way as in [`encode_file`].
+/// Use [`wrap_python_file_with_options`] to change this.
+///
+/// Returns statistics about the operation in the same way as [`encode_file`],
+/// where the output also includes the wrapper.
+///
+/// # Errors
+///
+/// Returns an error if the input file can not be read or the output file can not be written,
+/// or if the input file contains a character that can not be encoded.
+#[track_caller]
+pub fn wrap_python_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<FileStats, UnencodableFileError> {
+    wrap_python_file_with_options(in_file, out_file, &FileOptions::new())
+}
+
+/// Encodes the contents of the given Python source file in the same way as [`zalgo_wrap_python`](crate::zalgo_wrap_python)
+/// and stores the result in another file, handling the contents and the output file as determined by the `options`.
+///
+/// See [`wrap_python_file`] for more information.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`wrap_python_file`].
+/// In addition, an error of the kind [`AlreadyExists`](io::ErrorKind::AlreadyExists) is returned
+/// if the output file exists and the options do not allow overwriting it.
+#[track_caller]
+pub fn wrap_python_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<FileStats, UnencodableFileError> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "wrap_python_file",
+            level = "debug",
+            skip_all,
+            fields(in_file = %in_file.display(), out_file = %out_file.display()),
+        )
+    )]
+    #[track_caller]
+    fn inner(
+        in_file: &Path,
+        out_file: &Path,
+        options: &FileOptions,
+    ) -> Result<FileStats, UnencodableFileError> {
+        let in_error = |e: io::Error| UnencodableFileError::from(e).with_path(in_file);
+        let mut file = File::open(in_file).map_err(in_error)?;
+        let metadata = file.metadata().map_err(in_error)?;
+        let mut head = Vec::new();
+        (&mut file)
+            .take(python::PREAMBLE_LIMIT)
+            .read_to_end(&mut head)
+            .map_err(in_error)?;
+        let (preamble, rest) = python::split_preamble(&head);
+
+        let mut encoded = Vec::new();
+        let result = encode_chunked(
+            BufReader::new(rest.chain(file)),
+            &mut encoded,
+            CHUNK_SIZE,
+            options,
+            &mut ProgressReporter::none(),
+        );
+        let mut stats = match python::include_preamble(result, preamble) {
+            Ok(stats) => stats,
+            Err(e) => return Err(e.with_path(in_file)),
+        };
+
+        let parts = python::wrapped_parts(preamble, &encoded);
+        let write = || {
+            let mut out = AtomicFile::create(out_file, options)?;
+            copy_python_metadata(&mut out, &metadata, options)?;
+            let mut writer = BufWriter::new(out);
+            for part in parts {
+                writer.write_all(part)?;
+            }
+            persist(writer)
+        };
+        write().map_err(|e| UnencodableFileError::from(e).with_path(out_file))?;
+        stats.bytes_out = parts.iter().map(|part| part.len() as u64).sum();
+        #[cfg(feature = "tracing")]
+        stats.trace();
+        Ok(stats)
+    }
+
+    inner(in_file.as_ref(), out_file.as_ref(), options)
+}
+
+/// Recovers the Python source code in a file that was created by [`wrap_python_file`]
+/// with [`zalgo_unwrap_python`] and stores it in another file.
+///
+/// Any lines before the wrapper, like a shebang line, are kept at the start of the output.
+/// On Unix the output file gets the same permissions as the input file.
+/// Note that the tabs and CRLF line endings that were replaced by [`wrap_python_file`] can not be recovered.
+///
+/// # Errors
+///
+/// Returns an error if the input file can not be read or the output file can not be written,
+/// or if the contents of the input file were not produced by [`wrap_python_file`].
+/// The output file is never overwritten, so an error of the kind
+/// [`AlreadyExists`](io::ErrorKind::AlreadyExists) is returned if it already exists.
+/// Use [`unwrap_python_file_with_options`] to change this.
+#[track_caller]
+pub fn unwrap_python_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+) -> Result<(), UndecodableFileError> {
+    unwrap_python_file_with_options(in_file, out_file, &FileOptions::new().overwrite(false))
+}
+
+/// Recovers the Python source code in a file that was created by [`wrap_python_file`]
+/// and stores it in another file, which is overwritten only if the `options` allow it.
+///
+/// See [`unwrap_python_file`] for more information.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`unwrap_python_file`],
+/// except that the output file is overwritten if the options allow it.
+#[track_caller]
+pub fn unwrap_python_file_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    in_file: P,
+    out_file: Q,
+    options: &FileOptions,
+) -> Result<(), UndecodableFileError> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "unwrap_python_file",
+            level = "debug",
+            skip_all,
+            fields(in_file = %in_file.display(), out_file = %out_file.display()),
+        )
+    )]
+    #[track_caller]
+    fn inner(
+        in_file: &Path,
+        out_file: &Path,
+        options: &FileOptions,
+    ) -> Result<(), UndecodableFileError> {
+        let in_error = |e: io::Error| UndecodableFileError::from(e).with_path(in_file);
+        let wrapped = fs::read_to_string(in_file).map_err(in_error)?;
+        let metadata = fs::metadata(in_file).map_err(in_error)?;
+        let python = match zalgo_unwrap_python(&wrapped) {
+            Ok(python) => python,
+            Err(e) => return Err(UndecodableFileError::from(e).with_path(in_file)),
+        };
+        let write = || {
+            let mut out = AtomicFile::create(out_file, options)?;
+            copy_python_metadata(&mut out, &metadata, options)?;
+            out.write_all(python.as_bytes())?;
+            out.persist()
+        };
+        write().map_err(|e| UndecodableFileError::from(e).with_path(out_file))?;
+        Ok(())
+    }
+
+    inner(in_file.as_ref(), out_file.as_ref(), options)
+}
+
+/// Returns the size of the file in bytes, if it is known.
+fn file_size(file: &File) -> Option<u64> {
+    file.metadata()
+        .ok()
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+}
+
+/// Copies the metadata of the input file to the output file if the options say so.
+/// A Python script always keeps its permissions, so that an executable script stays executable.
+fn copy_python_metadata(
+    out: &mut AtomicFile,
+    metadata: &fs::Metadata,
+    options: &FileOptions,
+) -> io::Result<()> {
+    if options.preserve_metadata {
+        out.copy_metadata(metadata)
+    } else {
+        #[cfg(unix)]
+        out.set_permissions(metadata.permissions())?;
+        Ok(())
+    }
+}
+
+/// Returns the path of the output file if writing to it is what failed, and the path of the input file otherwise.
+fn failed_path<'a>(
+    writer: &BufWriter<AtomicFile>,
+    in_file: &'a Path,
+    out_file: &'a Path,
+) -> &'a Path {
+    if writer.get_ref().failed() {
+        out_file
+    } else {
+        in_file
+    }
+}
+
+/// Flushes the buffered writer and moves the written file to its destination.
+fn persist(writer: BufWriter<AtomicFile>) -> io::Result<()> {
+    writer
+        .into_inner()
+        .map_err(io::IntoInnerError::into_error)?
+        .persist()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{zalgo_decode, zalgo_encode, zalgo_wrap_python, DecodeErrorKind};
+    use alloc::{
+        format,
+        string::{String, ToString},
+    };
+    use std::path::PathBuf;
+
+    /// Generates `len` bytes of printable ASCII text split into lines of varying length.
+    pub(super) fn text(len: usize) -> String {
+        let mut text = String::with_capacity(len);
+        let mut state: u32 = 1;
+        while text.len() < len {
+            // A linear congruential generator is random enough to vary the line lengths.
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let line_len = (state >> 16) as usize % 120;
+            for i in 0..line_len {
+                text.push(char::from(b' ' + ((state as usize + i) % 95) as u8));
+            }
+            text.push('\n');
+        }
+        text.truncate(len);
+        text
+    }
+
+    #[test]
+    fn test_encode_large_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_file = dir.path().join("large.txt");
+        let out_file = dir.path().join("large.zalgo");
+
+        let contents = text(3 * 1024 * 1024 + 17);
+        fs::write(&in_file, &contents).unwrap();
+        encode_file(&in_file, &out_file).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&out_file).unwrap(),
+            zalgo_encode(&contents).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original.txt");
+        let encoded = dir.path().join("encoded.txt");
+        let decoded = dir.path().join("decoded.txt");
+
+        let contents = text(3 * CHUNK_SIZE + 17);
+        fs::write(&original, &contents).unwrap();
+        encode_file(&original, &encoded).unwrap();
+        decode_file(&encoded, &decoded).unwrap();
+        assert_eq!(fs::read_to_string(&decoded).unwrap(), contents);
+        assert_eq!(
+            zalgo_decode(&fs::read_to_string(&encoded).unwrap()).unwrap(),
+            contents
+        );
+
+        let mut encoded_contents = fs::read(&encoded).unwrap();
+
+        // Truncate the file in the middle of the final combining character.
+        let truncated = &encoded_contents[..encoded_contents.len() - 1];
+        fs::write(&encoded, truncated).unwrap();
+        let Err(UndecodableFileError::UndecodableContent { error: e, .. }) =
+            decode_file(&encoded, &decoded)
+        else {
+            panic!("a truncated file should not be decodable");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::DanglingByte);
+        assert_eq!(e.index(), Some(truncated.len() - 1));
+
+        // Corrupt a combining character far into the file.
+        let index = 2 * CHUNK_SIZE + 1;
+        encoded_contents[index..index + 2].copy_from_slice("\u{360}".as_bytes());
+        fs::write(&encoded, &encoded_contents).unwrap();
+        let Err(UndecodableFileError::UndecodableContent { error: e, .. }) =
+            decode_file(&encoded, &decoded)
+        else {
+            panic!("a corrupted file should not be decodable");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::NotPrintableAscii);
+        assert_eq!(e.index(), Some(index));
+        assert_eq!(e.char(), Some('\u{360}'));
+        assert!(matches!(
+            decode_file(dir.path().join("missing.txt"), decoded),
+            Err(UndecodableFileError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_wrap_python_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let python = dir.path().join("hello.py");
+        let wrapped = dir.path().join("hello_wrapped.py");
+
+        fs::write(&python, "def hello():\r\n\tprint(\"Hello, world!\")\r\n").unwrap();
+        let stats = wrap_python_file(&python, &wrapped).unwrap();
+        assert_eq!(
+            stats.normalizations,
+            [
+                Normalization::ExpandedTabs { count: 1 },
+                Normalization::StrippedCarriageReturns { count: 2 }
+            ]
+        );
+        assert_eq!(stats.bytes_out, fs::metadata(&wrapped).unwrap().len());
+        assert_eq!(
+            fs::read_to_string(&wrapped).unwrap(),
+            zalgo_wrap_python("def hello():\n    print(\"Hello, world!\")\n").unwrap()
+        );
+
+        fs::write(&python, "print(\"That will be 5\u{20ac} please\")").unwrap();
+        let err = wrap_python_file(&python, &wrapped).unwrap_err();
+        assert!(err.to_string().contains("can not encode '\u{20ac}'"));
+    }
+
+    #[test]
+    fn test_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_file = dir.path().join("in.txt");
+        let out_file = dir.path().join("out.txt");
+        fs::write(&in_file, "Zalgo").unwrap();
+        fs::write(&out_file, "existing").unwrap();
+
+        let options = FileOptions::new().overwrite(false);
+        let Err(UnencodableFileError::Io { error: e, .. }) =
+            encode_file_with_options(&in_file, &out_file, &options)
+        else {
+            panic!("an existing file should not be overwritten");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::AlreadyExists);
+        let Err(UnencodableFileError::Io { error: e, .. }) =
+            wrap_python_file_with_options(&in_file, &out_file, &options)
+        else {
+            panic!("an existing file should not be overwritten");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&out_file).unwrap(), "existing");
+
+        // The default options overwrite the file.
+        assert_eq!(
+            encode_file(&in_file, &out_file).unwrap(),
+            FileStats {
+                bytes_in: 5,
+                bytes_out: 2 * 5 + 1,
+                lines: 1,
+                normalizations: Vec::new()
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(&out_file).unwrap(),
+            zalgo_encode("Zalgo").unwrap()
+        );
+
+        let Err(UndecodableFileError::Io { error: e, .. }) =
+            decode_file_with_options(&out_file, &in_file, &options)
+        else {
+            panic!("an existing file should not be overwritten");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::AlreadyExists);
+        let decoded = dir.path().join("decoded.txt");
+        decode_file_with_options(&out_file, &decoded, &options).unwrap();
+        assert_eq!(fs::read_to_string(&decoded).unwrap(), "Zalgo");
+    }
+
+    #[test]
+    fn test_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let in_file = dir.path().join("in.txt");
+        let encoded = dir.path().join("encoded.txt");
+        let decoded = dir.path().join("decoded.txt");
+        let contents = text(3 * CHUNK_SIZE + 17);
+        let size = contents.len() as u64;
+        fs::write(&in_file, &contents).unwrap();
+
+        let mut calls = Vec::new();
+        encode_file_with_progress(&in_file, &encoded, &FileOptions::new(), |p| calls.push(p))
+            .unwrap();
+        assert!(calls.len() <= 4);
+        assert!(calls
+            .windows(2)
+            .all(|w| w[0].bytes_read < w[1].bytes_read && w[0].bytes_written < w[1].bytes_written));
+        assert!(calls.iter().all(|p| p.total_bytes == Some(size)));
+        let last = calls.last().unwrap();
+        assert_eq!(last.bytes_read, size);
+        assert_eq!(last.bytes_written, 2 * size + 1);
+
+        let mut calls = Vec::new();
+        decode_file_with_progress(&encoded, &decoded, &FileOptions::new(), |p| calls.push(p))
+            .unwrap();
+        assert!(calls
+            .windows(2)
+            .all(|w| w[0].bytes_read < w[1].bytes_read && w[0].bytes_written < w[1].bytes_written));
+        let last = calls.last().unwrap();
+        assert_eq!(
+            (last.bytes_read, last.bytes_written, last.total_bytes),
+            (2 * size + 1, size, Some(2 * size + 1))
+        );
+        assert_eq!(fs::read_to_string(&decoded).unwrap(), contents);
+
+        // The last call is made even if the file is empty.
+        fs::write(&in_file, "").unwrap();
+        let mut calls = Vec::new();
+        encode_file_with_progress(&in_file, &encoded, &FileOptions::new(), |p| calls.push(p))
+            .unwrap();
+        assert_eq!(
+            calls,
+            [Progress {
+                bytes_read: 0,
+                bytes_written: 1,
+                total_bytes: Some(0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_mixed_path_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let original: PathBuf = dir.path().join("original.py");
+        let encoded: &Path = &dir.path().join("encoded.txt");
+        let decoded: String = dir.path().join("decoded.py").to_str().unwrap().to_string();
+        let wrapped_path = dir.path().join("wrapped.py");
+        let wrapped: &str = wrapped_path.to_str().unwrap();
+        let contents = "print(\"Zalgo\")";
+        fs::write(&original, contents).unwrap();
+
+        let options = FileOptions::new();
+        encode_file(&original, encoded).unwrap();
+        decode_file(encoded.to_str().unwrap(), &decoded).unwrap();
+        decode_file_with_options(encoded, decoded.clone(), &options).unwrap();
+        encode_file_with_options(decoded.as_str(), original.clone(), &options).unwrap();
+        decode_file_with_progress(original.as_path(), wrapped_path.clone(), &options, |_| ())
+            .unwrap();
+        encode_file_with_progress(wrapped, encoded, &options, |_| ()).unwrap();
+        wrap_python_file(&decoded, wrapped).unwrap();
+        wrap_python_file_with_options(PathBuf::from(&decoded), encoded, &options).unwrap();
+        unwrap_python_file_with_options(wrapped, &original, &options).unwrap();
+
+        assert_eq!(fs::read_to_string(&original).unwrap(), contents);
+        assert_eq!(fs::read(encoded).unwrap(), fs::read(wrapped).unwrap());
+    }
+
+    #[test]
+    fn test_atomic_writes() {
+        fn file_names(dir: &Path) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect();
+            names.sort();
+            names
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let in_file = dir.path().join("in.txt");
+        let out_file = dir.path().join("out.txt");
+        fs::write(&out_file, "existing").unwrap();
+
+        // The unencodable character comes after more than a chunk of encodable text.
+        let contents = format!("{}\u{e5}", text(2 * CHUNK_SIZE));
+        fs::write(&in_file, &contents).unwrap();
+        assert!(matches!(
+            encode_file(&in_file, &out_file),
+            Err(UnencodableFileError::UnencodableContent { .. })
+        ));
+        assert!(matches!(
+            wrap_python_file(&in_file, &out_file),
+            Err(UnencodableFileError::UnencodableContent { .. })
+        ));
+        assert!(matches!(
+            decode_file(&in_file, &out_file),
+            Err(UndecodableFileError::NotEncoded { .. })
+        ));
+        assert!(matches!(
+            unwrap_python_file_with_options(&in_file, &out_file, &FileOptions::new()),
+            Err(UndecodableFileError::UndecodableContent { .. })
+        ));
+        assert_eq!(fs::read_to_string(&out_file).unwrap(), "existing");
+        assert_eq!(file_names(dir.path()), ["in.txt", "out.txt"]);
+
+        fs::write(&in_file, "Zalgo").unwrap();
+        encode_file(&in_file, &out_file).unwrap();
+        assert_eq!(
+            fs::read_to_string(&out_file).unwrap(),
+            zalgo_encode("Zalgo").unwrap()
+        );
+        assert_eq!(file_names(dir.path()), ["in.txt", "out.txt"]);
+
+        let decoded = dir.path().join("decoded.txt");
+        decode_file(&out_file, &decoded).unwrap();
+        assert_eq!(fs::read_to_string(&decoded).unwrap(), "Zalgo");
+        assert_eq!(file_names(dir.path()), ["decoded.txt", "in.txt", "out.txt"]);
+    }
+
+    #[test]
+    fn test_unwrap_python_file() {
+        const LOREM: &str = "\
+def lorem(words):
+\t\"\"\"Returns the given number of words of lorem ipsum.\"\"\"
+\ttext = \"Lorem ipsum dolor sit amet, consectetur adipiscing elit\"
+\treturn \" \".join(text.split()[:words])\r
+\r
+if __name__ == \"__main__\":
+\tprint(lorem(5))
+";
+        let dir = tempfile::tempdir().unwrap();
+        let python = dir.path().join("lorem.py");
+        let wrapped = dir.path().join("lorem_wrapped.py");
+        let unwrapped = dir.path().join("lorem_unwrapped.py");
+
+        fs::write(&python, LOREM).unwrap();
+        wrap_python_file(&python, &wrapped).unwrap();
+        unwrap_python_file(&wrapped, &unwrapped).unwrap();
+        assert_eq!(
+            fs::read_to_string(&unwrapped).unwrap(),
+            LOREM.replace('\t', "    ").replace("\r\n", "\n")
+        );
+
+        // The output file is not overwritten.
+        let Err(UndecodableFileError::Io { error: e, .. }) = unwrap_python_file(&wrapped, &python)
+        else {
+            panic!("an existing file should not be overwritten");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&python).unwrap(), LOREM);
+        unwrap_python_file_with_options(&wrapped, &unwrapped, &FileOptions::new()).unwrap();
+
+        let Err(UndecodableFileError::UndecodableContent { error: e, .. }) =
+            unwrap_python_file(&python, dir.path().join("not_wrapped.py"))
+        else {
+            panic!("a file that was not wrapped can not be unwrapped");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::NotWrappedPython);
+    }
+
+    #[test]
+    fn test_wrap_python_file_preamble() {
+        let dir = tempfile::tempdir().unwrap();
+        let python = dir.path().join("script.py");
+        let wrapped = dir.path().join("script_wrapped.py");
+        let unwrapped = dir.path().join("script_unwrapped.py");
+        let options = FileOptions::new().overwrite(true);
+
+        for script in [
+            "print(\"Hello, world!\")\n",
+            "#!/usr/bin/env python3\nprint(\"Hello, world!\")\n",
+            "#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\nprint(\"Hello, world!\")\n",
+            "# vim: set fileencoding=utf-8 :\nprint(\"Hello, world!\")\n",
+            "#!/usr/bin/env python3",
+        ] {
+            fs::write(&python, script).unwrap();
+            let stats = wrap_python_file_with_options(&python, &wrapped, &options).unwrap();
+            let expected = zalgo_wrap_python(script).unwrap();
+            assert_eq!(fs::read_to_string(&wrapped).unwrap(), expected);
+            assert_eq!(stats.bytes_in, script.len() as u64);
+            assert_eq!(stats.bytes_out, expected.len() as u64);
+            assert_eq!(stats.lines, script.lines().count() as u64);
+
+            unwrap_python_file_with_options(&wrapped, &unwrapped, &options).unwrap();
+            assert_eq!(
+                fs::read_to_string(&unwrapped).unwrap().trim_end(),
+                script.trim_end()
+            );
+        }
+
+        // The location of an error is in the original file.
+        fs::write(&python, "#!/usr/bin/env python3\nprint(\"\u{e5}\")\n").unwrap();
+        let Err(UnencodableFileError::UnencodableContent { error: e, .. }) =
+            wrap_python_file_with_options(&python, &wrapped, &options)
+        else {
+            panic!("the file can not be encoded");
+        };
+        assert_eq!((e.line(), e.column(), e.index()), (2, 8, 30));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_python_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let python = dir.path().join("script.py");
+        let wrapped = dir.path().join("script_wrapped.py");
+        let unwrapped = dir.path().join("script_unwrapped.py");
+        fs::write(
+            &python,
+            "#!/usr/bin/env python3\nprint(\"Hello, world!\")\n",
+        )
+        .unwrap();
+        fs::set_permissions(&python, fs::Permissions::from_mode(0o755)).unwrap();
+
+        wrap_python_file(&python, &wrapped).unwrap();
+        unwrap_python_file(&wrapped, &unwrapped).unwrap();
+        for file in [wrapped, unwrapped] {
+            assert_eq!(
+                fs::metadata(file).unwrap().permissions().mode() & 0o777,
+                0o755
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_preserve_metadata() {
+        use std::{os::unix::fs::PermissionsExt, time::SystemTime};
+
+        let dir = tempfile::tempdir().unwrap();
+        let python = dir.path().join("script.py");
+        let wrapped = dir.path().join("script_wrapped.py");
+        let encoded = dir.path().join("script.py.zalgo");
+        fs::write(
+            &python,
+            "#!/usr/bin/env python3\nprint(\"Hello, world!\")\n",
+        )
+        .unwrap();
+        fs::set_permissions(&python, fs::Permissions::from_mode(0o755)).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + core::time::Duration::from_secs(1_000_000_000);
+        File::options()
+            .write(true)
+            .open(&python)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let options = FileOptions::new().preserve_metadata(true);
+        wrap_python_file_with_options(&python, &wrapped, &options).unwrap();
+        encode_file_with_options(&python, &encoded, &options).unwrap();
+        for file in [&wrapped, &encoded] {
+            let metadata = fs::metadata(file).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+            assert_eq!(metadata.modified().unwrap(), modified);
+        }
+
+        // Only the permissions of a Python script are kept by default.
+        let options = FileOptions::new();
+        wrap_python_file_with_options(&python, &wrapped, &options).unwrap();
+        encode_file_with_options(&python, &encoded, &options).unwrap();
+        assert_eq!(
+            fs::metadata(&wrapped).unwrap().permissions().mode() & 0o777,
+            0o755
+        );
+        for file in [&wrapped, &encoded] {
+            assert_ne!(fs::metadata(file).unwrap().modified().unwrap(), modified);
+        }
+    }
+
+    #[test]
+    fn test_error_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.txt");
+        let in_file = dir.path().join("in.txt");
+        let out_file = dir.path().join("out.txt");
+
+        let e = encode_file(&missing, &out_file).unwrap_err();
+        assert_eq!(e.path(), Some(missing.as_path()));
+        assert!(e
+            .to_string()
+            .starts_with(&format!("failed to encode `{}`: ", missing.display())));
+        assert!(!out_file.exists());
+
+        fs::write(&in_file, "Z\u{e5}lgo").unwrap();
+        let e = encode_file(&in_file, &out_file).unwrap_err();
+        assert!(matches!(e, UnencodableFileError::UnencodableContent { .. }));
+        assert_eq!(e.path(), Some(in_file.as_path()));
+        assert!(e.to_string().contains(&in_file.display().to_string()));
+        let e = wrap_python_file(&in_file, &out_file).unwrap_err();
+        assert_eq!(e.path(), Some(in_file.as_path()));
+
+        // Errors that are caused by the output file name it instead.
+        fs::write(&in_file, "Zalgo").unwrap();
+        fs::write(&out_file, "existing").unwrap();
+        let options = FileOptions::new().overwrite(false);
+        let e = encode_file_with_options(&in_file, &out_file, &options).unwrap_err();
+        assert_eq!(e.path(), Some(out_file.as_path()));
+
+        let e = decode_file(&in_file, &out_file).unwrap_err();
+        assert!(matches!(e, UndecodableFileError::NotEncoded { .. }));
+        assert_eq!(e.path(), Some(in_file.as_path()));
+        assert!(e
+            .to_string()
+            .starts_with(&format!("failed to decode `{}`: ", in_file.display())));
+
+        let e = FileError::from(unwrap_python_file(&missing, &out_file).unwrap_err());
+        assert_eq!(e.path(), Some(missing.as_path()));
+
+        // The string and stream functions have no paths to report.
+        let e =
+            encode_stream("Z\u{e5}lgo".as_bytes(), Vec::new(), &FileOptions::new()).unwrap_err();
+        assert_eq!(e.path(), None);
+        assert!(!e.to_string().starts_with("failed to encode"));
+    }
+
+    #[test]
+    fn test_already_encoded() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original.txt");
+        let encoded = dir.path().join("encoded.txt");
+        let twice = dir.path().join("twice.txt");
+        let contents = text(2 * CHUNK_SIZE);
+        fs::write(&original, &contents).unwrap();
+        encode_file(&original, &encoded).unwrap();
+
+        let e = encode_file(&encoded, &twice).unwrap_err();
+        assert!(matches!(e, UnencodableFileError::AlreadyEncoded { .. }));
+        assert_eq!(e.path(), Some(encoded.as_path()));
+        assert!(e.to_string().ends_with("the contents are already encoded"));
+        assert!(matches!(
+            wrap_python_file(&encoded, &twice),
+            Err(UnencodableFileError::AlreadyEncoded { .. })
+        ));
+        assert!(!twice.exists());
+
+        // Text that merely starts with an "E" and a combining character is not mistaken for an encoding.
+        fs::write(&original, "E\u{301}tude").unwrap();
+        let Err(UnencodableFileError::UnencodableContent { error: e, .. }) =
+            encode_file(&original, &twice)
+        else {
+            panic!("the combining character should not be encodable");
+        };
+        assert_eq!((e.char(), e.index()), ('\u{301}', 1));
+
+        // Plain text is rejected at the start, even if it contains the byte pattern of an encoding later on.
+        fs::write(
+            &original,
+            format!("Zalgo{}", zalgo_encode("Zalgo").unwrap()),
+        )
+        .unwrap();
+        let Err(UndecodableFileError::NotEncoded { error: e, .. }) = decode_file(&original, &twice)
+        else {
+            panic!("plain text is not encoded");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::MissingLeadingE);
+        fs::write(&original, "Etude").unwrap();
+        let Err(UndecodableFileError::NotEncoded { error: e, .. }) = decode_file(&original, &twice)
+        else {
+            panic!("plain text is not encoded");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::NotCombiningChar);
+        assert!(!twice.exists());
+
+        // Problems further into an encoding are reported as before.
+        let mut corrupted = zalgo_encode(&contents).unwrap().into_bytes();
+        corrupted.truncate(corrupted.len() - 1);
+        fs::write(&original, corrupted).unwrap();
+        assert!(matches!(
+            decode_file(&original, &twice),
+            Err(UndecodableFileError::UndecodableContent { .. })
+        ));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing() {
+        use std::sync::{Arc, Mutex};
+
+        /// Collects everything that the subscriber writes.
+        #[derive(Clone, Default)]
+        struct Logs(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Logs {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original.txt");
+        let encoded = dir.path().join("encoded.txt");
+        fs::write(&original, "\tZalgo\r\n\tHe comes\r\n").unwrap();
+
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+        let stats = tracing::subscriber::with_default(subscriber, || {
+            encode_file(&original, &encoded).unwrap()
+        });
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = logs.lines().collect();
+        assert_eq!(lines.len(), 3, "{logs}");
+        for line in &lines {
+            assert!(line.contains("DEBUG"), "{line}");
+            assert!(
+                line.contains(&format!("in_file={}", original.display())),
+                "{line}"
+            );
+            assert!(
+                line.contains(&format!("out_file={}", encoded.display())),
+                "{line}"
+            );
+        }
+        assert!(lines[0].contains("replaced tabs with spaces count=2"));
+        assert!(lines[1].contains("stripped carriage returns from CRLF line endings count=2"));
+        assert!(lines[2].contains(&format!(
+            "finished bytes_in={} bytes_out={} lines=2",
+            stats.bytes_in, stats.bytes_out
+        )));
+
+        // Nothing is logged below the `debug` level.
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            encode_file(&original, &encoded).unwrap();
+        });
+        assert!(logs.0.lock().unwrap().is_empty());
+    }
+}
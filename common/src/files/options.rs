@@ -0,0 +1,151 @@
+//! Contains [`FileOptions`], which configures the functions in the [`files`](super) module,
+//! as well as the types that describe how the contents of a file were changed to make it encodable.
+
+use alloc::borrow::Cow;
+
+/// Determines what happens to the tabs in a file that is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TabHandling {
+    /// Tabs can not be encoded, so they result in an [`EncodeError`](crate::EncodeError).
+    Error,
+    /// Every tab is replaced by the given number of spaces before it is encoded.
+    ExpandTo(usize),
+}
+
+/// Determines what happens to the carriage returns in a file that is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CarriageReturns {
+    /// Carriage returns can not be encoded, so they result in an [`EncodeError`](crate::EncodeError).
+    Error,
+    /// CRLF line endings are turned into LF line endings by removing the carriage return.
+    /// A carriage return that is not followed by a line feed still results in an error.
+    Strip,
+}
+
+/// A change that was made to the contents of a file to make it encodable.
+///
+/// The functions in the [`files`](super) module return these instead of printing them,
+/// so that the caller can decide whether and how to report them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Normalization {
+    /// The given number of tabs were replaced by spaces.
+    ExpandedTabs {
+        /// The number of tabs that were replaced.
+        count: usize,
+    },
+    /// The given number of CRLF line endings were replaced by LF line endings.
+    StrippedCarriageReturns {
+        /// The number of line endings that were replaced.
+        count: usize,
+    },
+}
+
+/// Options that determine how the functions in the [`files`](super) module
+/// treat the contents of their input files and their output files.
+///
+/// The default options are the ones used by [`encode_file`](super::encode_file):
+/// tabs are replaced by four spaces, CRLF line endings are replaced by LF line endings,
+/// an existing output file is overwritten, the metadata of the input file is not copied, symbolic links are not followed,
+/// and [`encode_files`](super::encode_files) names its output files by appending `.zalgo` to the names of the input files.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{CarriageReturns, FileOptions, TabHandling};
+/// let options = FileOptions::new()
+///     .tab_handling(TabHandling::ExpandTo(2))
+///     .carriage_returns(CarriageReturns::Error)
+///     .overwrite(false);
+/// assert_ne!(options, FileOptions::default());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FileOptions {
+    pub(super) tab_handling: TabHandling,
+    pub(super) carriage_returns: CarriageReturns,
+    pub(super) overwrite: bool,
+    pub(super) preserve_metadata: bool,
+    pub(super) follow_symlinks: bool,
+    pub(super) suffix: Cow<'static, str>,
+}
+
+impl FileOptions {
+    /// Returns the default options, see the documentation of [`FileOptions`].
+    #[inline]
+    #[must_use = "this associated method returns a new `FileOptions` and does nothing else"]
+    pub const fn new() -> Self {
+        Self {
+            tab_handling: TabHandling::ExpandTo(4),
+            carriage_returns: CarriageReturns::Strip,
+            overwrite: true,
+            preserve_metadata: false,
+            follow_symlinks: false,
+            suffix: Cow::Borrowed(".zalgo"),
+        }
+    }
+
+    /// Sets what happens to the tabs in the input file.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn tab_handling(mut self, tab_handling: TabHandling) -> Self {
+        self.tab_handling = tab_handling;
+        self
+    }
+
+    /// Sets what happens to the carriage returns in the input file.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn carriage_returns(mut self, carriage_returns: CarriageReturns) -> Self {
+        self.carriage_returns = carriage_returns;
+        self
+    }
+
+    /// Sets whether an existing output file is overwritten.
+    ///
+    /// If this is `false` and the output file already exists, the functions return
+    /// an error of the kind [`AlreadyExists`](std::io::ErrorKind::AlreadyExists).
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// Sets whether the output file gets the modification time and, on Unix, the permissions of the input file.
+    ///
+    /// This keeps the output of a build that encodes its files reproducible,
+    /// and keeps an executable input file executable.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn preserve_metadata(mut self, preserve_metadata: bool) -> Self {
+        self.preserve_metadata = preserve_metadata;
+        self
+    }
+
+    /// Sets whether [`encode_dir`](super::encode_dir) and [`decode_dir`](super::decode_dir)
+    /// follow symbolic links in the input directory. If they do not, the links are skipped.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Sets the suffix that [`encode_files`](super::encode_files) appends to the names of the files it creates,
+    /// and that [`decode_files`](super::decode_files) removes from them.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn suffix<S: Into<Cow<'static, str>>>(mut self, suffix: S) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+}
+
+impl Default for FileOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,55 @@
+//! Contains [`Progress`], which is passed to the callbacks of the `_with_progress` functions in the [`files`](super) module.
+
+/// Describes how far along a file operation is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Progress {
+    /// The number of bytes that have been read from the input file so far.
+    pub bytes_read: u64,
+    /// The number of bytes that have been written to the output file so far.
+    pub bytes_written: u64,
+    /// The size of the input file in bytes, if it is known.
+    pub total_bytes: Option<u64>,
+}
+
+/// Passes the progress of a chunked operation to a callback, if there is one.
+pub(super) struct ProgressReporter<'a> {
+    callback: Option<&'a mut dyn FnMut(Progress)>,
+    total_bytes: Option<u64>,
+    last: Option<Progress>,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub(super) fn new(total_bytes: Option<u64>, callback: &'a mut dyn FnMut(Progress)) -> Self {
+        Self {
+            callback: Some(callback),
+            total_bytes,
+            last: None,
+        }
+    }
+
+    /// Returns a reporter that does not report anything.
+    pub(super) fn none() -> Self {
+        Self {
+            callback: None,
+            total_bytes: None,
+            last: None,
+        }
+    }
+
+    /// Calls the callback with the given byte counts, unless they are the same as in the previous call.
+    pub(super) fn report(&mut self, bytes_read: u64, bytes_written: u64) {
+        let Some(callback) = self.callback.as_mut() else {
+            return;
+        };
+        let progress = Progress {
+            bytes_read,
+            bytes_written,
+            total_bytes: self.total_bytes,
+        };
+        if self.last != Some(progress) {
+            callback(progress);
+            self.last = Some(progress);
+        }
+    }
+}
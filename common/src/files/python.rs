@@ -0,0 +1,58 @@
+//! Contains the parts of wrapping a Python file that are shared by the blocking and the asynchronous functions.
+
+use super::{FileStats, UnencodableFileError};
+use crate::{python_preamble_len, PYTHON_WRAPPER_PREFIX, PYTHON_WRAPPER_SUFFIX};
+
+/// The number of bytes at the start of a Python file that are searched for the lines
+/// that [`zalgo_wrap_python`](crate::zalgo_wrap_python) keeps before the wrapper.
+pub(super) const PREAMBLE_LIMIT: u64 = 1024;
+
+/// Splits `head`, which holds at most the first [`PREAMBLE_LIMIT`] bytes of a Python file,
+/// into the lines that are kept before the wrapper and the start of the code that is encoded.
+pub(super) fn split_preamble(head: &[u8]) -> (&[u8], &[u8]) {
+    let complete = (head.len() as u64) < PREAMBLE_LIMIT;
+    head.split_at(python_preamble_len(head, complete))
+}
+
+/// Returns the parts of the wrapped file in the order they should be written.
+pub(super) fn wrapped_parts<'a>(preamble: &'a [u8], encoded: &'a [u8]) -> [&'a [u8]; 5] {
+    // The wrapper must begin on a line of its own.
+    let separator: &[u8] = if preamble.is_empty() || preamble.ends_with(b"\n") {
+        b""
+    } else {
+        b"\n"
+    };
+    [
+        preamble,
+        separator,
+        PYTHON_WRAPPER_PREFIX.as_bytes(),
+        encoded,
+        PYTHON_WRAPPER_SUFFIX.as_bytes(),
+    ]
+}
+
+/// Turns the result of encoding the code after the preamble into one that describes the whole file.
+///
+/// The number of bytes that are written is not changed, since that depends on the [`wrapped_parts`].
+pub(super) fn include_preamble(
+    result: Result<FileStats, UnencodableFileError>,
+    preamble: &[u8],
+) -> Result<FileStats, UnencodableFileError> {
+    let newlines = preamble.iter().filter(|&&byte| byte == b'\n').count();
+    match result {
+        Ok(mut stats) => {
+            stats.bytes_in += preamble.len() as u64;
+            // A preamble without a final line ending is the entire file, so it is a line of its own.
+            stats.lines +=
+                (newlines + usize::from(!preamble.is_empty() && !preamble.ends_with(b"\n"))) as u64;
+            Ok(stats)
+        }
+        Err(UnencodableFileError::UnencodableContent { error, path }) => {
+            Err(UnencodableFileError::UnencodableContent {
+                error: error.offset(newlines, preamble.len()),
+                path,
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,43 @@
+//! Contains [`FileStats`], which is returned by the functions in the [`files`](super) module when they succeed.
+
+use super::Normalization;
+
+use alloc::vec::Vec;
+
+/// Statistics about a file that was successfully encoded or decoded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct FileStats {
+    /// The number of bytes that were read from the input file.
+    pub bytes_in: u64,
+    /// The number of bytes that were written to the output file.
+    pub bytes_out: u64,
+    /// The number of lines in the decoded text, where a final line without a line ending is also counted.
+    pub lines: u64,
+    /// The changes that were made to the contents of the input file to make it encodable.
+    /// This is always empty when decoding.
+    pub normalizations: Vec<Normalization>,
+}
+
+#[cfg(feature = "tracing")]
+impl FileStats {
+    /// Emits `debug` level events with these statistics in the current span.
+    pub(super) fn trace(&self) {
+        for normalization in &self.normalizations {
+            match normalization {
+                Normalization::ExpandedTabs { count } => {
+                    tracing::debug!(count, "replaced tabs with spaces");
+                }
+                Normalization::StrippedCarriageReturns { count } => {
+                    tracing::debug!(count, "stripped carriage returns from CRLF line endings");
+                }
+            }
+        }
+        tracing::debug!(
+            bytes_in = self.bytes_in,
+            bytes_out = self.bytes_out,
+            lines = self.lines,
+            "finished"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Contains [`encode_stream`] and [`decode_stream`], which work on any reader and writer instead of on files.
+
+use super::{
+    chunked::{decode_chunked, encode_chunked},
+    progress::ProgressReporter,
+    FileError, FileOptions, FileStats, CHUNK_SIZE,
+};
+
+use std::io::{Read, Write};
+
+/// Encodes everything that can be read from `reader` and writes the result to `writer`.
+///
+/// This is what [`encode_file_with_options`](super::encode_file_with_options) does with its files,
+/// so the input is read in chunks and tabs and carriage returns are handled as determined by the `options`.
+/// Since no file is created, [`FileOptions::overwrite`] has no effect.
+///
+/// It is not necessary to wrap the reader or the writer in a [`BufReader`](std::io::BufReader)
+/// or [`BufWriter`](std::io::BufWriter), since they are read from and written to in large chunks.
+///
+/// # Errors
+///
+/// Returns an error if `reader` can not be read from or `writer` can not be written to,
+/// or if the input contains a character that can not be encoded.
+/// In that case `writer` may have been partially written to.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{files::{encode_stream, FileOptions}, zalgo_encode};
+/// let mut encoded = Vec::new();
+/// let stats = encode_stream("Zalgo\r\n".as_bytes(), &mut encoded, &FileOptions::new())?;
+/// assert_eq!(encoded, zalgo_encode("Zalgo\n")?.into_bytes());
+/// assert_eq!(stats.lines, 1);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+#[track_caller]
+pub fn encode_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    options: &FileOptions,
+) -> Result<FileStats, FileError> {
+    match encode_chunked(
+        reader,
+        writer,
+        CHUNK_SIZE,
+        options,
+        &mut ProgressReporter::none(),
+    ) {
+        Ok(stats) => {
+            #[cfg(feature = "tracing")]
+            stats.trace();
+            Ok(stats)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Decodes everything that can be read from `reader` and writes the result to `writer`.
+///
+/// This is what [`decode_file`](super::decode_file) does with its files,
+/// so the input is read in chunks and validated before it is written.
+/// The options do not affect decoding, but are taken for symmetry with [`encode_stream`].
+///
+/// # Errors
+///
+/// Returns an error if `reader` can not be read from or `writer` can not be written to,
+/// or if the input is not a valid encoding of printable ASCII and newlines.
+/// In that case `writer` may have been partially written to.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::files::{decode_stream, encode_stream, FileOptions};
+/// let mut encoded = Vec::new();
+/// encode_stream("Zalgo".as_bytes(), &mut encoded, &FileOptions::new())?;
+/// let mut decoded = Vec::new();
+/// decode_stream(encoded.as_slice(), &mut decoded, &FileOptions::new())?;
+/// assert_eq!(decoded, b"Zalgo");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+#[track_caller]
+pub fn decode_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    _options: &FileOptions,
+) -> Result<FileStats, FileError> {
+    match decode_chunked(reader, writer, CHUNK_SIZE, &mut ProgressReporter::none()) {
+        Ok(stats) => {
+            #[cfg(feature = "tracing")]
+            stats.trace();
+            Ok(stats)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{files::Normalization, zalgo_encode, DecodeErrorKind};
+    use alloc::{string::String, vec::Vec};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_streams_match_string_api() {
+        let contents = "fn main() {\r\n    println!(\"Zalgo\");\r\n}\r\n";
+        let expected = zalgo_encode(&contents.replace("\r\n", "\n")).unwrap();
+
+        let mut encoded = Cursor::new(Vec::new());
+        let stats =
+            encode_stream(Cursor::new(contents), &mut encoded, &FileOptions::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(encoded.get_ref().clone()).unwrap(),
+            expected
+        );
+        assert_eq!(
+            stats.normalizations,
+            [Normalization::StrippedCarriageReturns { count: 3 }]
+        );
+
+        encoded.set_position(0);
+        let mut decoded = Cursor::new(Vec::new());
+        let stats = decode_stream(&mut encoded, &mut decoded, &FileOptions::new()).unwrap();
+        assert_eq!(
+            String::from_utf8(decoded.into_inner()).unwrap(),
+            contents.replace("\r\n", "\n")
+        );
+        assert_eq!(stats.lines, 3);
+
+        let Err(FileError::UnencodableContent { error: e, .. }) =
+            encode_stream(Cursor::new("Z\u{e5}lgo"), Vec::new(), &FileOptions::new())
+        else {
+            panic!("'\u{e5}' should not be encodable");
+        };
+        assert_eq!((e.char(), e.index()), ('\u{e5}', 1));
+
+        let Err(FileError::NotEncoded { error: e, .. }) =
+            decode_stream(Cursor::new("Zalgo"), Vec::new(), &FileOptions::new())
+        else {
+            panic!("unencoded text should not be decodable");
+        };
+        assert_eq!(e.kind(), DecodeErrorKind::MissingLeadingE);
+    }
+}
@@ -47,6 +47,15 @@
 //! assert_eq!(zstr.into_decoded_string(), "Zalgo");
 //! # Ok::<(), EncodeError>(())
 //! ```
+//! The [`ZalgoArrayString`] type stores the encoded string inline in an array of a fixed capacity instead,
+//! and none of its methods allocate:
+//! ```
+//! # use zalgo_codec_common::{ArrayEncodeError, ZalgoArrayString};
+//! let zstr = ZalgoArrayString::<11>::new("Zalgo")?;
+//! assert!(zstr.decoded_chars().eq("Zalgo".chars()));
+//! assert!(ZalgoArrayString::<11>::new("Zalgo!").is_err());
+//! # Ok::<(), ArrayEncodeError>(())
+//! ```
 //!
 //! # Feature flags
 //!
@@ -54,9 +63,65 @@
 //! If this feature is not enabled the library is `no_std` compatible, but still uses the `alloc` crate.
 //!
 //! `serde`: derives the [`serde::Serialize`] and [`serde::Deserialize`] traits
-//! from [`serde`] for [`ZalgoString`].
+//! from [`serde`] for [`ZalgoString`], and implements [`serde::Serialize`] for [`EncodeError`] and [`DecodeError`].
+//! Deserialization of a [`ZalgoString`] validates the string in the same way as [`ZalgoString::from_encoded`].
+//! Also adds the [`serde_support`] module with helpers for `#[serde(with = "...")]` that store a plain string encoded,
+//! or a [`ZalgoString`] as its decoded string.
+//!
+//! `serde-compact`: makes [`ZalgoString`] serialize its decoded string instead of its encoded string
+//! in formats that are not human-readable, like postcard or bincode, which halves the size of the payload.
+//! Deserialization from such formats encodes the string again. Human-readable formats like JSON are not affected.
+//! This changes the serialization format, so data serialized with and without this feature are not compatible. Enables `serde`.
 //!
 //! `rkyv`: derives the [`rkyv::Serialize`], [`rkyv::Deserialize`], and [`rkyv::Archive`] traits from [`rkyv`] for [`ZalgoString`].
+//! Accessing an archived [`ZalgoString`] with validation, e.g. with [`rkyv::access`], also validates the string
+//! in the same way as [`ZalgoString::from_encoded`].
+//! The archived type has the `decoded_len`, `decoded_bytes`, `decoded_chars`, and `as_combining_chars` methods,
+//! so it can be decoded without being deserialized.
+//!
+//! `borsh`: implements the [`borsh::BorshSerialize`] and [`borsh::BorshDeserialize`] traits from [`borsh`] for [`ZalgoString`].
+//! A [`ZalgoString`] is serialized like a [`String`], and deserialization validates the string
+//! in the same way as [`ZalgoString::from_encoded`].
+//!
+//! `schemars`: implements the [`schemars::JsonSchema`] trait from [`schemars`] for [`ZalgoString`].
+//! It is described as a string with the format `"zalgo"` and a pattern that matches an "E" followed by combining characters.
+//!
+//! `defmt`: implements the [`defmt::Format`] trait from [`defmt`] for [`ZalgoString`], [`EncodeError`], [`DecodeError`],
+//! and their kinds, for logging on embedded devices. Does not need `std`.
+//!
+//! `arbitrary`: implements the [`arbitrary::Arbitrary`] trait from [`arbitrary`] for [`ZalgoString`], for use in fuzzing.
+//! Every byte of the input is mapped to a printable ASCII character or a newline, so every input produces a valid value.
+//!
+//! `proptest`: adds the [`proptest`](crate::proptest) module with [`proptest`](https://docs.rs/proptest) strategies
+//! that generate encodable text and [`ZalgoString`]s. Enables `std`.
+//!
+//! `quickcheck`: implements the [`quickcheck::Arbitrary`] trait from [`quickcheck`] for [`ZalgoString`].
+//! Shrinking removes decoded characters, so every shrunk value is valid. Enables `std`.
+//!
+//! `zeroize`: implements the [`zeroize::Zeroize`] trait from [`zeroize`] for [`ZalgoString`], so that it can be
+//! wrapped in [`Zeroizing`](zeroize::Zeroizing) to scrub it from memory when it is dropped.
+//! Decoding a [`ZalgoString`] in-place also zeroes the part of the buffer that is no longer used.
+//!
+//! `sqlx`: implements the [`sqlx::Type`], [`sqlx::Encode`], and [`sqlx::Decode`] traits from [`sqlx`] for [`ZalgoString`]
+//! for every database where [`String`] implements them, so that it can be stored in text columns.
+//! Decoding validates the string in the same way as [`ZalgoString::from_encoded`]
+//! and fails with the [`DecodeError`] if it is invalid. Enables `std`.
+//! The `sqlx-postgres` and `sqlx-sqlite` features additionally enable the corresponding database backends of `sqlx`.
+//!
+//! `segmentation`: adds the `EncodeError::grapheme` and `EncodeError::grapheme_range` methods that
+//! find the full grapheme cluster that contains an unencodable character.
+//!
+//! `diagnostics`: implements [`miette::Diagnostic`] for [`EncodeError`] and [`DecodeError`],
+//! and adds the `EncodeErrorWithSource` type that can show the source of an [`EncodeError`]. Enables `std`.
+//!
+//! `files`: adds the [`files`] module with functions that encode and decode files. Enables `std`.
+//!
+//! `async-io`: adds the `files::async_io` module with asynchronous versions of the functions in the [`files`] module
+//! that are built on [`tokio`](https://docs.rs/tokio). Enables `files`.
+//!
+//! `tracing`: instruments the functions in the [`files`] module with spans and `debug` level events from
+//! [`tracing`](https://docs.rs/tracing) that report the paths, the number of bytes read and written,
+//! and the tabs and carriage returns that were replaced. Enables `files`.
 //!
 //! # Explanation
 //!
@@ -180,14 +245,29 @@
 extern crate std;
 
 extern crate alloc;
-use alloc::{format, string::String, vec, vec::Vec};
+use alloc::{borrow::Cow, format, string::String, vec, vec::Vec};
 use core::{fmt, str};
 
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
 mod error;
+#[cfg(feature = "files")]
+pub mod files;
+mod normalization;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "serde")]
+pub mod serde_support;
+pub mod zalgo_array_string;
 pub mod zalgo_string;
 
-pub use error::{DecodeError, EncodeError};
-pub use zalgo_string::ZalgoString;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::EncodeErrorWithSource;
+use error::DecodeErrorCause;
+pub use error::{ArrayEncodeError, DecodeError, DecodeErrorKind, EncodeError, EncodeErrorKind};
+pub use normalization::{is_normalization_stable, zalgo_decode_normalized};
+pub use zalgo_array_string::ZalgoArrayString;
+pub use zalgo_string::{DecodedOrd, ZalgoString};
 
 /// Takes in a string slice that consists of only printable ACII and newline characters
 /// and encodes it into a single grapheme cluster using a reversible encoding scheme.
@@ -220,6 +300,7 @@ pub use zalgo_string::ZalgoString;
 /// assert!(zalgo_encode("Zålgö").is_err());
 /// ```
 #[must_use = "the function returns a new value and does not modify the input"]
+#[track_caller]
 pub fn zalgo_encode(string: &str) -> Result<String, EncodeError> {
     // We will encode this many bytes at a time before pushing onto the result vector.
     const BATCH_SIZE: usize = 16;
@@ -247,9 +328,7 @@ pub fn zalgo_encode(string: &str) -> Result<String, EncodeError> {
                     column = 0;
                 }
 
-                let v = ((i16::from(*byte) - 11).rem_euclid(133) - 21) as u8;
-                buffer[encoded] = (v >> 6) & 1 | 0b1100_1100;
-                buffer[encoded + 1] = (v & 63) | 0b1000_0000;
+                buffer[encoded..encoded + 2].copy_from_slice(&encode_byte(*byte));
                 encoded += 2;
                 column += 1;
             } else {
@@ -274,6 +353,35 @@ pub fn zalgo_encode(string: &str) -> Result<String, EncodeError> {
     Ok(unsafe { String::from_utf8_unchecked(result) })
 }
 
+/// Encodes the string with [`zalgo_encode`] and attaches the given label to the error if that fails.
+///
+/// This is useful when encoding many strings, since the label can tell you which of them failed.
+/// See [`EncodeError::with_context`] for more information.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`zalgo_encode`].
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::zalgo_encode_named;
+/// let err = zalgo_encode_named("title", "Zålgo").unwrap_err();
+/// assert_eq!(err.context(), Some("title"));
+/// assert!(err.to_string().starts_with("title: "));
+/// ```
+#[must_use = "the function returns a new value and does not modify the input"]
+#[track_caller]
+pub fn zalgo_encode_named(
+    label: impl Into<Cow<'static, str>>,
+    string: &str,
+) -> Result<String, EncodeError> {
+    match zalgo_encode(string) {
+        Ok(encoded) => Ok(encoded),
+        Err(e) => Err(e.with_context(label)),
+    }
+}
+
 /// Takes in a string that was encoded by [`zalgo_encode`] and decodes it back into an ASCII string.
 ///
 /// # Errors
@@ -304,9 +412,10 @@ pub fn zalgo_encode(string: &str) -> Result<String, EncodeError> {
 /// # Ok::<(), DecodeError>(())
 /// ```
 #[must_use = "the function returns a new value and does not modify the input"]
+#[track_caller]
 pub fn zalgo_decode(encoded: &str) -> Result<String, DecodeError> {
     if encoded.is_empty() {
-        return Err(DecodeError::new(None));
+        return Err(DecodeError::new(DecodeErrorCause::EmptyInput));
     }
     let mut res = vec![0; (encoded.len() - 1) / 2];
     let bytes = encoded.as_bytes();
@@ -318,7 +427,95 @@ pub fn zalgo_decode(encoded: &str) -> Result<String, DecodeError> {
         }
     }
 
-    String::from_utf8(res).map_err(|e| DecodeError::new(Some(e)))
+    match String::from_utf8(res) {
+        Ok(decoded) => Ok(decoded),
+        Err(e) => Err(DecodeError::new(DecodeErrorCause::InvalidUtf8(e))),
+    }
+}
+
+/// Returns whether the given string is a valid output of [`zalgo_encode`],
+/// meaning that it can be decoded with [`zalgo_decode`] without an error.
+///
+/// That is: whether it begins with an "E" followed only by combining characters
+/// that decode into printable ASCII characters or newlines.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{is_zalgo_encoded, zalgo_encode};
+/// assert!(is_zalgo_encoded(&zalgo_encode("Zalgo")?));
+/// assert!(!is_zalgo_encoded("Zalgo"));
+/// # Ok::<(), zalgo_codec_common::EncodeError>(())
+/// ```
+#[must_use = "the function returns a new value and does not modify the input"]
+pub fn is_zalgo_encoded(string: &str) -> bool {
+    validate_encoded(string).is_ok()
+}
+
+/// Checks that the given string is a valid output of [`zalgo_encode`].
+///
+/// That is: that it begins with an "E" followed only by combining characters
+/// that decode into printable ASCII characters or newlines.
+/// Since the leading "E" takes up one byte and every combining character takes up two,
+/// a valid string always has an odd length in bytes.
+#[track_caller]
+pub(crate) fn validate_encoded(encoded: &str) -> Result<(), DecodeError> {
+    let Some(first) = encoded.chars().next() else {
+        return Err(DecodeError::new(DecodeErrorCause::EmptyInput));
+    };
+    if first != 'E' {
+        return Err(DecodeError::new(DecodeErrorCause::MissingLeadingE {
+            found: first,
+        }));
+    }
+
+    validate_combining_chars(&encoded[1..], 1)
+}
+
+/// Checks that the given string consists only of combining characters
+/// that decode into printable ASCII characters or newlines.
+///
+/// The `offset` is added to the indices reported in the errors, so that they can refer to
+/// a position in a larger string that `combining_chars` is a part of.
+#[track_caller]
+pub(crate) fn validate_combining_chars(
+    combining_chars: &str,
+    offset: usize,
+) -> Result<(), DecodeError> {
+    if combining_chars.len() % 2 != 0 {
+        return Err(DecodeError::new(DecodeErrorCause::DanglingByte {
+            index: offset + combining_chars.len() - 1,
+        }));
+    }
+
+    let bytes = combining_chars.as_bytes();
+    for (index, character) in combining_chars.char_indices() {
+        if !('\u{300}'..='\u{36f}').contains(&character) {
+            return Err(DecodeError::new(DecodeErrorCause::NotCombiningChar {
+                index: offset + index,
+                character,
+            }));
+        }
+
+        let decoded = decode_byte_pair(bytes[index], bytes[index + 1]);
+        if !((32..127).contains(&decoded) || decoded == b'\n') {
+            return Err(DecodeError::new(DecodeErrorCause::NotPrintableAscii {
+                index: offset + index,
+                character,
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+/// Encodes a byte that corresponds to a printable ASCII character or newline into the two bytes
+/// of the combining character that represents it.
+#[inline]
+#[must_use = "the function returns a new value and does not modify its input"]
+const fn encode_byte(byte: u8) -> [u8; 2] {
+    let v = ((byte as i16 - 11).rem_euclid(133) - 21) as u8;
+    [(v >> 6) & 1 | 0b1100_1100, (v & 63) | 0b1000_0000]
 }
 
 #[inline]
@@ -354,6 +551,15 @@ const fn decode_byte_pair(odd: u8, even: u8) -> u8 {
 /// Hello, world!
 /// ```
 ///
+/// A shebang line and an encoding declaration as described in [PEP 263](https://peps.python.org/pep-0263/)
+/// at the start of the code are kept as they are before the wrapper, since they only work on the first two lines of a file.
+/// ```
+/// # use zalgo_codec_common::{EncodeError, zalgo_wrap_python};
+/// let wrapped = zalgo_wrap_python("#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\nprint(\"Zalgo\")\n")?;
+/// assert!(wrapped.starts_with("#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\nb='E"));
+/// # Ok::<(), EncodeError>(())
+/// ```
+///
 /// # Known issues
 ///
 /// May not work correctly on python versions before 3.10,
@@ -372,9 +578,141 @@ const fn decode_byte_pair(odd: u8, even: u8) -> u8 {
 /// );
 /// ```
 #[must_use = "the function returns a new value and does not modify the input"]
+#[track_caller]
 pub fn zalgo_wrap_python(python: &str) -> Result<String, EncodeError> {
-    let encoded_string = zalgo_encode(python)?;
-    Ok(format!("b='{encoded_string}'.encode();exec(''.join(chr(((h<<6&64|c&63)+22)%133+10)for h,c in zip(b[1::2],b[2::2])))"))
+    let (preamble, code) = python.split_at(python_preamble_len(python.as_bytes(), true));
+    let encoded_string = match zalgo_encode(code) {
+        Ok(encoded_string) => encoded_string,
+        Err(e) => return Err(e.offset(preamble.matches('\n').count(), preamble.len())),
+    };
+    // The wrapper must begin on a line of its own.
+    let separator = if preamble.is_empty() || preamble.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    Ok(format!(
+        "{preamble}{separator}{PYTHON_WRAPPER_PREFIX}{encoded_string}{PYTHON_WRAPPER_SUFFIX}"
+    ))
+}
+
+/// Returns the length in bytes of the lines at the start of the given Python source code that have to stay
+/// on the first two lines of the file to work: a shebang line and an encoding declaration as described in PEP 263.
+///
+/// If `complete` is false `python` is only the start of the source code, and a final line without a line ending
+/// is not considered, since it could continue after the end of `python`.
+pub(crate) fn python_preamble_len(python: &[u8], complete: bool) -> usize {
+    let mut lines = python
+        .split_inclusive(|&byte| byte == b'\n')
+        .filter(|line| complete || line.ends_with(b"\n"));
+    match (lines.next(), lines.next()) {
+        // An encoding declaration on the second line only works if the first line is a comment as well.
+        (Some(first), Some(second))
+            if is_python_comment(first) && is_coding_declaration(second) =>
+        {
+            first.len() + second.len()
+        }
+        (Some(first), _) if first.starts_with(b"#!") || is_coding_declaration(first) => first.len(),
+        _ => 0,
+    }
+}
+
+/// Returns whether the line only contains a Python comment.
+fn is_python_comment(line: &[u8]) -> bool {
+    line.iter()
+        .find(|byte| !matches!(byte, b' ' | b'\t' | b'\x0c'))
+        .is_some_and(|&byte| byte == b'#')
+}
+
+/// Returns whether the line is an encoding declaration, i.e. whether it matches the regular expression
+/// `^[ \t\f]*#.*?coding[:=][ \t]*([-_.a-zA-Z0-9]+)` from PEP 263.
+fn is_coding_declaration(line: &[u8]) -> bool {
+    is_python_comment(line)
+        && line.windows(7).enumerate().any(|(i, window)| {
+            window.starts_with(b"coding")
+                && matches!(window[6], b':' | b'=')
+                && line[i + 7..]
+                    .iter()
+                    .find(|byte| !matches!(byte, b' ' | b'\t'))
+                    .is_some_and(|&byte| byte.is_ascii_alphanumeric() || b"-_.".contains(&byte))
+        })
+}
+
+/// The part of the output of [`zalgo_wrap_python`] that comes before the encoded string.
+pub(crate) const PYTHON_WRAPPER_PREFIX: &str = "b='";
+
+/// The part of the output of [`zalgo_wrap_python`] that comes after the encoded string.
+pub(crate) const PYTHON_WRAPPER_SUFFIX: &str =
+    "'.encode();exec(''.join(chr(((h<<6&64|c&63)+22)%133+10)for h,c in zip(b[1::2],b[2::2])))";
+
+/// Recovers the Python source code that was wrapped by [`zalgo_wrap_python`].
+///
+/// Any lines before the line with the wrapper, like a shebang line, are kept as they are
+/// at the start of the result. A line ending after the wrapper is ignored,
+/// since text editors tend to add one.
+///
+/// # Errors
+///
+/// Returns an error if the input does not contain a line that was produced by [`zalgo_wrap_python`],
+/// or if the encoded string in that line is not a valid encoding of printable ASCII and newlines.
+/// The indices in the error refer to positions in the input.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{zalgo_unwrap_python, zalgo_wrap_python, DecodeError, DecodeErrorKind};
+/// let python = "print(\"Hello, world!\")\n";
+/// let wrapped = zalgo_wrap_python(python).unwrap();
+/// assert_eq!(zalgo_unwrap_python(&wrapped)?, python);
+///
+/// let with_shebang = format!("#!/usr/bin/env python3\n{wrapped}\n");
+/// assert_eq!(zalgo_unwrap_python(&with_shebang)?, format!("#!/usr/bin/env python3\n{python}"));
+///
+/// assert_eq!(
+///     zalgo_unwrap_python(python).map_err(|e| e.kind()),
+///     Err(DecodeErrorKind::NotWrappedPython),
+/// );
+/// # Ok::<(), DecodeError>(())
+/// ```
+#[must_use = "the function returns a new value and does not modify the input"]
+#[track_caller]
+pub fn zalgo_unwrap_python(wrapped: &str) -> Result<String, DecodeError> {
+    let wrapper_start = if wrapped.starts_with(PYTHON_WRAPPER_PREFIX) {
+        Some(0)
+    } else {
+        wrapped
+            .match_indices('\n')
+            .map(|(i, _)| i + 1)
+            .find(|&i| wrapped[i..].starts_with(PYTHON_WRAPPER_PREFIX))
+    };
+    let Some(wrapper_start) = wrapper_start else {
+        return Err(DecodeError::new(DecodeErrorCause::NotWrappedPython));
+    };
+
+    let (preamble, wrapper) = wrapped.split_at(wrapper_start);
+    let wrapper = wrapper
+        .strip_suffix('\n')
+        .map_or(wrapper, |w| w.strip_suffix('\r').unwrap_or(w));
+    let Some(encoded) = wrapper
+        .strip_prefix(PYTHON_WRAPPER_PREFIX)
+        .and_then(|w| w.strip_suffix(PYTHON_WRAPPER_SUFFIX))
+        .filter(|encoded| encoded.starts_with('E'))
+    else {
+        return Err(DecodeError::new(DecodeErrorCause::NotWrappedPython));
+    };
+
+    // The index of the first combining character in the input.
+    let offset = wrapper_start + PYTHON_WRAPPER_PREFIX.len() + 1;
+    validate_combining_chars(&encoded[1..], offset)?;
+
+    let mut python = String::with_capacity(preamble.len() + encoded.len() / 2);
+    python.push_str(preamble);
+    python.extend(
+        encoded.as_bytes()[1..]
+            .chunks_exact(2)
+            .map(|pair| char::from(decode_byte_pair(pair[0], pair[1]))),
+    );
+    Ok(python)
 }
 
 #[cfg(test)]
@@ -387,6 +725,107 @@ mod test {
         assert_eq!(zalgo_encode("Zålgo").map_err(|e| e.char()), Err('å'));
     }
 
+    #[test]
+    fn test_unwrap_python() {
+        let python = "def hello():\n    print(\"Hello, world!\")\n\nhello()\n";
+        let wrapped = zalgo_wrap_python(python).unwrap();
+        assert_eq!(zalgo_unwrap_python(&wrapped).unwrap(), python);
+        assert_eq!(
+            zalgo_unwrap_python(&format!("{wrapped}\r\n")).unwrap(),
+            python
+        );
+        assert_eq!(
+            zalgo_unwrap_python(&format!(
+                "#!/usr/bin/python3\n# -*- coding: utf-8 -*-\n{wrapped}"
+            ))
+            .unwrap(),
+            format!("#!/usr/bin/python3\n# -*- coding: utf-8 -*-\n{python}")
+        );
+
+        for not_wrapped in [
+            "",
+            python,
+            &wrapped[..wrapped.len() - 1],
+            &format!("{wrapped}\nprint(\"Zalgo\")"),
+            &wrapped.replacen("b='E", "b='", 1),
+            "b=''.encode();exec(''.join(chr(((h<<6&64|c&63)+22)%133+10)for h,c in zip(b[1::2],b[2::2])))",
+        ] {
+            assert_eq!(
+                zalgo_unwrap_python(not_wrapped).map_err(|e| e.kind()),
+                Err(DecodeErrorKind::NotWrappedPython)
+            );
+        }
+
+        // The special lines at the start of a script are kept outside the wrapper.
+        for preamble in [
+            "#!/usr/bin/env python3\n",
+            "#!/usr/bin/env python3\r\n# -*- coding: latin-1 -*-\n",
+            "# vim: set fileencoding=utf-8 :\n",
+            "# A comment\n# coding=utf-8\n",
+        ] {
+            let script = format!("{preamble}{python}");
+            let wrapped_script = zalgo_wrap_python(&script).unwrap();
+            assert_eq!(wrapped_script, format!("{preamble}{wrapped}"));
+            assert_eq!(zalgo_unwrap_python(&wrapped_script).unwrap(), script);
+        }
+        for not_preamble in [
+            "# A comment\n",
+            "print(1) # coding: utf-8\n",
+            "# coding: \n",
+            "\n# coding: utf-8\n",
+        ] {
+            let script = format!("{not_preamble}{python}");
+            assert!(zalgo_wrap_python(&script).unwrap().starts_with("b='E"));
+        }
+        assert_eq!(
+            zalgo_wrap_python("#!python").unwrap(),
+            format!("#!python\n{}", zalgo_wrap_python("").unwrap())
+        );
+        let err = zalgo_wrap_python("#!python\n# coding: utf-8\nprint('\u{e5}')").unwrap_err();
+        assert_eq!(
+            (err.char(), err.line(), err.column(), err.index()),
+            ('\u{e5}', 3, 8, 32)
+        );
+
+        // Errors in the encoded string point to positions in the input.
+        let corrupted = wrapped.replacen("\u{345}", "\u{360}", 1);
+        let err = zalgo_unwrap_python(&format!("#!python\n{corrupted}")).unwrap_err();
+        assert_eq!(err.kind(), DecodeErrorKind::NotPrintableAscii);
+        let index = err.index().unwrap();
+        assert_eq!(
+            &format!("#!python\n{corrupted}")[index..index + 2],
+            "\u{360}"
+        );
+    }
+
+    #[test]
+    fn test_location() {
+        let err = zalgo_encode("Zålgo").unwrap_err();
+        assert_eq!(err.location().file(), file!());
+        assert_eq!(err.location().line(), line!() - 2);
+
+        let err = zalgo_decode("").unwrap_err();
+        assert_eq!(err.location().file(), file!());
+        assert_eq!(err.location().line(), line!() - 2);
+
+        let err = ZalgoString::from_encoded("Zalgo").unwrap_err();
+        assert_eq!(err.location().file(), file!());
+        assert_eq!(err.location().line(), line!() - 2);
+    }
+
+    #[test]
+    fn test_location_through_helper() {
+        #[track_caller]
+        fn encode_twice(s: &str) -> Result<String, EncodeError> {
+            zalgo_encode(&zalgo_encode(s)?)
+        }
+
+        // The helper is `#[track_caller]` so the location points to this call.
+        let err = encode_twice("\r").unwrap_err();
+        assert_eq!(err.location().file(), file!());
+        assert_eq!(err.location().line(), line!() - 2);
+    }
+
     #[test]
     fn test_empty_decode() {
         assert!(zalgo_decode("").is_err());
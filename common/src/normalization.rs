@@ -0,0 +1,286 @@
+//! Contains functions that deal with the effects of Unicode canonical normalization on encoded strings.
+
+use crate::{decode_byte_pair, error::DecodeErrorCause, validate_combining_chars, DecodeError};
+
+use alloc::{string::String, vec::Vec};
+
+/// The letters that NFC composes from an "E" followed by combining characters in the range U+0300–U+036F,
+/// and the combining characters they decompose into.
+const PRECOMPOSED_E: [(char, &[char]); 25] = [
+    ('\u{c8}', &['\u{300}']),
+    ('\u{c9}', &['\u{301}']),
+    ('\u{ca}', &['\u{302}']),
+    ('\u{cb}', &['\u{308}']),
+    ('\u{112}', &['\u{304}']),
+    ('\u{114}', &['\u{306}']),
+    ('\u{116}', &['\u{307}']),
+    ('\u{118}', &['\u{328}']),
+    ('\u{11a}', &['\u{30c}']),
+    ('\u{204}', &['\u{30f}']),
+    ('\u{206}', &['\u{311}']),
+    ('\u{228}', &['\u{327}']),
+    ('\u{1e14}', &['\u{304}', '\u{300}']),
+    ('\u{1e16}', &['\u{304}', '\u{301}']),
+    ('\u{1e18}', &['\u{32d}']),
+    ('\u{1e1a}', &['\u{330}']),
+    ('\u{1e1c}', &['\u{327}', '\u{306}']),
+    ('\u{1eb8}', &['\u{323}']),
+    ('\u{1eba}', &['\u{309}']),
+    ('\u{1ebc}', &['\u{303}']),
+    ('\u{1ebe}', &['\u{302}', '\u{301}']),
+    ('\u{1ec0}', &['\u{302}', '\u{300}']),
+    ('\u{1ec2}', &['\u{302}', '\u{309}']),
+    ('\u{1ec4}', &['\u{302}', '\u{303}']),
+    ('\u{1ec6}', &['\u{323}', '\u{302}']),
+];
+
+/// Returns the canonical combining class of a character in the range U+0300–U+036F.
+const fn combining_class(c: char) -> u8 {
+    match c {
+        '\u{334}'..='\u{338}' => 1,
+        '\u{321}'..='\u{322}' | '\u{327}'..='\u{328}' => 202,
+        '\u{31b}' => 216,
+        '\u{316}'..='\u{319}'
+        | '\u{31c}'..='\u{320}'
+        | '\u{323}'..='\u{326}'
+        | '\u{329}'..='\u{333}'
+        | '\u{339}'..='\u{33c}'
+        | '\u{347}'..='\u{349}'
+        | '\u{34d}'..='\u{34e}'
+        | '\u{353}'..='\u{356}'
+        | '\u{359}'..='\u{35a}' => 220,
+        '\u{315}' | '\u{31a}' | '\u{358}' => 232,
+        '\u{35c}' | '\u{35f}' | '\u{362}' => 233,
+        '\u{35d}'..='\u{35e}' | '\u{360}'..='\u{361}' => 234,
+        '\u{345}' => 240,
+        '\u{34f}' => 0,
+        _ => 230,
+    }
+}
+
+/// Decodes a string that was encoded by [`zalgo_encode`](crate::zalgo_encode) and then possibly
+/// normalized to NFC or NFD by a transport that it passed through.
+///
+/// Before decoding, the leading letter is decomposed if NFC composed it with combining characters,
+/// and the combining characters are put in canonical order.
+/// This recovers the original string if [`is_normalization_stable`] returns true for its encoding.
+///
+/// # Normalization
+///
+/// Some transports, like file systems on macOS and some chat applications, normalize the text that passes through them.
+/// Canonical normalization to NFC or NFD changes an encoded string in three ways:
+///
+/// 1. NFC composes the leading "E" with the first one or two combining characters into a precomposed letter,
+///    e.g. "E\u{301}" into "É". This is undone by this function.
+/// 2. The combining characters are sorted by their canonical combining class. Characters of the same class
+///    keep their order relative to each other, but the order between characters of different classes is lost.
+///    Most printable ASCII characters are encoded as characters of class 230 or 220, for example "a" and "Z" respectively.
+/// 3. The characters U+0340, U+0341, U+0343, and U+0344, which are the encodings of "\`", "a", "c", and "d",
+///    are replaced by the encodings of " ", "!", "3", and "(!" respectively.
+///
+/// The last two can not be undone, so only strings that are not changed by them,
+/// as determined by [`is_normalization_stable`], can be recovered after normalization.
+/// For other strings the order of some decoded characters may differ from the original,
+/// and some characters may have been replaced.
+///
+/// # Errors
+///
+/// Returns an error if the string is empty, does not begin with an "E" or a letter that decomposes into an "E"
+/// followed by combining characters, or if the rest of the string contains a character that is not
+/// a combining character that decodes into a printable ASCII character or newline.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{is_normalization_stable, zalgo_decode_normalized, zalgo_encode, DecodeError};
+/// let encoded = zalgo_encode("HELLO").unwrap();
+/// assert!(is_normalization_stable(&encoded));
+///
+/// // NFC composes the leading "E" and the encoding of the "H" into an "Ę".
+/// let normalized = "\u{118}\u{325}\u{32c}\u{32c}\u{32f}";
+/// assert_eq!(zalgo_decode_normalized(normalized)?, "HELLO");
+/// assert_eq!(zalgo_decode_normalized(&encoded)?, "HELLO");
+/// # Ok::<(), DecodeError>(())
+/// ```
+#[must_use = "the function returns a new value and does not modify the input"]
+#[track_caller]
+pub fn zalgo_decode_normalized(encoded: &str) -> Result<String, DecodeError> {
+    let mut chars = encoded.chars();
+    let Some(first) = chars.next() else {
+        return Err(DecodeError::new(DecodeErrorCause::EmptyInput));
+    };
+    let leading_marks: &[char] = if first == 'E' {
+        &[]
+    } else if let Some((_, marks)) = PRECOMPOSED_E.iter().find(|(letter, _)| *letter == first) {
+        marks
+    } else {
+        return Err(DecodeError::new(DecodeErrorCause::MissingLeadingE {
+            found: first,
+        }));
+    };
+
+    let rest = chars.as_str();
+    validate_combining_chars(rest, first.len_utf8())?;
+
+    let mut marks: Vec<char> = leading_marks.iter().copied().chain(rest.chars()).collect();
+    // Characters of class 0 are never reordered, and nothing is moved across them.
+    for run in marks.split_mut(|&c| combining_class(c) == 0) {
+        // This sort is stable, just like canonical ordering.
+        run.sort_by_key(|&c| combining_class(c));
+    }
+
+    Ok(marks
+        .into_iter()
+        .map(|c| {
+            let mut bytes = [0; 2];
+            c.encode_utf8(&mut bytes);
+            char::from(decode_byte_pair(bytes[0], bytes[1]))
+        })
+        .collect())
+}
+
+/// Returns whether the given string is a valid output of [`zalgo_encode`](crate::zalgo_encode)
+/// that survives Unicode canonical normalization, meaning that [`zalgo_decode_normalized`]
+/// recovers the original string after it has been normalized to NFC or NFD.
+///
+/// This is the case if its combining characters are already in canonical order,
+/// and it does not contain the encodings of "\`", "a", "c", or "d",
+/// see the documentation of [`zalgo_decode_normalized`] for the details.
+/// Such a string is not changed by NFD, but NFC may still compose its leading "E",
+/// which [`zalgo_decode`](crate::zalgo_decode) can not decode.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{is_normalization_stable, zalgo_encode, EncodeError};
+/// assert!(is_normalization_stable(&zalgo_encode("HELLO")?));
+/// // The "Z" is encoded as a character of class 220, and the "a" as one of class 230 that NFC replaces.
+/// assert!(!is_normalization_stable(&zalgo_encode("Zalgo")?));
+/// # Ok::<(), EncodeError>(())
+/// ```
+#[must_use = "the function returns a new value and does not modify the input"]
+pub fn is_normalization_stable(encoded: &str) -> bool {
+    if !crate::is_zalgo_encoded(encoded) {
+        return false;
+    }
+
+    let mut previous_class = 0;
+    encoded[1..].chars().all(|c| {
+        let class = combining_class(c);
+        let in_order = class == 0 || class >= previous_class;
+        previous_class = class;
+        in_order && !matches!(c, '\u{340}' | '\u{341}' | '\u{343}' | '\u{344}')
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{combining_class, is_normalization_stable, zalgo_decode_normalized, PRECOMPOSED_E};
+    use crate::{zalgo_decode, zalgo_encode, DecodeErrorKind};
+    use alloc::{format, string::String, vec::Vec};
+    use unicode_normalization::{char::canonical_combining_class, UnicodeNormalization};
+
+    /// Every character that can be encoded.
+    fn encodable_chars() -> impl Iterator<Item = char> + Clone {
+        (' '..='~').chain(['\n'])
+    }
+
+    #[test]
+    fn tables_match_unicode_normalization() {
+        for c in '\u{300}'..='\u{36f}' {
+            assert_eq!(combining_class(c), canonical_combining_class(c), "{c:?}");
+        }
+
+        let precomposed: Vec<char> = (char::MIN..=char::MAX)
+            .filter(|c| {
+                let decomposed: Vec<char> = c.nfd().collect();
+                decomposed.len() > 1
+                    && decomposed[0] == 'E'
+                    && decomposed[1..]
+                        .iter()
+                        .all(|m| ('\u{300}'..='\u{36f}').contains(m))
+            })
+            .collect();
+        assert_eq!(
+            precomposed,
+            PRECOMPOSED_E.iter().map(|(c, _)| *c).collect::<Vec<char>>()
+        );
+        for (letter, marks) in PRECOMPOSED_E {
+            assert!(letter
+                .nfd()
+                .eq(['E'].into_iter().chain(marks.iter().copied())));
+        }
+    }
+
+    #[test]
+    fn recovers_stable_strings() {
+        let mut stable = 0;
+        for a in encodable_chars() {
+            for b in encodable_chars() {
+                let s = format!("{a}{b}");
+                let encoded = zalgo_encode(&s).unwrap();
+
+                let nfd: String = encoded.nfd().collect();
+                assert_eq!(is_normalization_stable(&encoded), nfd == encoded, "{s:?}");
+                if is_normalization_stable(&encoded) {
+                    stable += 1;
+                    assert_eq!(zalgo_decode_normalized(&encoded).unwrap(), s, "{s:?}");
+                    let nfc: String = encoded.nfc().collect();
+                    assert_eq!(zalgo_decode_normalized(&nfc).unwrap(), s, "{s:?}");
+                    assert_eq!(zalgo_decode_normalized(&nfd).unwrap(), s, "{s:?}");
+                }
+            }
+        }
+        // Most pairs of characters with different classes are only stable in one order.
+        assert!(stable > 96 * 96 / 2);
+    }
+
+    #[test]
+    fn recovers_composed_leading_letter() {
+        let encoded = zalgo_encode("HELLO").unwrap();
+        let nfc: String = encoded.nfc().collect();
+        assert!(nfc.starts_with('\u{118}'));
+        assert_ne!(zalgo_decode(&nfc).ok().as_deref(), Some("HELLO"));
+        assert_eq!(zalgo_decode_normalized(&nfc).unwrap(), "HELLO");
+
+        // The encodings of "$" and "!" are composed with the leading "E" into an "Ḗ",
+        // past the encoding of the "Z" which is of a lower class.
+        let encoded = zalgo_encode("Z$!").unwrap();
+        assert!(is_normalization_stable(&encoded));
+        let nfc: String = encoded.nfc().collect();
+        assert_eq!(nfc, "\u{1e16}\u{33a}");
+        assert_eq!(zalgo_decode_normalized(&nfc).unwrap(), "Z$!");
+    }
+
+    #[test]
+    fn unrecoverable_strings() {
+        // The "a" is replaced by the encoding of a "!".
+        let encoded = zalgo_encode("a").unwrap();
+        assert!(!is_normalization_stable(&encoded));
+        let nfc: String = encoded.nfc().collect();
+        assert_eq!(zalgo_decode_normalized(&nfc).unwrap(), "!");
+
+        // The "Z" and "g" are of class 220 and are moved in front of the "l" and "o" of class 230.
+        let encoded = zalgo_encode("Zlgo").unwrap();
+        assert!(!is_normalization_stable(&encoded));
+        let nfc: String = encoded.nfc().collect();
+        assert_eq!(zalgo_decode_normalized(&nfc).unwrap(), "Zglo");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            zalgo_decode_normalized("").unwrap_err().kind(),
+            DecodeErrorKind::EmptyInput
+        );
+        assert_eq!(
+            zalgo_decode_normalized("Zalgo").unwrap_err().kind(),
+            DecodeErrorKind::MissingLeadingE
+        );
+        let err = zalgo_decode_normalized("\u{c9}\u{301}ab").unwrap_err();
+        assert_eq!(err.kind(), DecodeErrorKind::NotCombiningChar);
+        assert_eq!(err.index(), Some(4));
+        assert!(!is_normalization_stable("Zalgo"));
+        assert!(!is_normalization_stable(""));
+    }
+}
@@ -0,0 +1,93 @@
+//! Contains [`proptest`](https://docs.rs/proptest) strategies that generate text that can be encoded
+//! and [`ZalgoString`]s, for property-based testing of code that uses the codec.
+//!
+//! The strategies shrink towards shorter strings of spaces, and every shrunk value is still valid.
+//!
+//! # Example
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use zalgo_codec_common::{proptest::{encodable_string, zalgo_string}, zalgo_decode, zalgo_encode};
+//!
+//! proptest! {
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn round_trip(s in encodable_string(0..100)) {
+//!         prop_assert_eq!(zalgo_decode(&zalgo_encode(&s).unwrap()).unwrap(), s);
+//!     }
+//!
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn decoded_len(zs in zalgo_string(..10)) {
+//!         prop_assert!(zs.decoded_len() < 10);
+//!     }
+//! }
+//! # round_trip();
+//! # decoded_len();
+//! ```
+
+use crate::ZalgoString;
+
+use alloc::{borrow::Cow, string::String};
+
+use ::proptest::{collection::SizeRange, strategy::Strategy};
+
+/// Returns a strategy that generates strings of printable ASCII characters and newlines,
+/// whose length in bytes is in the given range.
+#[must_use = "the function returns a new value and does not modify the input"]
+pub fn encodable_string(len_range: impl Into<SizeRange>) -> impl Strategy<Value = String> {
+    ::proptest::collection::vec(
+        ::proptest::char::ranges(Cow::Borrowed(&[' '..='~', '\n'..='\n'])),
+        len_range,
+    )
+    .prop_map(String::from_iter)
+}
+
+/// Returns a strategy that generates [`ZalgoString`]s that decode into strings
+/// whose length in bytes is in the given range.
+#[must_use = "the function returns a new value and does not modify the input"]
+pub fn zalgo_string(len_range: impl Into<SizeRange>) -> impl Strategy<Value = ZalgoString> {
+    encodable_string(len_range).prop_map(|s| {
+        ZalgoString::new(&s).expect("printable ASCII characters and newlines can be encoded")
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{encodable_string, zalgo_string};
+    use crate::{zalgo_decode, zalgo_encode, ZalgoString};
+    use ::proptest::prelude::*;
+    use alloc::{format, string::String, vec::Vec};
+
+    proptest! {
+        #[test]
+        fn round_trip(s in encodable_string(0..200)) {
+            let encoded = zalgo_encode(&s).unwrap();
+            prop_assert_eq!(zalgo_decode(&encoded).unwrap(), s.as_str());
+            prop_assert_eq!(ZalgoString::new(&s).unwrap().into_decoded_string(), s);
+        }
+
+        #[test]
+        fn push_zalgo_str_concatenates(zs1 in zalgo_string(0..100), zs2 in zalgo_string(0..100)) {
+            let expected = format!("{}{}", zs1.clone().into_decoded_string(), zs2.clone().into_decoded_string());
+            let mut pushed = zs1;
+            pushed.push_zalgo_str(&zs2);
+            prop_assert_eq!(pushed.decoded_len(), expected.len());
+            prop_assert_eq!(pushed.into_decoded_string(), expected);
+        }
+
+        #[test]
+        fn iterators_agree_with_into_decoded_string(zs in zalgo_string(0..200)) {
+            let decoded = zs.clone().into_decoded_string();
+            prop_assert_eq!(zs.decoded_chars().collect::<String>(), decoded.as_str());
+            prop_assert_eq!(zs.decoded_bytes().collect::<Vec<u8>>(), decoded.as_bytes());
+            prop_assert_eq!(
+                zs.decoded_chars().rev().collect::<String>(),
+                decoded.chars().rev().collect::<String>()
+            );
+            prop_assert_eq!(zs.decoded_len(), decoded.len());
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Contains modules for use with `#[serde(with = "...")]` that encode or decode a field when it is serialized,
+//! so that its serialized form differs from its form in the program.
+//!
+//! # Example
+//!
+//! ```
+//! # use zalgo_codec_common::{zalgo_encode, ZalgoString};
+//! use serde::{Deserialize, Serialize};
+//! use zalgo_codec_common::serde_support::{decoded_as_encoded, encoded_as_decoded};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Message {
+//!     // A plain string in the program that is stored encoded.
+//!     #[serde(with = "decoded_as_encoded")]
+//!     secret: String,
+//!     // An encoded string in the program that is stored as plain text.
+//!     #[serde(with = "encoded_as_decoded")]
+//!     greeting: ZalgoString,
+//! }
+//!
+//! let message = Message {
+//!     secret: String::from("Zalgo"),
+//!     greeting: ZalgoString::new("Hi")?,
+//! };
+//! let json = serde_json::to_string(&message)?;
+//! assert_eq!(
+//!     json,
+//!     format!(r#"{{"secret":"{}","greeting":"Hi"}}"#, zalgo_encode("Zalgo")?),
+//! );
+//! assert_eq!(serde_json::from_str::<Message>(&json)?, message);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+/// Serializes a [`String`](alloc::string::String) of printable ASCII and newlines as its encoding,
+/// and decodes it again when it is deserialized.
+///
+/// Serialization fails with the message of the [`EncodeError`](crate::EncodeError) if the string can not be encoded,
+/// and deserialization fails with the message of the [`DecodeError`](crate::DecodeError)
+/// if the serialized string is not a valid encoding, see [`ZalgoString::from_encoded`](crate::ZalgoString::from_encoded).
+pub mod decoded_as_encoded {
+    use crate::ZalgoString;
+    use alloc::string::String;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    /// Encodes the string and serializes the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string contains a character that can not be encoded.
+    pub fn serialize<S: Serializer>(decoded: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoded = ZalgoString::new(decoded).map_err(ser::Error::custom)?;
+        serializer.serialize_str(encoded.as_str())
+    }
+
+    /// Deserializes an encoded string, validates it, and decodes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the deserialized string is not a valid encoding of printable ASCII and newlines.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        ZalgoString::try_from(encoded)
+            .map(ZalgoString::into_decoded_string)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Serializes a [`ZalgoString`](crate::ZalgoString) as its decoded string,
+/// and encodes it again when it is deserialized.
+///
+/// Deserialization fails with the message of the [`EncodeError`](crate::EncodeError)
+/// if the serialized string can not be encoded.
+pub mod encoded_as_decoded {
+    use crate::ZalgoString;
+    use alloc::string::String;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Decodes the `ZalgoString` and serializes the result.
+    pub fn serialize<S: Serializer>(
+        encoded: &ZalgoString,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encoded.decoded_chars().collect::<String>())
+    }
+
+    /// Deserializes a string and encodes it into a `ZalgoString`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the deserialized string contains a character that can not be encoded.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<ZalgoString, D::Error> {
+        let decoded = String::deserialize(deserializer)?;
+        ZalgoString::new(&decoded).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decoded_as_encoded, encoded_as_decoded};
+    use crate::ZalgoString;
+    use alloc::string::{String, ToString};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Stored {
+        #[serde(with = "decoded_as_encoded")]
+        plain: String,
+        #[serde(with = "encoded_as_decoded")]
+        encoded: ZalgoString,
+    }
+
+    #[test]
+    fn round_trip() {
+        let stored = Stored {
+            plain: String::from("Zalgo\nHe comes!"),
+            encoded: ZalgoString::new("To invoke the hive-mind").unwrap(),
+        };
+        let json = serde_json::to_value(&stored).unwrap();
+        assert_eq!(
+            json["plain"],
+            ZalgoString::new("Zalgo\nHe comes!").unwrap().as_str()
+        );
+        assert_eq!(json["encoded"], "To invoke the hive-mind");
+        assert_eq!(serde_json::from_value::<Stored>(json).unwrap(), stored);
+    }
+
+    #[test]
+    fn unencodable_plain_text() {
+        let stored = Stored {
+            plain: String::from("Zålgo"),
+            encoded: ZalgoString::default(),
+        };
+        let err = serde_json::to_string(&stored).unwrap_err();
+        assert!(err.to_string().contains("can not encode 'å' character"));
+
+        let err = serde_json::from_str::<Stored>(r#"{"plain":"E","encoded":"Zålgo"}"#).unwrap_err();
+        assert!(err.to_string().contains("can not encode 'å' character"));
+    }
+
+    #[test]
+    fn invalid_encoding() {
+        let err =
+            serde_json::from_str::<Stored>(r#"{"plain":"Zalgo","encoded":"Zalgo"}"#).unwrap_err();
+        assert!(err.to_string().contains("begins with 'Z' instead of 'E'"));
+    }
+}
@@ -0,0 +1,398 @@
+//! Contains the implementation of [`ZalgoArrayString`], a string that has been encoded with [`zalgo_encode`](crate::zalgo_encode)
+//! and is stored inline in an array instead of on the heap.
+//!
+//! None of the methods of a `ZalgoArrayString` allocate, so it can be used where there is no allocator.
+
+use crate::{
+    encode_byte,
+    zalgo_string::{combining_chars, decoded_len, DecodedBytes, DecodedChars},
+    ArrayEncodeError, EncodeError,
+};
+
+use core::{cmp::Ordering, fmt, hash, str};
+
+/// A string that has been encoded with [`zalgo_encode`](crate::zalgo_encode) and is stored inline in an array of `N` bytes.
+///
+/// Like a [`ZalgoString`](crate::ZalgoString) it always contains a valid encoding: an "E" followed by
+/// two combining characters of one byte each for every encoded character.
+/// This means that it can hold the encoding of at most `(N - 1) / 2` characters.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{ArrayEncodeError, ZalgoArrayString};
+/// let mut zs = ZalgoArrayString::<16>::new("Zalgo")?;
+/// assert_eq!(zs, "E\u{33a}\u{341}\u{34c}\u{347}\u{34f}");
+///
+/// zs.push('!')?;
+/// assert!(zs.decoded_chars().eq("Zalgo!".chars()));
+///
+/// assert!(matches!(
+///     zs.push_str(" He comes"),
+///     Err(ArrayEncodeError::CapacityExceeded { required: 31, capacity: 16 }),
+/// ));
+/// # Ok::<(), ArrayEncodeError>(())
+/// ```
+#[derive(Clone)]
+pub struct ZalgoArrayString<const N: usize> {
+    buf: [u8; N],
+    // `buf[..len]` is always a valid encoding, the rest of the buffer is unused.
+    len: usize,
+}
+
+impl<const N: usize> ZalgoArrayString<N> {
+    /// Encodes the given string slice in the same way as [`zalgo_encode`](crate::zalgo_encode) and stores the result inline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArrayEncodeError::CapacityExceeded`] with the required capacity if the encoding
+    /// of the string, which is `2 * s.len() + 1` bytes long, does not fit in `N` bytes.
+    /// Otherwise returns [`ArrayEncodeError::Unencodable`] if the string contains a character
+    /// that is not a printable ASCII character or a newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{ArrayEncodeError, ZalgoArrayString};
+    /// assert_eq!(ZalgoArrayString::<11>::new("Zalgo")?, "E\u{33a}\u{341}\u{34c}\u{347}\u{34f}");
+    /// assert!(matches!(
+    ///     ZalgoArrayString::<10>::new("Zalgo"),
+    ///     Err(ArrayEncodeError::CapacityExceeded { required: 11, capacity: 10 }),
+    /// ));
+    /// assert!(matches!(
+    ///     ZalgoArrayString::<16>::new("Zålgo"),
+    ///     Err(ArrayEncodeError::Unencodable { .. }),
+    /// ));
+    /// # Ok::<(), ArrayEncodeError>(())
+    /// ```
+    #[must_use = "this associated method returns a new `ZalgoArrayString` and does not modify the input"]
+    #[track_caller]
+    pub fn new(s: &str) -> Result<Self, ArrayEncodeError> {
+        let mut zs = Self {
+            buf: [0; N],
+            len: 0,
+        };
+        // The "E" must fit even if the string is empty.
+        check_capacity::<N>(1 + 2 * s.len())?;
+        zs.buf[0] = b'E';
+        zs.len = 1;
+        zs.push_str(s)?;
+        Ok(zs)
+    }
+
+    /// Encodes the given character and appends the result to the end of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the character does not fit or can not be encoded, see [`push_str`](ZalgoArrayString::push_str).
+    /// `self` is not modified if an error is returned.
+    #[track_caller]
+    pub fn push(&mut self, c: char) -> Result<(), ArrayEncodeError> {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    /// Encodes the given string slice and appends the result to the end of `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArrayEncodeError::CapacityExceeded`] with the required capacity if the result does not fit in `N` bytes.
+    /// Otherwise returns [`ArrayEncodeError::Unencodable`] if the string contains a character
+    /// that is not a printable ASCII character or a newline.
+    /// The line, column, and index of the [`EncodeError`] are those in the given string.
+    ///
+    /// `self` is not modified if an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{ArrayEncodeError, ZalgoArrayString};
+    /// let mut zs = ZalgoArrayString::<11>::new("Zal")?;
+    /// assert!(zs.push_str("go!").is_err());
+    /// zs.push_str("go")?;
+    /// assert!(zs.decoded_chars().eq("Zalgo".chars()));
+    /// # Ok::<(), ArrayEncodeError>(())
+    /// ```
+    #[track_caller]
+    pub fn push_str(&mut self, s: &str) -> Result<(), ArrayEncodeError> {
+        let new_len = self.len.saturating_add(2 * s.len());
+        check_capacity::<N>(new_len)?;
+        // A failure leaves `self.len` untouched, so the bytes that were written are never read.
+        encode_into(s, &mut self.buf[self.len..new_len])?;
+        self.len = new_len;
+        Ok(())
+    }
+
+    /// Returns the encoded contents of `self` as a string slice.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: `buf[..len]` is always an "E" followed by combining characters, which is valid UTF-8.
+        unsafe { str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// Returns the encoded contents of `self` as a byte slice.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Returns a string slice of just the combining characters of `self` without the initial "E",
+    /// see [`ZalgoString::as_combining_chars`](crate::ZalgoString::as_combining_chars).
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn as_combining_chars(&self) -> &str {
+        combining_chars(self.as_str())
+    }
+
+    /// Returns an iterator over the decoded characters of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{ArrayEncodeError, ZalgoArrayString};
+    /// let zs = ZalgoArrayString::<32>::new("Zalgo")?;
+    /// assert!(zs.decoded_chars().rev().eq("oglaZ".chars()));
+    /// # Ok::<(), ArrayEncodeError>(())
+    /// ```
+    #[inline]
+    pub fn decoded_chars(&self) -> DecodedChars<'_> {
+        DecodedChars::new(self.as_str())
+    }
+
+    /// Returns an iterator over the decoded bytes of `self`.
+    #[inline]
+    pub fn decoded_bytes(&self) -> DecodedBytes<'_> {
+        DecodedBytes::new(self.as_str())
+    }
+
+    /// Returns the length of the encoded string in bytes.
+    ///
+    /// This length is twice the length of the decoded string plus one.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    // The encoded string always contains the "E", use `decoded_is_empty` instead.
+    #[allow(clippy::len_without_is_empty)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the capacity of `self` in bytes, which is always `N`.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the length of `self` in bytes if it were to be decoded.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn decoded_len(&self) -> usize {
+        decoded_len(self.as_str())
+    }
+
+    /// Returns whether the string would be empty if decoded.
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn decoded_is_empty(&self) -> bool {
+        self.decoded_len() == 0
+    }
+}
+
+/// Returns an error if an encoded string of the given length does not fit in `N` bytes.
+#[inline]
+const fn check_capacity<const N: usize>(required: usize) -> Result<(), ArrayEncodeError> {
+    if required > N {
+        Err(ArrayEncodeError::CapacityExceeded {
+            required,
+            capacity: N,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Encodes the string into the given buffer, which must be exactly twice as long as the string.
+#[track_caller]
+fn encode_into(s: &str, buf: &mut [u8]) -> Result<(), EncodeError> {
+    debug_assert_eq!(buf.len(), 2 * s.len());
+
+    let mut line = 1;
+    let mut column = 1;
+    for (index, (&byte, pair)) in s.as_bytes().iter().zip(buf.chunks_exact_mut(2)).enumerate() {
+        if byte == b'\n' {
+            line += 1;
+            column = 1;
+        } else if (32..127).contains(&byte) {
+            column += 1;
+        } else {
+            // Every byte before this one was ASCII, so this is the first byte of a character.
+            let unencodable_character = s[index..]
+                .chars()
+                .next()
+                .expect("`index` is on a char boundary within the string");
+            return Err(EncodeError::new(unencodable_character, line, column, index));
+        }
+        pair.copy_from_slice(&encode_byte(byte));
+    }
+    Ok(())
+}
+
+impl<const N: usize> fmt::Debug for ZalgoArrayString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ZalgoArrayString")
+            .field(&self.as_str())
+            .finish()
+    }
+}
+
+/// Displays the encoded form of the `ZalgoArrayString`.
+impl<const N: usize> fmt::Display for ZalgoArrayString<N> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ZalgoArrayString<M>> for ZalgoArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &ZalgoArrayString<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ZalgoArrayString<N> {}
+
+impl<const N: usize> PartialOrd for ZalgoArrayString<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for ZalgoArrayString<N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> hash::Hash for ZalgoArrayString<N> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize> PartialEq<str> for ZalgoArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ZalgoArrayString<N> {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<ZalgoArrayString<N>> for str {
+    #[inline]
+    fn eq(&self, other: &ZalgoArrayString<N>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<ZalgoArrayString<N>> for &str {
+    #[inline]
+    fn eq(&self, other: &ZalgoArrayString<N>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ZalgoArrayString;
+    use crate::{zalgo_encode, ArrayEncodeError};
+
+    #[test]
+    fn test_exact_capacity() {
+        let s = "Zalgo\n He comes!";
+        let zs = ZalgoArrayString::<33>::new(s).unwrap();
+        assert_eq!(zs, zalgo_encode(s).unwrap().as_str());
+        assert_eq!(zs.len(), 33);
+        assert_eq!(zs.capacity(), 33);
+        assert_eq!(zs.decoded_len(), s.len());
+        assert!(zs.decoded_chars().eq(s.chars()));
+        assert!(zs.decoded_bytes().rev().eq(s.bytes().rev()));
+
+        let zs = ZalgoArrayString::<1>::new("").unwrap();
+        assert_eq!(zs, "E");
+        assert!(zs.decoded_is_empty());
+        assert_eq!(zs.as_combining_chars(), "");
+    }
+
+    #[test]
+    fn test_capacity_exceeded() {
+        assert!(matches!(
+            ZalgoArrayString::<32>::new("Zalgo\n He comes!"),
+            Err(ArrayEncodeError::CapacityExceeded {
+                required: 33,
+                capacity: 32
+            })
+        ));
+        assert!(matches!(
+            ZalgoArrayString::<0>::new(""),
+            Err(ArrayEncodeError::CapacityExceeded {
+                required: 1,
+                capacity: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn test_push() {
+        let mut zs = ZalgoArrayString::<5>::new("").unwrap();
+        zs.push('H').unwrap();
+        zs.push('i').unwrap();
+        assert_eq!(zs, "E\u{328}\u{349}");
+
+        // The string is full, and a failed push leaves it untouched.
+        assert!(matches!(
+            zs.push('!'),
+            Err(ArrayEncodeError::CapacityExceeded {
+                required: 7,
+                capacity: 5
+            })
+        ));
+        assert!(zs.push_str("!").is_err());
+        zs.push_str("").unwrap();
+        assert_eq!(zs, "E\u{328}\u{349}");
+
+        let mut zs = ZalgoArrayString::<64>::new("Zalgo").unwrap();
+        let Err(ArrayEncodeError::Unencodable { error }) = zs.push_str(" He\ncomes\r!") else {
+            panic!("expected an encoding error");
+        };
+        assert_eq!(error.char(), '\r');
+        assert_eq!((error.line(), error.column(), error.index()), (2, 6, 9));
+        assert!(zs.decoded_chars().eq("Zalgo".chars()));
+
+        assert!(matches!(
+            zs.push('å'),
+            Err(ArrayEncodeError::Unencodable { .. })
+        ));
+        assert!(zs.decoded_chars().eq("Zalgo".chars()));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let a = ZalgoArrayString::<16>::new("Zalgo").unwrap();
+        let b = ZalgoArrayString::<11>::new("Zalgo").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a, *"E\u{33a}\u{341}\u{34c}\u{347}\u{34f}");
+        assert_eq!("E\u{33a}\u{341}\u{34c}\u{347}\u{34f}", a);
+        assert!(ZalgoArrayString::<16>::new("A").unwrap() < a);
+    }
+}
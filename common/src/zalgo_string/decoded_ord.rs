@@ -0,0 +1,211 @@
+use super::ZalgoString;
+use core::{cmp::Ordering, hash, ops::Deref};
+
+/// A wrapper around a [`ZalgoString`] that is compared, ordered, and hashed by its decoded contents.
+///
+/// The derived [`Ord`] implementation of a `ZalgoString` orders values by their encoded bytes.
+/// The encoding maps printable ASCII onto combining characters in order, but it maps newlines past the end of that range,
+/// so the encoded order differs from the order of the decoded strings when they contain newlines.
+/// The comparisons of this type decode the bytes of both values pairwise as they go, and never allocate.
+///
+/// This struct is obtained by calling the [`by_decoded`](ZalgoString::by_decoded) method on a [`ZalgoString`],
+/// or with the [`From`] implementation, and it dereferences to the inner value.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_common::{DecodedOrd, EncodeError, ZalgoString};
+/// use std::collections::BTreeSet;
+///
+/// let set: BTreeSet<DecodedOrd> = ["b", "a\nb", "a b"]
+///     .into_iter()
+///     .map(|s| ZalgoString::new(s).map(ZalgoString::by_decoded))
+///     .collect::<Result<_, _>>()?;
+///
+/// let decoded: Vec<String> = set.iter().map(|zs| zs.decoded_chars().collect()).collect();
+/// assert_eq!(decoded, ["a\nb", "a b", "b"]);
+/// # Ok::<(), EncodeError>(())
+/// ```
+#[derive(Debug, Clone)]
+#[repr(transparent)]
+pub struct DecodedOrd(pub ZalgoString);
+
+impl DecodedOrd {
+    /// Returns the inner `ZalgoString`.
+    #[inline]
+    #[must_use = "`self` will be dropped if the result is not used"]
+    pub fn into_inner(self) -> ZalgoString {
+        self.0
+    }
+}
+
+impl From<ZalgoString> for DecodedOrd {
+    #[inline]
+    fn from(zs: ZalgoString) -> Self {
+        Self(zs)
+    }
+}
+
+impl Deref for DecodedOrd {
+    type Target = ZalgoString;
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq for DecodedOrd {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.decoded_bytes().eq(other.0.decoded_bytes())
+    }
+}
+
+impl Eq for DecodedOrd {}
+
+impl PartialOrd for DecodedOrd {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DecodedOrd {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.decoded_bytes().cmp(other.0.decoded_bytes())
+    }
+}
+
+impl hash::Hash for DecodedOrd {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        for byte in self.0.decoded_bytes() {
+            state.write_u8(byte);
+        }
+        // Ends the value with a byte that is not printable ASCII, just like `str` does,
+        // so that the values in a sequence can not run into each other.
+        state.write_u8(0xff);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DecodedOrd;
+    use crate::ZalgoString;
+    use alloc::{
+        collections::BTreeSet,
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use core::hash::{Hash, Hasher};
+
+    const STRINGS: [&str; 10] = [
+        "Zalgo",
+        "zalgo",
+        "Zalgo\nHe comes!",
+        "Zalgo He comes!",
+        "~",
+        "",
+        "\n",
+        "0123",
+        "Zalg",
+        "{}",
+    ];
+
+    fn wrapped() -> Vec<DecodedOrd> {
+        STRINGS
+            .into_iter()
+            .map(|s| ZalgoString::new(s).unwrap().by_decoded())
+            .collect()
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut sorted = wrapped();
+        sorted.sort();
+        let mut strings = STRINGS.map(String::from);
+        strings.sort();
+        let decoded: Vec<String> = sorted
+            .into_iter()
+            .map(|d| d.decoded_chars().collect())
+            .collect();
+        assert_eq!(decoded, strings);
+
+        // The order of the encoded strings is different.
+        let mut encoded: Vec<ZalgoString> =
+            wrapped().into_iter().map(DecodedOrd::into_inner).collect();
+        encoded.sort();
+        assert_ne!(
+            encoded
+                .into_iter()
+                .map(ZalgoString::into_decoded_string)
+                .collect::<Vec<_>>(),
+            strings
+        );
+    }
+
+    #[test]
+    fn test_btree_set() {
+        let set: BTreeSet<DecodedOrd> = wrapped().into_iter().collect();
+        assert_eq!(set.len(), STRINGS.len());
+        for s in STRINGS {
+            assert!(set.contains(&ZalgoString::new(s).unwrap().by_decoded()));
+        }
+        assert!(!set.contains(&ZalgoString::new("Zalgo!").unwrap().by_decoded()));
+
+        let strings: BTreeSet<String> = STRINGS.into_iter().map(ToString::to_string).collect();
+        assert!(set
+            .iter()
+            .map(|d| d.decoded_chars().collect::<String>())
+            .eq(strings.into_iter()));
+
+        let range: Vec<String> = set
+            .range(ZalgoString::new("Zalg").unwrap().by_decoded()..)
+            .take(3)
+            .map(|d| d.0.clone().into_decoded_string())
+            .collect();
+        assert_eq!(range, ["Zalg", "Zalgo", "Zalgo\nHe comes!"]);
+    }
+
+    /// A hasher that records every byte that is written to it.
+    #[derive(Default)]
+    struct RecordingHasher(Vec<u8>);
+
+    impl Hasher for RecordingHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    fn recorded_hash(value: impl Hash) -> Vec<u8> {
+        let mut hasher = RecordingHasher::default();
+        value.hash(&mut hasher);
+        hasher.0
+    }
+
+    #[test]
+    fn test_eq_and_hash() {
+        for (a, b) in wrapped().into_iter().zip(wrapped()) {
+            assert_eq!(a, b);
+            assert_eq!(recorded_hash(&a), recorded_hash(&b));
+        }
+        let a = ZalgoString::new("Zalgo").unwrap().by_decoded();
+        assert_eq!(recorded_hash(&a), b"Zalgo\xff");
+        assert_ne!(a, ZalgoString::new("Zalgo!").unwrap().by_decoded());
+
+        // Values in a sequence do not run into each other when they are hashed.
+        let ab_c = (
+            ZalgoString::new("ab").unwrap().by_decoded(),
+            ZalgoString::new("c").unwrap().by_decoded(),
+        );
+        let a_bc = (
+            ZalgoString::new("a").unwrap().by_decoded(),
+            ZalgoString::new("bc").unwrap().by_decoded(),
+        );
+        assert_ne!(recorded_hash(ab_c), recorded_hash(a_bc));
+    }
+}
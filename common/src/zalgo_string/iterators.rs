@@ -1,18 +1,21 @@
-use crate::{decode_byte_pair, ZalgoString};
+use super::combining_chars;
+use crate::decode_byte_pair;
 use core::iter::FusedIterator;
 
-/// An iterator over the decoded bytes of a [`ZalgoString`].
+/// An iterator over the decoded bytes of a [`ZalgoString`](super::ZalgoString).
 ///
-/// This struct is obtained by calling the [`decoded_bytes`](ZalgoString::decoded_bytes) method on a [`ZalgoString`].
+/// This struct is obtained by calling the [`decoded_bytes`](super::ZalgoString::decoded_bytes) method on a [`ZalgoString`](super::ZalgoString)
+/// or a [`ZalgoArrayString`](crate::ZalgoArrayString).
 /// See its documentation for more.
 #[derive(Debug, Clone)]
 #[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct DecodedBytes<'a>(core::str::Bytes<'a>);
 
 impl<'a> DecodedBytes<'a> {
+    /// Creates an iterator over the decoded bytes of the given encoded string.
     #[inline]
-    pub(crate) fn new(zs: &'a ZalgoString) -> Self {
-        Self(zs.as_combining_chars().bytes())
+    pub(crate) fn new(encoded: &'a str) -> Self {
+        Self(combining_chars(encoded).bytes())
     }
 }
 
@@ -76,17 +79,20 @@ impl DoubleEndedIterator for DecodedBytes<'_> {
 impl FusedIterator for DecodedBytes<'_> {}
 impl ExactSizeIterator for DecodedBytes<'_> {}
 
-/// An iterator over the decoded characters of a [`ZalgoString`].
+/// An iterator over the decoded characters of a [`ZalgoString`](super::ZalgoString).
 ///
-/// This struct is obtained by calling the [`decoded_chars`](ZalgoString::decoded_chars) method on a [`ZalgoString`].
+/// This struct is obtained by calling the [`decoded_chars`](super::ZalgoString::decoded_chars) method on a [`ZalgoString`](super::ZalgoString)
+/// or a [`ZalgoArrayString`](crate::ZalgoArrayString).
 /// See it's documentation for more.
 #[derive(Debug, Clone)]
 #[must_use = "iterators are lazy and do nothing unless consumed"]
 pub struct DecodedChars<'a>(DecodedBytes<'a>);
 
 impl<'a> DecodedChars<'a> {
-    pub(crate) fn new(zs: &'a ZalgoString) -> Self {
-        Self(zs.decoded_bytes())
+    /// Creates an iterator over the decoded characters of the given encoded string.
+    #[inline]
+    pub(crate) fn new(encoded: &'a str) -> Self {
+        Self(DecodedBytes::new(encoded))
     }
 }
 
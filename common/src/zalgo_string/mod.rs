@@ -5,12 +5,17 @@
 //! It can be decoded in-place and the encoded information in other ZalgoStrings can be pushed
 //! onto it.
 
+mod decoded_ord;
 mod iterators;
 
-use crate::{decode_byte_pair, fmt, zalgo_encode, EncodeError};
+use crate::{
+    decode_byte_pair, fmt, validate_combining_chars, validate_encoded, zalgo_encode, DecodeError,
+    EncodeError,
+};
+pub use decoded_ord::DecodedOrd;
 pub use iterators::{DecodedBytes, DecodedChars};
 
-use core::{ops::Index, slice::SliceIndex};
+use core::{ops::Index, slice::SliceIndex, str::FromStr};
 
 use alloc::{borrow::Cow, string::String, vec::Vec};
 
@@ -18,10 +23,14 @@ use alloc::{borrow::Cow, string::String, vec::Vec};
 /// This struct can be decoded in-place and also allows iteration over its characters and bytes, both in
 /// decoded and encoded form.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    all(feature = "serde", not(feature = "serde-compact")),
+    derive(serde::Serialize)
+)]
 #[cfg_attr(
     feature = "rkyv",
-    derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive)
+    derive(rkyv::Serialize, rkyv::Deserialize, rkyv::Archive),
+    rkyv(bytecheck(verify))
 )]
 pub struct ZalgoString(String);
 
@@ -54,10 +63,77 @@ impl ZalgoString {
     /// assert!(ZalgoString::new("\r").is_err());
     /// ```
     #[must_use = "this associated method returns a new `ZalgoString` and does not modify the input"]
+    #[track_caller]
     pub fn new(s: &str) -> Result<Self, EncodeError> {
         zalgo_encode(s).map(Self)
     }
 
+    /// Creates a new `ZalgoString` from a string that has already been encoded with [`zalgo_encode`].
+    ///
+    /// Unlike [`zalgo_decode`](crate::zalgo_decode) this checks that the given string
+    /// actually is an encoding of printable ASCII and newlines, and copies it into a new allocation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is empty, does not begin with an "E",
+    /// has an even length in bytes, contains a character outside the range U+0300–U+036F after the "E",
+    /// or contains a combining character that does not decode into a printable ASCII character or newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{DecodeError, ZalgoString};
+    /// let zs = ZalgoString::from_encoded("E\u{33a}\u{341}\u{34c}\u{347}\u{34f}")?;
+    /// assert_eq!(zs.into_decoded_string(), "Zalgo");
+    /// # Ok::<(), DecodeError>(())
+    /// ```
+    /// Strings that were not produced by [`zalgo_encode`] are rejected:
+    /// ```
+    /// # use zalgo_codec_common::ZalgoString;
+    /// assert!(ZalgoString::from_encoded("Zalgo").is_err());
+    /// assert!(ZalgoString::from_encoded("").is_err());
+    /// ```
+    #[must_use = "this associated method returns a new `ZalgoString` and does not modify the input"]
+    #[track_caller]
+    pub fn from_encoded(encoded: &str) -> Result<Self, DecodeError> {
+        validate_encoded(encoded)?;
+        Ok(Self(String::from(encoded)))
+    }
+
+    /// Creates a new `ZalgoString` from the combining characters of an encoded string,
+    /// i.e. an encoded string without the initial "E", such as the output of
+    /// [`as_combining_chars`](ZalgoString::as_combining_chars).
+    ///
+    /// The indices in the errors refer to positions in the given string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string has an odd length in bytes, contains a character outside the range U+0300–U+036F,
+    /// or contains a combining character that does not decode into a printable ASCII character or newline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{DecodeError, ZalgoString};
+    /// let zs = ZalgoString::from_combining_chars("\u{328}\u{349}")?;
+    /// assert_eq!(zs.into_decoded_string(), "Hi");
+    /// assert_eq!(ZalgoString::from_combining_chars("")?, ZalgoString::default());
+    /// # Ok::<(), DecodeError>(())
+    /// ```
+    /// ```
+    /// # use zalgo_codec_common::ZalgoString;
+    /// assert_eq!(ZalgoString::from_combining_chars("\u{328}i").map_err(|e| e.index()), Err(Some(2)));
+    /// ```
+    #[must_use = "this associated method returns a new `ZalgoString` and does not modify the input"]
+    #[track_caller]
+    pub fn from_combining_chars(combining_chars: &str) -> Result<Self, DecodeError> {
+        validate_combining_chars(combining_chars, 0)?;
+        let mut s = String::with_capacity(combining_chars.len() + 1);
+        s.push('E');
+        s.push_str(combining_chars);
+        Ok(Self(s))
+    }
+
     /// Creates a new `ZalgoString` with at least the specified capacity.
     ///
     /// A ZalgoString always has an allocated buffer with an "E" in it,
@@ -248,7 +324,7 @@ impl ZalgoString {
     /// ```
     #[inline]
     pub fn decoded_chars(&self) -> DecodedChars<'_> {
-        DecodedChars::new(self)
+        DecodedChars::new(&self.0)
     }
 
     /// Converts `self` into a `String`.
@@ -273,6 +349,8 @@ impl ZalgoString {
     /// Decodes `self` into a `String` in-place.
     ///
     /// This method has no effect on the allocated capacity.
+    /// With the `zeroize` feature the part of the buffer that is no longer used is zeroed,
+    /// see [`into_decoded_bytes`](ZalgoString::into_decoded_bytes).
     ///
     /// # Example
     ///
@@ -354,7 +432,7 @@ impl ZalgoString {
     /// ```
     #[inline]
     pub fn decoded_bytes(&self) -> DecodedBytes<'_> {
-        DecodedBytes::new(self)
+        DecodedBytes::new(&self.0)
     }
 
     /// Converts `self` into a byte vector.
@@ -379,6 +457,8 @@ impl ZalgoString {
     /// Decodes `self` into a byte vector in-place.
     ///
     /// This method has no effect on the allocated capacity.
+    /// With the `zeroize` feature the part of the buffer that is no longer used is zeroed,
+    /// so that the encoded string does not remain in the spare capacity of the result.
     ///
     /// # Example
     ///
@@ -397,6 +477,9 @@ impl ZalgoString {
             bytes[w] = decode_byte_pair(bytes[r], bytes[r + 1]);
             w += 1;
         }
+        // Do not leave the encoded string behind in the spare capacity of the decoded one.
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut bytes[w..]);
         bytes.truncate(w);
         bytes
     }
@@ -455,7 +538,7 @@ impl ZalgoString {
     #[inline]
     #[must_use = "the method returns a new value and does not modify `self`"]
     pub fn decoded_len(&self) -> usize {
-        (self.len() - 1) / 2
+        decoded_len(&self.0)
     }
 
     /// Returns whether the string would be empty if decoded.
@@ -495,7 +578,7 @@ impl ZalgoString {
     #[inline]
     #[must_use = "the method returns a new value and does not modify `self`"]
     pub fn as_combining_chars(&self) -> &str {
-        self.0.split_at(1).1
+        combining_chars(&self.0)
     }
 
     /// Converts `self` into a String that contains only the combining characters of the grapheme cluster.
@@ -524,6 +607,28 @@ impl ZalgoString {
         self.0
     }
 
+    /// Wraps `self` in a [`DecodedOrd`], which is compared, ordered, and hashed by the decoded string
+    /// instead of the encoded one.
+    ///
+    /// This is useful for ordering collections like a `BTreeMap` by their human-readable content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use zalgo_codec_common::{EncodeError, ZalgoString};
+    /// let (newline, space) = (ZalgoString::new("\n")?, ZalgoString::new(" ")?);
+    /// // A newline is encoded as U+036F and a space as U+0300.
+    /// assert!(newline > space);
+    /// // But decoded, a newline comes before a space.
+    /// assert!(newline.by_decoded() < space.by_decoded());
+    /// # Ok::<(), EncodeError>(())
+    /// ```
+    #[inline]
+    #[must_use = "`self` will be dropped if the result is not used"]
+    pub fn by_decoded(self) -> DecodedOrd {
+        DecodedOrd(self)
+    }
+
     /// Appends the combining characters of a different `ZalgoString` to the end of `self`.
     ///
     /// # Example
@@ -572,6 +677,7 @@ impl ZalgoString {
     /// assert_eq!(zs.into_decoded_string(), format!("{s1}{s2}"));
     /// # Ok::<(), EncodeError>(())
     /// ```
+    #[track_caller]
     pub fn encode_and_push_str(&mut self, string: &str) -> Result<(), EncodeError> {
         self.push_zalgo_str(&ZalgoString::new(string)?);
         Ok(())
@@ -749,6 +855,365 @@ impl_partial_eq! {String, &str, str, Cow<'_, str>}
 
 // endregion: PartialEq impls
 
+/// Parses a string that has already been encoded with [`zalgo_encode`], see [`ZalgoString::from_encoded`].
+impl FromStr for ZalgoString {
+    type Err = DecodeError;
+
+    #[track_caller]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_encoded(s)
+    }
+}
+
+/// Validates the string in the same way as [`ZalgoString::from_encoded`], but reuses its allocation.
+impl TryFrom<String> for ZalgoString {
+    type Error = DecodeError;
+
+    #[track_caller]
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        validate_encoded(&s)?;
+        Ok(Self(s))
+    }
+}
+
+/// Serializes the encoded string in the same way as a [`String`]: its length in bytes followed by its bytes.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for ZalgoString {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+/// Deserializes a [`String`] and validates it like [`ZalgoString::from_encoded`].
+/// Fails with an error of the kind [`InvalidData`](borsh::io::ErrorKind::InvalidData)
+/// and the message of the [`DecodeError`] if the string is not a valid encoding.
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for ZalgoString {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        use alloc::string::ToString;
+
+        Self::try_from(String::deserialize_reader(reader)?)
+            .map_err(|e| borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+/// Returns the length in bytes of the decoded form of the given encoded string.
+#[inline]
+pub(crate) const fn decoded_len(encoded: &str) -> usize {
+    (encoded.len() - 1) / 2
+}
+
+/// Returns the combining characters of the given encoded string without the initial "E".
+#[inline]
+pub(crate) fn combining_chars(encoded: &str) -> &str {
+    encoded.split_at(1).1
+}
+
+#[cfg(feature = "rkyv")]
+impl ArchivedZalgoString {
+    /// Returns the archived encoded string.
+    #[inline]
+    #[must_use = "the method returns a reference and does not modify `self`"]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Returns the length of the archived `ZalgoString` in bytes if it were to be decoded,
+    /// see [`ZalgoString::decoded_len`].
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn decoded_len(&self) -> usize {
+        decoded_len(self.as_str())
+    }
+
+    /// Returns an iterator over the decoded bytes of the archived `ZalgoString`
+    /// without deserializing it, see [`ZalgoString::decoded_bytes`].
+    #[inline]
+    pub fn decoded_bytes(&self) -> DecodedBytes<'_> {
+        DecodedBytes::new(self.as_str())
+    }
+
+    /// Returns an iterator over the decoded characters of the archived `ZalgoString`
+    /// without deserializing it, see [`ZalgoString::decoded_chars`].
+    #[inline]
+    pub fn decoded_chars(&self) -> DecodedChars<'_> {
+        DecodedChars::new(self.as_str())
+    }
+
+    /// Returns a string slice of just the combining characters of the archived `ZalgoString`
+    /// without the initial "E", see [`ZalgoString::as_combining_chars`].
+    #[inline]
+    #[must_use = "the method returns a new value and does not modify `self`"]
+    pub fn as_combining_chars(&self) -> &str {
+        combining_chars(self.as_str())
+    }
+}
+
+/// Validates the archived string like [`ZalgoString::from_encoded`] when the archive is accessed
+/// with validation, e.g. with [`rkyv::access`], so that hostile bytes can not produce an invalid `ArchivedZalgoString`.
+/// Fails with the [`DecodeError`] as the source of the error if the string is not a valid encoding.
+#[cfg(feature = "rkyv")]
+// SAFETY: `verify` only returns `Ok` if the archived string is a valid encoding,
+// which is the only invariant of a `ZalgoString` beyond those of the string itself.
+unsafe impl<C> rkyv::bytecheck::Verify<C> for ArchivedZalgoString
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+    C::Error: rkyv::rancor::Source,
+{
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        validate_encoded(self.0.as_str()).map_err(<C::Error as rkyv::rancor::Source>::new)
+    }
+}
+
+/// Generates a `ZalgoString` by mapping every byte of the input to a printable ASCII character or a newline
+/// and encoding the result, so that every input produces a valid value.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for ZalgoString {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<u8>()?;
+        Ok(Self::from_arbitrary_bytes(u.bytes(len)?))
+    }
+
+    fn arbitrary_take_rest(u: arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::from_arbitrary_bytes(u.take_rest()))
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl ZalgoString {
+    /// Maps every byte to one of the 95 printable ASCII characters or a newline, and encodes the result.
+    fn from_arbitrary_bytes(bytes: &[u8]) -> Self {
+        let mut encoded = Vec::with_capacity(2 * bytes.len() + 1);
+        encoded.push(b'E');
+        for byte in bytes {
+            let byte = match byte % 96 {
+                95 => b'\n',
+                printable => b' ' + printable,
+            };
+            encoded.extend_from_slice(&crate::encode_byte(byte));
+        }
+        // Safety: the encoding of printable ASCII and newlines is valid UTF-8.
+        Self(unsafe { String::from_utf8_unchecked(encoded) })
+    }
+}
+
+/// Generates a `ZalgoString` that decodes into printable ASCII characters and newlines
+/// whose length is at most the size of the generator.
+///
+/// Shrinking removes decoded characters, so every shrunk value is valid and shorter than the original.
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for ZalgoString {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let len = usize::arbitrary(g) % (g.size() + 1);
+        let alphabet: Vec<u8> = (b' '..=b'~').chain([b'\n']).collect();
+        let decoded: Vec<u8> = (0..len)
+            .map(|_| *g.choose(&alphabet).expect("the alphabet is not empty"))
+            .collect();
+        Self::new(core::str::from_utf8(&decoded).expect("printable ASCII is valid UTF-8"))
+            .expect("printable ASCII characters and newlines can be encoded")
+    }
+
+    fn shrink(&self) -> alloc::boxed::Box<dyn Iterator<Item = Self>> {
+        let decoded = self.clone().into_decoded_string();
+        let len = decoded.len();
+        // Remove chunks of decreasing size, from the whole string down to single characters.
+        let shrunk: Vec<Self> = core::iter::successors(Some(len), |&size| Some(size / 2))
+            .take_while(|&size| size > 0)
+            .flat_map(|size| {
+                (0..=len - size)
+                    .step_by(size)
+                    .map(move |start| (start, size))
+            })
+            .map(|(start, size)| {
+                let mut s = String::with_capacity(len - size);
+                s.push_str(&decoded[..start]);
+                s.push_str(&decoded[start + size..]);
+                Self::new(&s).expect("the decoded string of a `ZalgoString` can be encoded")
+            })
+            .collect();
+        alloc::boxed::Box::new(shrunk.into_iter())
+    }
+}
+
+/// Stores a `ZalgoString` in the same column types as a [`String`].
+#[cfg(feature = "sqlx")]
+impl<DB: sqlx::Database> sqlx::Type<DB> for ZalgoString
+where
+    String: sqlx::Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+/// Encodes the encoded string in the same way as a [`String`].
+#[cfg(feature = "sqlx")]
+impl<'q, DB: sqlx::Database> sqlx::Encode<'q, DB> for ZalgoString
+where
+    String: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::Database>::ArgumentBuffer<'q>,
+    ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
+        self.0.encode_by_ref(buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+/// Decodes a string and validates it like [`ZalgoString::from_encoded`].
+/// Fails with the [`DecodeError`] if the content of the column is not a valid encoding.
+#[cfg(feature = "sqlx")]
+impl<'r, DB: sqlx::Database> sqlx::Decode<'r, DB> for ZalgoString
+where
+    &'r str: sqlx::Decode<'r, DB>,
+{
+    fn decode(
+        value: <DB as sqlx::Database>::ValueRef<'r>,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        Ok(Self::from_encoded(<&str as sqlx::Decode<DB>>::decode(
+            value,
+        )?)?)
+    }
+}
+
+/// Writes the encoded string.
+#[cfg(feature = "defmt")]
+impl defmt::Format for ZalgoString {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}", self.as_str());
+    }
+}
+
+/// Zeroes the whole allocation of the underlying string, including its spare capacity,
+/// and then leaves the `ZalgoString` as an encoding of the empty string without reallocating.
+///
+/// Use [`Zeroizing<ZalgoString>`](zeroize::Zeroizing) or `#[derive(ZeroizeOnDrop)]` on a containing type
+/// to zeroize the string when it is dropped.
+///
+/// Only the current allocation is zeroed. Methods that grow the string, like [`push_zalgo_str`](ZalgoString::push_zalgo_str),
+/// may reallocate it and leave a copy of its old contents in memory that has been freed,
+/// so reserve enough capacity up front if that matters. Likewise, methods that return the underlying buffer,
+/// like [`into_string`](ZalgoString::into_string), hand over the responsibility of zeroizing it to the caller.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for ZalgoString {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+        self.0.push('E');
+    }
+}
+
+/// Serializes the encoded string in human-readable formats like JSON,
+/// and the decoded string in other formats like postcard, where it takes up about half as many bytes.
+#[cfg(feature = "serde-compact")]
+impl serde::Serialize for ZalgoString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(self.as_str())
+        } else {
+            serializer.serialize_str(&self.decoded_chars().collect::<String>())
+        }
+    }
+}
+
+/// Deserializes and validates the encoded string like [`ZalgoString::from_encoded`].
+/// If the format hands over an owned `String` it is validated in-place and kept without copying it.
+///
+/// With the `serde-compact` feature, formats that are not human-readable like postcard contain the decoded string,
+/// which is encoded instead.
+///
+/// Fails with the message of the [`DecodeError`] or [`EncodeError`] if the string is invalid.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ZalgoString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let decoded = cfg!(feature = "serde-compact") && !deserializer.is_human_readable();
+        deserializer.deserialize_string(ZalgoStringVisitor { decoded })
+    }
+}
+
+/// Builds a [`ZalgoString`] from the string that is handed to it by a [`serde::Deserializer`].
+#[cfg(feature = "serde")]
+struct ZalgoStringVisitor {
+    /// Whether the string is the decoded string, which is the case with the `serde-compact` feature
+    /// in formats that are not human-readable.
+    decoded: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ZalgoStringVisitor {
+    type Value = ZalgoString;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.decoded {
+            formatter.write_str("a string of printable ASCII characters and newlines")
+        } else {
+            formatter.write_str("a string that was encoded with `zalgo_encode`")
+        }
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if self.decoded {
+            ZalgoString::new(v).map_err(E::custom)
+        } else {
+            // Validate before copying so that invalid input does not allocate.
+            validate_encoded(v).map_err(E::custom)?;
+            Ok(ZalgoString(v.into()))
+        }
+    }
+
+    fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        // A `ZalgoString` owns its string, so a borrowed one must be copied just like in `visit_str`.
+        self.visit_str(v)
+    }
+
+    fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+        if self.decoded {
+            self.visit_str(&v)
+        } else {
+            ZalgoString::try_from(v).map_err(E::custom)
+        }
+    }
+}
+
+/// Describes a `ZalgoString` as a string with the custom format `"zalgo"`,
+/// and a pattern that matches an "E" followed by combining characters in the range U+0300–U+036F.
+///
+/// The pattern does not check that the combining characters decode into printable ASCII or newlines,
+/// which deserialization with the `serde` feature also does.
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for ZalgoString {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("ZalgoString")
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Cow::Borrowed(concat!(module_path!(), "::ZalgoString"))
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "format": "zalgo",
+            "pattern": r"^E[\u0300-\u036F]*$",
+            "description": "Printable ASCII and newlines that have been encoded into a single grapheme cluster: \
+                an \"E\" followed by two combining characters in the range U+0300–U+036F for every encoded character.",
+            "examples": ["E", "E\u{33a}\u{341}\u{34c}\u{347}\u{34f}"],
+        })
+    }
+}
+
 /// Displays the encoded form of the `ZalgoString`.
 impl fmt::Display for ZalgoString {
     #[inline]
@@ -1020,6 +1485,283 @@ mod test {
         assert_eq!(dcc2.last(), Some('o'));
     }
 
+    #[test]
+    fn test_from_encoded() {
+        let zs = ZalgoString::new("Zalgo\n He comes!").unwrap();
+        assert_eq!(ZalgoString::from_encoded(zs.as_str()).unwrap(), zs);
+        assert_eq!(
+            ZalgoString::from_encoded("E").unwrap(),
+            ZalgoString::default()
+        );
+        assert_eq!(
+            ZalgoString::from_encoded("").unwrap_err().kind(),
+            crate::DecodeErrorKind::EmptyInput
+        );
+    }
+
+    #[test]
+    fn test_validating_constructors() {
+        let zs = ZalgoString::new("Zalgo").unwrap();
+        assert_eq!(zs.as_str().parse::<ZalgoString>().unwrap(), zs);
+        assert_eq!(ZalgoString::try_from(zs.clone().into_string()).unwrap(), zs);
+        assert_eq!(
+            ZalgoString::from_combining_chars(zs.as_combining_chars()).unwrap(),
+            zs
+        );
+
+        // All constructors report the same kind of error for the same problem.
+        let errors = [
+            "E\u{300}ab".parse::<ZalgoString>().unwrap_err(),
+            ZalgoString::try_from(String::from("E\u{300}ab")).unwrap_err(),
+            ZalgoString::from_combining_chars("\u{300}ab").unwrap_err(),
+        ];
+        for err in &errors {
+            assert_eq!(err.kind(), crate::DecodeErrorKind::NotCombiningChar);
+            assert_eq!(err.char(), Some('a'));
+        }
+        assert_eq!(errors[0].index(), Some(3));
+        assert_eq!(errors[1].index(), Some(3));
+        assert_eq!(errors[2].index(), Some(2));
+
+        assert_eq!(
+            ZalgoString::from_combining_chars("\u{300}a")
+                .unwrap_err()
+                .kind(),
+            crate::DecodeErrorKind::DanglingByte
+        );
+        assert_eq!(
+            ZalgoString::from_combining_chars("\u{360}")
+                .unwrap_err()
+                .kind(),
+            crate::DecodeErrorKind::NotPrintableAscii
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_validates() {
+        let zs = ZalgoString::new("Zalgo").unwrap();
+        let json = serde_json::to_string(&zs).unwrap();
+        assert_eq!(serde_json::from_str::<ZalgoString>(&json).unwrap(), zs);
+
+        let err = serde_json::from_str::<ZalgoString>("\"Zalgo\"").unwrap_err();
+        assert!(err.to_string().contains("begins with 'Z' instead of 'E'"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_visitors() {
+        use serde::{
+            de::value::{BorrowedStrDeserializer, Error, StrDeserializer, StringDeserializer},
+            Deserialize,
+        };
+
+        let zs = ZalgoString::new("Zalgo\nHe comes!").unwrap();
+        let json = serde_json::to_string(&zs).unwrap();
+
+        // Borrowed from the input.
+        assert_eq!(serde_json::from_str::<ZalgoString>(&json).unwrap(), zs);
+        // Copied out of the scratch buffer of the reader.
+        assert_eq!(
+            serde_json::from_reader::<_, ZalgoString>(json.as_bytes()).unwrap(),
+            zs
+        );
+        // Handed over as an owned `String`.
+        let value = serde_json::Value::String(zs.to_string());
+        assert_eq!(serde_json::from_value::<ZalgoString>(value).unwrap(), zs);
+
+        assert_eq!(
+            ZalgoString::deserialize(StrDeserializer::<Error>::new(zs.as_str())).unwrap(),
+            zs
+        );
+        assert_eq!(
+            ZalgoString::deserialize(BorrowedStrDeserializer::<Error>::new(zs.as_str())).unwrap(),
+            zs
+        );
+
+        // The owned string is kept without copying it.
+        let owned = zs.to_string();
+        let ptr = owned.as_ptr();
+        let deserialized =
+            ZalgoString::deserialize(StringDeserializer::<Error>::new(owned)).unwrap();
+        assert_eq!(deserialized, zs);
+        assert_eq!(deserialized.as_str().as_ptr(), ptr);
+
+        for err in [
+            ZalgoString::deserialize(StrDeserializer::<Error>::new("Zalgo")).unwrap_err(),
+            ZalgoString::deserialize(BorrowedStrDeserializer::<Error>::new("Zalgo")).unwrap_err(),
+            ZalgoString::deserialize(StringDeserializer::<Error>::new("Zalgo".into())).unwrap_err(),
+        ] {
+            assert!(err.to_string().contains("begins with 'Z' instead of 'E'"));
+        }
+
+        let err = serde_json::from_str::<ZalgoString>("5").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("a string that was encoded with `zalgo_encode`"));
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh() {
+        let zs = ZalgoString::new("Zalgo\nHe comes!").unwrap();
+        let bytes = borsh::to_vec(&zs).unwrap();
+        assert_eq!(bytes, borsh::to_vec(zs.as_str()).unwrap());
+        assert_eq!(borsh::from_slice::<ZalgoString>(&bytes).unwrap(), zs);
+
+        // Replace the leading "E" with a "Z".
+        let mut tampered = bytes.clone();
+        tampered[4] = b'Z';
+        let err = borsh::from_slice::<ZalgoString>(&tampered).unwrap_err();
+        assert_eq!(err.kind(), borsh::io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("begins with 'Z' instead of 'E'"));
+
+        // Replace a combining character with one outside the range U+0300–U+036F.
+        let mut tampered = bytes;
+        tampered[5..7].copy_from_slice("\u{380}".as_bytes());
+        assert!(borsh::from_slice::<ZalgoString>(&tampered).is_err());
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let zs =
+            ZalgoString::arbitrary_take_rest(Unstructured::new(&[58, 65, 76, 71, 79, 95, 255]))
+                .unwrap();
+        assert_eq!(zs.into_decoded_string(), "Zalgo\n_");
+
+        assert_eq!(
+            ZalgoString::arbitrary(&mut Unstructured::new(&[])).unwrap(),
+            ZalgoString::default()
+        );
+
+        // Every input produces a valid `ZalgoString`.
+        let bytes: Vec<u8> = (0..=255).chain((0..=255).rev()).collect();
+        for start in 0..bytes.len() {
+            let mut u = Unstructured::new(&bytes[start..]);
+            while !u.is_empty() {
+                let zs = ZalgoString::arbitrary(&mut u).unwrap();
+                assert_eq!(ZalgoString::from_encoded(zs.as_str()).unwrap(), zs);
+            }
+        }
+    }
+
+    #[cfg(feature = "quickcheck")]
+    mod quickcheck_properties {
+        use super::ZalgoString;
+        use crate::{zalgo_decode, zalgo_encode};
+        use alloc::{format, vec::Vec};
+        use quickcheck::{quickcheck, Arbitrary, Gen};
+
+        quickcheck! {
+            fn round_trip(zs: ZalgoString) -> bool {
+                let decoded = zs.clone().into_decoded_string();
+                zalgo_encode(&decoded).unwrap() == zs.as_str() && zalgo_decode(zs.as_str()).unwrap() == decoded
+            }
+
+            fn decoded_len_is_consistent(zs: ZalgoString) -> bool {
+                zs.decoded_len() == zs.clone().into_decoded_string().len()
+                    && zs.decoded_len() == zs.decoded_chars().count()
+                    && 2 * zs.decoded_len() + 1 == zs.len()
+            }
+
+            fn add_is_associative(a: ZalgoString, b: ZalgoString, c: ZalgoString) -> bool {
+                let left = (a.clone() + &b) + &c;
+                let right = a.clone() + &(b.clone() + &c);
+                let expected = format!(
+                    "{}{}{}",
+                    a.into_decoded_string(),
+                    b.into_decoded_string(),
+                    c.into_decoded_string()
+                );
+                left == right && left.into_decoded_string() == expected
+            }
+        }
+
+        #[test]
+        fn shrinking_terminates() {
+            let zs = ZalgoString::arbitrary(&mut Gen::new(100));
+            let mut current = zs.clone();
+            let mut steps = 0;
+            // Always take the last candidate, which removes the fewest characters.
+            while let Some(shrunk) = current.shrink().last() {
+                assert!(shrunk.decoded_len() < current.decoded_len());
+                assert_eq!(ZalgoString::from_encoded(shrunk.as_str()).unwrap(), shrunk);
+                current = shrunk;
+                steps += 1;
+            }
+            assert_eq!(current, ZalgoString::default());
+            assert_eq!(steps, zs.decoded_len());
+
+            // Every candidate is shorter than the original.
+            let zs = ZalgoString::new("Zalgo").unwrap();
+            let candidates: Vec<ZalgoString> = zs.shrink().collect();
+            assert!(candidates.contains(&ZalgoString::new("").unwrap()));
+            assert!(candidates.contains(&ZalgoString::new("Zalg").unwrap()));
+            assert!(candidates.contains(&ZalgoString::new("algo").unwrap()));
+            assert!(candidates
+                .iter()
+                .all(|c| c.decoded_len() < zs.decoded_len()));
+        }
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schema() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct User {
+            name: ZalgoString,
+            nicknames: Vec<ZalgoString>,
+        }
+
+        let schema = schemars::schema_for!(ZalgoString);
+        assert_eq!(schema.get("title").unwrap(), "ZalgoString");
+        assert_eq!(schema.get("type").unwrap(), "string");
+        assert_eq!(schema.get("format").unwrap(), "zalgo");
+        assert_eq!(schema.get("pattern").unwrap(), r"^E[\u0300-\u036F]*$");
+        assert!(schema
+            .get("description")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .contains("U+0300–U+036F"));
+        let examples = schema.get("examples").unwrap().as_array().unwrap();
+        for example in examples {
+            assert!(ZalgoString::from_encoded(example.as_str().unwrap()).is_ok());
+        }
+
+        // The schema is defined once and referenced by name from the structs that contain the type.
+        let schema = serde_json::to_value(schemars::schema_for!(User)).unwrap();
+        assert_eq!(schema["properties"]["name"]["$ref"], "#/$defs/ZalgoString");
+        assert_eq!(
+            schema["properties"]["nicknames"]["items"]["$ref"],
+            "#/$defs/ZalgoString"
+        );
+        assert_eq!(schema["$defs"]["ZalgoString"]["format"], "zalgo");
+    }
+
+    #[cfg(feature = "serde-compact")]
+    #[test]
+    fn test_serde_compact() {
+        let zs = ZalgoString::new("Zalgo\nHe comes!").unwrap();
+
+        let json = serde_json::to_string(&zs).unwrap();
+        assert_eq!(json, format!("\"{zs}\""));
+        assert_eq!(serde_json::from_str::<ZalgoString>(&json).unwrap(), zs);
+
+        let bytes = postcard::to_allocvec(&zs).unwrap();
+        assert_eq!(bytes, postcard::to_allocvec("Zalgo\nHe comes!").unwrap());
+        assert!(2 * bytes.len() < json.len());
+        assert_eq!(postcard::from_bytes::<ZalgoString>(&bytes).unwrap(), zs);
+
+        // The compact form is validated by encoding it.
+        let bytes = postcard::to_allocvec("Zålgo").unwrap();
+        assert!(postcard::from_bytes::<ZalgoString>(&bytes).is_err());
+    }
+
     #[test]
     fn test_into_combining_chars() {
         let zs = ZalgoString::new("Hi").unwrap();
@@ -1027,4 +1769,143 @@ mod test {
         let zs = ZalgoString::new("").unwrap();
         assert_eq!(zs.into_combining_chars(), "");
     }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_rkyv_validation() {
+        use rkyv::rancor::Error;
+
+        let zs = ZalgoString::new("Zalgo\n He comes!").unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&zs).unwrap();
+        let archived = rkyv::access::<ArchivedZalgoString, Error>(&bytes).unwrap();
+        assert_eq!(archived.0.as_str(), zs.as_str());
+        assert_eq!(
+            rkyv::deserialize::<ZalgoString, Error>(archived).unwrap(),
+            zs
+        );
+
+        let start = bytes
+            .windows(zs.len())
+            .position(|window| window == zs.as_bytes())
+            .unwrap();
+
+        // Replace the leading "E".
+        let mut flipped = bytes.to_vec();
+        flipped[start] ^= 1;
+        assert!(rkyv::access::<ArchivedZalgoString, Error>(&flipped).is_err());
+
+        // Move the first combining character out of the range U+0300–U+036F.
+        let mut flipped = bytes.to_vec();
+        flipped[start + 1] ^= 2;
+        assert!(rkyv::access::<ArchivedZalgoString, Error>(&flipped).is_err());
+
+        // Any single flipped bit that is accepted must still result in a valid encoding.
+        for index in 0..bytes.len() {
+            for bit in 0..8 {
+                let mut flipped = bytes.to_vec();
+                flipped[index] ^= 1 << bit;
+                if let Ok(archived) = rkyv::access::<ArchivedZalgoString, Error>(&flipped) {
+                    assert!(ZalgoString::from_encoded(archived.0.as_str()).is_ok());
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn test_archived_methods() {
+        use rkyv::rancor::Error;
+
+        for s in ["", "Zalgo", "Zalgo\n He comes!"] {
+            let zs = ZalgoString::new(s).unwrap();
+            let bytes = rkyv::to_bytes::<Error>(&zs).unwrap();
+            let archived = rkyv::access::<ArchivedZalgoString, Error>(&bytes).unwrap();
+            assert_eq!(archived.as_str(), zs.as_str());
+            assert_eq!(archived.decoded_len(), s.len());
+            assert_eq!(archived.as_combining_chars(), zs.as_combining_chars());
+            assert_eq!(archived.decoded_chars().collect::<String>(), s);
+            assert!(archived.decoded_bytes().eq(s.bytes()));
+            assert!(archived.decoded_bytes().rev().eq(s.bytes().rev()));
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        use zeroize::Zeroize;
+
+        /// Returns every byte of the allocation of the vector, including its spare capacity.
+        ///
+        /// # Safety
+        ///
+        /// Every byte of the allocation must have been initialized, e.g. because the vector
+        /// was filled to its capacity before it was cleared or truncated.
+        unsafe fn allocation(v: &Vec<u8>) -> &[u8] {
+            // SAFETY: the caller guarantees that the whole allocation is initialized.
+            unsafe { core::slice::from_raw_parts(v.as_ptr(), v.capacity()) }
+        }
+
+        let mut zs = ZalgoString::new("Zalgo\n He comes!").unwrap();
+        let capacity = zs.capacity();
+        assert_eq!(capacity, zs.len());
+        zs.zeroize();
+        assert_eq!(zs, ZalgoString::default());
+        assert_eq!(zs.capacity(), capacity);
+        // SAFETY: the vector is only read, and the string filled its allocation before it was zeroized.
+        let bytes = unsafe { allocation(zs.0.as_mut_vec()) };
+        assert_eq!(bytes[0], b'E');
+        assert!(bytes[1..].iter().all(|&b| b == 0));
+
+        let zs = ZalgoString::new("Zalgo").unwrap();
+        let capacity = zs.capacity();
+        assert_eq!(capacity, zs.len());
+        let decoded = zs.into_decoded_bytes();
+        assert_eq!(decoded.capacity(), capacity);
+        // SAFETY: the encoded string filled its allocation before it was decoded in place.
+        let bytes = unsafe { allocation(&decoded) };
+        assert_eq!(&bytes[..5], b"Zalgo");
+        assert!(bytes[5..].iter().all(|&b| b == 0));
+
+        let mut secret = zeroize::Zeroizing::new(ZalgoString::new("secret").unwrap());
+        secret.push_zalgo_str(&ZalgoString::new("!").unwrap());
+        assert_eq!(secret.decoded_len(), 7);
+    }
+
+    #[cfg(feature = "sqlx")]
+    #[tokio::test]
+    async fn test_sqlx() {
+        use sqlx::{Connection, SqliteConnection};
+
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE messages (id INTEGER PRIMARY KEY, body TEXT NOT NULL)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let zs = ZalgoString::new("Zalgo\n He comes!").unwrap();
+        sqlx::query("INSERT INTO messages (id, body) VALUES (1, ?)")
+            .bind(&zs)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let fetched: ZalgoString = sqlx::query_scalar("SELECT body FROM messages WHERE id = 1")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(fetched, zs);
+
+        sqlx::query("INSERT INTO messages (id, body) VALUES (2, 'Zalgo')")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let err = sqlx::query_scalar::<_, ZalgoString>("SELECT body FROM messages WHERE id = 2")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap_err();
+        let sqlx::Error::ColumnDecode { source, .. } = err else {
+            panic!("expected a column decode error, got {err:?}");
+        };
+        let source = source.downcast_ref::<DecodeError>().unwrap();
+        assert_eq!(source.char(), Some('Z'));
+    }
 }
@@ -0,0 +1,31 @@
+//! Generates the C header of the crate with cbindgen into `OUT_DIR`.
+//!
+//! The copy of the header that is checked in at `include/zalgo_codec.h` is only overwritten
+//! when the environment variable `ZALGO_CODEC_FFI_UPDATE_HEADER` is set,
+//! so that building the crate does not write to its source directory.
+
+use std::{env, path::PathBuf};
+
+const UPDATE_HEADER_VAR: &str = "ZALGO_CODEC_FFI_UPDATE_HEADER";
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed={UPDATE_HEADER_VAR}");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("could not read cbindgen.toml");
+    // The crate is a single file, so it is parsed on its own instead of asking cargo for the metadata of the workspace.
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(crate_dir.join("src").join("lib.rs"))
+        .generate()
+        .expect("could not generate the C header");
+    bindings.write_to_file(out_dir.join("zalgo_codec.h"));
+    if env::var_os(UPDATE_HEADER_VAR).is_some() {
+        bindings.write_to_file(crate_dir.join("include").join("zalgo_codec.h"));
+    }
+}
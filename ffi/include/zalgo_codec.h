@@ -0,0 +1,112 @@
+#ifndef ZALGO_CODEC_H
+#define ZALGO_CODEC_H
+
+/* This file is generated by cbindgen from src/lib.rs. Do not edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The result of a call to one of the functions in the crate.
+ */
+typedef enum ZalgoStatus {
+  /**
+   * The function succeeded and wrote its result to the output pointer.
+   */
+  ZALGO_STATUS_OK = 0,
+  /**
+   * The input or the output pointer was null.
+   */
+  ZALGO_STATUS_NULL_POINTER = 1,
+  /**
+   * The input was not valid UTF-8.
+   * The `index` of the [`ZalgoErrorInfo`] is the byte index of the first invalid byte.
+   */
+  ZALGO_STATUS_INVALID_UTF8 = 2,
+  /**
+   * The input contained a character that can not be encoded.
+   * The [`ZalgoErrorInfo`] contains the character and its position.
+   */
+  ZALGO_STATUS_ENCODE_ERROR = 3,
+  /**
+   * The input was not a valid encoding of printable ASCII and newlines.
+   * The [`ZalgoErrorInfo`] contains the offending character and its byte index if the problem was caused by one.
+   */
+  ZALGO_STATUS_DECODE_ERROR = 4,
+} ZalgoStatus;
+
+/**
+ * The details of a failure, written to the error info pointer by the functions in the crate.
+ *
+ * Fields that do not apply to the failure are zero.
+ */
+typedef struct ZalgoErrorInfo {
+  /**
+   * The line of the offending character, starting at 1.
+   */
+  size_t line;
+  /**
+   * The column of the offending character, starting at 1.
+   */
+  size_t column;
+  /**
+   * The byte index of the offending character in the input.
+   */
+  size_t index;
+  /**
+   * The unicode scalar value of the offending character.
+   */
+  uint32_t character;
+} ZalgoErrorInfo;
+
+/**
+ * Encodes a nul-terminated string of printable ASCII and newlines into a single grapheme cluster,
+ * see [`zalgo_codec_common::zalgo_encode`].
+ *
+ * On success `*output` is set to a newly allocated nul-terminated string that must be freed with [`zalgo_string_free`].
+ * On failure `*output` is set to null, and the details of the failure are written to `*error_info`
+ * if it is not null.
+ *
+ * # Safety
+ *
+ * `input` must be null or point to a nul-terminated string, `output` must be null or valid for writes,
+ * and `error_info` must be null or valid for writes.
+ */
+enum ZalgoStatus zalgo_encode_c(const char *input,
+                                char **output,
+                                struct ZalgoErrorInfo *error_info);
+
+/**
+ * Decodes a nul-terminated string that was encoded by [`zalgo_encode_c`] back into the original string.
+ *
+ * Unlike [`zalgo_codec_common::zalgo_decode`] the input is validated first,
+ * like in [`ZalgoString::from_encoded`], so that the error info names the offending character.
+ *
+ * On success `*output` is set to a newly allocated nul-terminated string that must be freed with [`zalgo_string_free`].
+ * On failure `*output` is set to null, and the details of the failure are written to `*error_info`
+ * if it is not null.
+ *
+ * # Safety
+ *
+ * `input` must be null or point to a nul-terminated string, `output` must be null or valid for writes,
+ * and `error_info` must be null or valid for writes.
+ */
+enum ZalgoStatus zalgo_decode_c(const char *input,
+                                char **output,
+                                struct ZalgoErrorInfo *error_info);
+
+/**
+ * Frees a string that was returned by [`zalgo_encode_c`] or [`zalgo_decode_c`].
+ * Does nothing if `string` is null.
+ *
+ * # Safety
+ *
+ * `string` must be null or a pointer that was written to the output of one of the functions in this crate,
+ * and it must not have been freed already.
+ */
+void zalgo_string_free(char *string);
+
+#endif  /* ZALGO_CODEC_H */
@@ -0,0 +1,221 @@
+//! A C interface to [`zalgo-codec-common`](https://docs.rs/zalgo-codec-common/latest/zalgo_codec_common/),
+//! for converting a string containing only printable ASCII and newlines
+//! into a single unicode grapheme cluster and back from C and other languages that can call C functions.
+//!
+//! Building the crate generates the header `zalgo_codec.h` with [`cbindgen`](https://crates.io/crates/cbindgen) into its `OUT_DIR`,
+//! and produces a shared and a static library to link against.
+//! A copy of the header is checked in at `include/zalgo_codec.h`,
+//! and is updated by building with the environment variable `ZALGO_CODEC_FFI_UPDATE_HEADER` set.
+//!
+//! The strings returned by the functions are allocated by Rust and must be freed with [`zalgo_string_free`].
+//!
+//! # Example
+//!
+//! ```c
+//! #include <stdio.h>
+//! #include "zalgo_codec.h"
+//!
+//! int main(void) {
+//!     char *encoded = NULL;
+//!     ZalgoErrorInfo info;
+//!
+//!     if (zalgo_encode_c("Zalgo", &encoded, &info) != ZALGO_STATUS_OK) {
+//!         return 1;
+//!     }
+//!     printf("%s\n", encoded);
+//!     zalgo_string_free(encoded);
+//!
+//!     if (zalgo_encode_c("Zålgo", &encoded, &info) == ZALGO_STATUS_ENCODE_ERROR) {
+//!         printf("can not encode U+%04X on line %zu at column %zu\n", info.character, info.line, info.column);
+//!     }
+//!     return 0;
+//! }
+//! ```
+
+use core::{
+    ffi::{c_char, CStr},
+    ptr,
+};
+use std::ffi::CString;
+
+use zalgo_codec_common::{zalgo_encode, DecodeError, EncodeError, ZalgoString};
+
+/// The result of a call to one of the functions in the crate.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZalgoStatus {
+    /// The function succeeded and wrote its result to the output pointer.
+    Ok = 0,
+    /// The input or the output pointer was null.
+    NullPointer = 1,
+    /// The input was not valid UTF-8.
+    /// The `index` of the [`ZalgoErrorInfo`] is the byte index of the first invalid byte.
+    InvalidUtf8 = 2,
+    /// The input contained a character that can not be encoded.
+    /// The [`ZalgoErrorInfo`] contains the character and its position.
+    EncodeError = 3,
+    /// The input was not a valid encoding of printable ASCII and newlines.
+    /// The [`ZalgoErrorInfo`] contains the offending character and its byte index if the problem was caused by one.
+    DecodeError = 4,
+}
+
+/// The details of a failure, written to the error info pointer by the functions in the crate.
+///
+/// Fields that do not apply to the failure are zero.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ZalgoErrorInfo {
+    /// The line of the offending character, starting at 1.
+    pub line: usize,
+    /// The column of the offending character, starting at 1.
+    pub column: usize,
+    /// The byte index of the offending character in the input.
+    pub index: usize,
+    /// The unicode scalar value of the offending character.
+    pub character: u32,
+}
+
+impl From<&EncodeError> for ZalgoErrorInfo {
+    fn from(e: &EncodeError) -> Self {
+        Self {
+            line: e.line(),
+            column: e.column(),
+            index: e.index(),
+            character: e.char().into(),
+        }
+    }
+}
+
+impl From<&DecodeError> for ZalgoErrorInfo {
+    fn from(e: &DecodeError) -> Self {
+        Self {
+            index: e.index().unwrap_or_default(),
+            character: e.char().map(u32::from).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Encodes a nul-terminated string of printable ASCII and newlines into a single grapheme cluster,
+/// see [`zalgo_codec_common::zalgo_encode`].
+///
+/// On success `*output` is set to a newly allocated nul-terminated string that must be freed with [`zalgo_string_free`].
+/// On failure `*output` is set to null, and the details of the failure are written to `*error_info`
+/// if it is not null.
+///
+/// # Safety
+///
+/// `input` must be null or point to a nul-terminated string, `output` must be null or valid for writes,
+/// and `error_info` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn zalgo_encode_c(
+    input: *const c_char,
+    output: *mut *mut c_char,
+    error_info: *mut ZalgoErrorInfo,
+) -> ZalgoStatus {
+    // SAFETY: the caller upholds the safety contract of this function, which is the same as that of `convert`.
+    unsafe {
+        convert(input, output, error_info, |s| {
+            zalgo_encode(s).map_err(|e| (ZalgoStatus::EncodeError, ZalgoErrorInfo::from(&e)))
+        })
+    }
+}
+
+/// Decodes a nul-terminated string that was encoded by [`zalgo_encode_c`] back into the original string.
+///
+/// Unlike [`zalgo_codec_common::zalgo_decode`] the input is validated first,
+/// like in [`ZalgoString::from_encoded`], so that the error info names the offending character.
+///
+/// On success `*output` is set to a newly allocated nul-terminated string that must be freed with [`zalgo_string_free`].
+/// On failure `*output` is set to null, and the details of the failure are written to `*error_info`
+/// if it is not null.
+///
+/// # Safety
+///
+/// `input` must be null or point to a nul-terminated string, `output` must be null or valid for writes,
+/// and `error_info` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn zalgo_decode_c(
+    input: *const c_char,
+    output: *mut *mut c_char,
+    error_info: *mut ZalgoErrorInfo,
+) -> ZalgoStatus {
+    // SAFETY: the caller upholds the safety contract of this function, which is the same as that of `convert`.
+    unsafe {
+        convert(input, output, error_info, |s| {
+            ZalgoString::from_encoded(s)
+                .map(ZalgoString::into_decoded_string)
+                .map_err(|e| (ZalgoStatus::DecodeError, ZalgoErrorInfo::from(&e)))
+        })
+    }
+}
+
+/// Frees a string that was returned by [`zalgo_encode_c`] or [`zalgo_decode_c`].
+/// Does nothing if `string` is null.
+///
+/// # Safety
+///
+/// `string` must be null or a pointer that was written to the output of one of the functions in this crate,
+/// and it must not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn zalgo_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the caller guarantees that the pointer came from `CString::into_raw` in `convert`
+        // and has not been freed.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Reads the input as UTF-8, converts it with `f`, and writes the result to `output`
+/// or the details of the failure to `error_info`.
+///
+/// # Safety
+///
+/// See the safety section of [`zalgo_encode_c`].
+unsafe fn convert(
+    input: *const c_char,
+    output: *mut *mut c_char,
+    error_info: *mut ZalgoErrorInfo,
+    f: impl FnOnce(&str) -> Result<String, (ZalgoStatus, ZalgoErrorInfo)>,
+) -> ZalgoStatus {
+    if output.is_null() {
+        return ZalgoStatus::NullPointer;
+    }
+    // SAFETY: `output` is not null, and the caller guarantees that it is valid for writes.
+    unsafe { output.write(ptr::null_mut()) };
+    if input.is_null() {
+        return ZalgoStatus::NullPointer;
+    }
+
+    // SAFETY: `input` is not null, and the caller guarantees that it points to a nul-terminated string.
+    let input = unsafe { CStr::from_ptr(input) };
+    let result = input
+        .to_str()
+        .map_err(|e| {
+            (
+                ZalgoStatus::InvalidUtf8,
+                ZalgoErrorInfo {
+                    index: e.valid_up_to(),
+                    ..Default::default()
+                },
+            )
+        })
+        .and_then(f);
+
+    match result {
+        Ok(converted) => {
+            // The output of the codec only contains printable ASCII, newlines, and combining characters.
+            let converted = CString::new(converted).expect("the codec never outputs a nul byte");
+            // SAFETY: see above.
+            unsafe { output.write(converted.into_raw()) };
+            ZalgoStatus::Ok
+        }
+        Err((status, info)) => {
+            if !error_info.is_null() {
+                // SAFETY: `error_info` is not null, and the caller guarantees that it is valid for writes.
+                unsafe { error_info.write(info) };
+            }
+            status
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Calls the functions of the crate through raw pointers, like a C program would.
+
+use core::{ffi::c_char, ptr};
+use std::ffi::{CStr, CString};
+
+use zalgo_codec_ffi::{
+    zalgo_decode_c, zalgo_encode_c, zalgo_string_free, ZalgoErrorInfo, ZalgoStatus,
+};
+
+type Function =
+    unsafe extern "C" fn(*const c_char, *mut *mut c_char, *mut ZalgoErrorInfo) -> ZalgoStatus;
+
+/// Calls the function with the input, and returns its status, its output, and the error info it wrote.
+fn call(function: Function, input: &[u8]) -> (ZalgoStatus, Option<String>, ZalgoErrorInfo) {
+    let input = CString::new(input).unwrap();
+    let mut output = ptr::null_mut();
+    let mut info = ZalgoErrorInfo::default();
+    let status = unsafe { function(input.as_ptr(), &mut output, &mut info) };
+    let output = (!output.is_null()).then(|| {
+        let string = unsafe { CStr::from_ptr(output) }
+            .to_str()
+            .unwrap()
+            .to_owned();
+        unsafe { zalgo_string_free(output) };
+        string
+    });
+    (status, output, info)
+}
+
+#[test]
+fn round_trip() {
+    let (status, encoded, _) = call(zalgo_encode_c, b"Zalgo\nHe comes!");
+    assert_eq!(status, ZalgoStatus::Ok);
+    let encoded = encoded.unwrap();
+    assert!(encoded.starts_with("E\u{33a}\u{341}\u{34c}\u{347}\u{34f}"));
+
+    let (status, decoded, _) = call(zalgo_decode_c, encoded.as_bytes());
+    assert_eq!(status, ZalgoStatus::Ok);
+    assert_eq!(decoded.as_deref(), Some("Zalgo\nHe comes!"));
+}
+
+#[test]
+fn encode_error() {
+    let (status, output, info) = call(zalgo_encode_c, "Zalgo\nZålgo".as_bytes());
+    assert_eq!(status, ZalgoStatus::EncodeError);
+    assert_eq!(output, None);
+    assert_eq!(
+        info,
+        ZalgoErrorInfo {
+            line: 2,
+            column: 2,
+            index: 7,
+            character: u32::from('å'),
+        }
+    );
+}
+
+#[test]
+fn decode_error() {
+    let (status, output, info) = call(zalgo_decode_c, b"Zalgo");
+    assert_eq!(status, ZalgoStatus::DecodeError);
+    assert_eq!(output, None);
+    assert_eq!(info.index, 0);
+    assert_eq!(info.character, u32::from('Z'));
+}
+
+#[test]
+fn invalid_utf8() {
+    let (status, output, info) = call(zalgo_encode_c, b"Zal\xffgo");
+    assert_eq!(status, ZalgoStatus::InvalidUtf8);
+    assert_eq!(output, None);
+    assert_eq!(info.index, 3);
+
+    let (status, _, _) = call(zalgo_decode_c, b"E\xcc");
+    assert_eq!(status, ZalgoStatus::InvalidUtf8);
+}
+
+#[test]
+fn null_pointers() {
+    let input = CString::new("Zalgo").unwrap();
+    let mut output = ptr::null_mut();
+    let status = unsafe { zalgo_encode_c(input.as_ptr(), ptr::null_mut(), ptr::null_mut()) };
+    assert_eq!(status, ZalgoStatus::NullPointer);
+    let status = unsafe { zalgo_decode_c(ptr::null(), &mut output, ptr::null_mut()) };
+    assert_eq!(status, ZalgoStatus::NullPointer);
+    assert!(output.is_null());
+
+    // The error info is optional.
+    let input = CString::new("Zålgo").unwrap();
+    let status = unsafe { zalgo_encode_c(input.as_ptr(), &mut output, ptr::null_mut()) };
+    assert_eq!(status, ZalgoStatus::EncodeError);
+    assert!(output.is_null());
+}
+
+#[test]
+fn free() {
+    let input = CString::new("Zalgo").unwrap();
+    let mut output = ptr::null_mut();
+    let status = unsafe { zalgo_encode_c(input.as_ptr(), &mut output, ptr::null_mut()) };
+    assert_eq!(status, ZalgoStatus::Ok);
+    assert!(!output.is_null());
+    unsafe { zalgo_string_free(output) };
+
+    // Freeing null does nothing.
+    unsafe { zalgo_string_free(ptr::null_mut()) };
+}
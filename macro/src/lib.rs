@@ -1,5 +1,6 @@
 //! This crate provides the proc-macro part of the crate [`zalgo-codec`](https://docs.rs/zalgo-codec/latest/zalgo_codec/)
-//! by defining the procedural macro [`zalgo_embed!`].
+//! by defining the procedural macros [`zalgo_embed!`], [`zalgo_embed_file!`], [`zalgofy!`], [`zalgofy_file!`], and [`zalgo_decodify!`],
+//! and the attribute macros [`macro@zalgo_embed_mod`] and [`macro@zalgofy_strings`].
 //!
 //! It lets you take source code that's been converted into a single grapheme cluster by the
 //! [`zalgo-codec-common`](https://docs.rs/zalgo-codec-common/latest/zalgo_codec_common/) crate
@@ -20,12 +21,37 @@
 #![forbid(unsafe_code)]
 
 extern crate alloc;
+extern crate std;
 
-use alloc::format;
-use proc_macro::TokenStream;
-use syn::{parse_macro_input, spanned::Spanned, Error, LitStr};
+use core::fmt::Display;
 
-use zalgo_codec_common::{zalgo_decode, zalgo_encode};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    path::{self, Path, PathBuf},
+};
+use syn::{
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{ParseStream, Parser},
+    parse_macro_input, token,
+    visit_mut::{self, VisitMut},
+    Attribute, ConstParam, Error, Expr, ExprConst, ExprLit, File, GenericArgument, Ident,
+    ImplItemConst, ImplItemFn, Item, ItemConst, ItemFn, ItemStatic, Lit, LitStr, Macro, Pat, Token,
+    TraitItemConst, TraitItemFn, Type,
+};
+
+use zalgo_codec_common::{zalgo_decode, zalgo_encode, ZalgoString};
 
 /// This macro decodes a string that has been encoded with [`zalgo_encode`](https://docs.rs/zalgo-codec-common/latest/zalgo_codec_common/fn.zalgo_encode.html)
 /// and passes the results on to the compiler.
@@ -83,31 +109,1032 @@ use zalgo_codec_common::{zalgo_decode, zalgo_encode};
 /// ```
 /// To do something more like `obfstr`, use [`zalgofy!`].
 ///
+/// A long encoded string can be split into several comma separated string literals, which are decoded as if they were one.
+/// Every literal after the first may leave out the leading "E".
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed;
+/// // These literals are the encoding of `fn add(x: i32, y: i32) -> i32 {x + y}` split into three parts.
+/// zalgo_embed!(
+///     "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}",
+///     "\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}",
+///     "\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}",
+/// );
+/// assert_eq!(add(10, 20), 30);
+/// ```
+///
+/// If the literals are complete encodings of their own, e.g. of items that are encoded one by one,
+/// they can be given after `items:`. Every literal is then decoded on its own, and the decoded code
+/// is joined with line breaks in between:
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed;
+/// // These literals are the encodings of `fn add(x: i32, y: i32) -> i32 {x + y}`
+/// // and `fn sub(x: i32, y: i32) -> i32 {x - y}`.
+/// zalgo_embed!(
+///     items:
+///     "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}",
+///     "E\u{346}\u{34e}\u{300}\u{353}\u{355}\u{342}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30d}\u{300}\u{359}\u{35d}",
+/// );
+/// assert_eq!(add(10, 20), 30);
+/// assert_eq!(sub(10, 20), -10);
+/// ```
+/// An error in one of the literals says which of them it is.
+///
+/// Since a proc-macro receives its input before any macros in it are expanded, the built-in macros
+/// `concat!`, `include_str!`, and `env!` are evaluated by this macro itself. This lets the encoded
+/// string be assembled from constants, read from a file, or set by a build script:
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed;
+/// zalgo_embed!(concat!(
+///     "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}",
+///     "\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}",
+///     "\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}",
+/// ));
+/// assert_eq!(add(10, 20), 30);
+/// ```
+/// Unlike the real `include_str!`, the path given to `include_str!` is relative to the manifest directory
+/// of the crate that uses the macro, like the path given to [`zalgo_embed_file!`].
+/// Trailing whitespace is removed from every string, so the final line ending of an included file does not matter.
+///
+/// # Crate paths
+///
+/// Encoded code that refers to a crate stops working if the crate is renamed in the `Cargo.toml` of the crate
+/// that uses the macro. To avoid this the encoded code can use the identifier `__zalgo_crate__` in place of the
+/// path to the crate, and the path can be given to the macro with a `crate = path` argument in front of the strings.
+/// Every `__zalgo_crate__` in the decoded code is then replaced by the path before the code is compiled.
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed;
+/// use zalgo_codec_common as zc;
+///
+/// // This line expands to the code
+/// // `zc::zalgo_encode("Zalgo").unwrap()`
+/// let encoded = zalgo_embed!(crate = zc, "E\u{33f}\u{33f}\u{35a}\u{341}\u{34c}\u{347}\u{34f}\u{33f}\u{343}\u{352}\u{341}\u{354}\u{345}\u{33f}\u{33f}\u{31a}\u{31a}\u{35a}\u{341}\u{34c}\u{347}\u{34f}\u{33f}\u{345}\u{34e}\u{343}\u{34f}\u{344}\u{345}\u{308}\u{302}\u{33a}\u{341}\u{34c}\u{347}\u{34f}\u{302}\u{309}\u{30e}\u{355}\u{34e}\u{357}\u{352}\u{341}\u{350}\u{308}\u{309}");
+/// assert_eq!(encoded, zc::zalgo_encode("Zalgo").unwrap());
+/// ```
+/// If they are given, the `debug` and `emit` flags come first, then the `crate` argument, and then `items:`.
+///
+/// # Attributes
+///
+/// Outer attributes in front of the arguments are put in front of every item in the decoded code.
+/// This lets you e.g. allow lints or add `#[cfg(test)]` to the items without encoding the code again:
+/// ```
+/// #![deny(dead_code)]
+/// # use zalgo_codec_macro::zalgo_embed;
+/// // This line expands to the code
+/// // `#[allow(dead_code)] #[doc(hidden)] fn add(x: i32, y: i32) -> i32 {x + y}`
+/// zalgo_embed!(#[allow(dead_code)] #[doc(hidden)] "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}");
+/// # fn main() {}
+/// ```
+/// The attributes come before any other arguments. Attributes are not allowed on expressions,
+/// so a macro call in expression position with attributes results in a compile error.
+///
+/// # Debugging
+///
+/// Compiler errors and panics in embedded code point at code that can not be read.
+/// If the first argument is `debug` the macro also writes the decoded source to a file in the directory
+/// `zalgo_embed` in the `OUT_DIR` of the crate if it has a build script, and otherwise in its target directory.
+/// The compiler then emits a deprecation warning that contains the path to the file.
+/// The warning comes from items that the macro emits, so in this mode it must be used where items are allowed.
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed;
+/// // warning: use of deprecated constant: zalgo_embed! wrote the decoded source to `.../zalgo_embed/....rs`
+/// zalgo_embed!(debug, "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}");
+/// assert_eq!(add(10, 20), 30);
+/// ```
+///
+/// Tools like debuggers and coverage tools can not show code that only exists in the expansion of a macro.
+/// If the first argument is `emit`, or if the environment variable `ZALGO_EMBED_EMIT_SOURCES` is set
+/// when the crate is compiled, the macro instead writes the decoded source to a file and includes it
+/// with `include!`, so that the code points into a file that can be read.
+/// The file is named after a hash of the source, so it is the same every time the crate is compiled.
+/// With the `emit` flag it is written to the same directory as in debug mode,
+/// and with the environment variable it is written to the directory given by the variable,
+/// relative to the directory that contains the `Cargo.toml` of the crate.
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed;
+/// // This line expands to `include!(".../zalgo_embed/....rs")` of a file with the code
+/// // `fn add(x: i32, y: i32) -> i32 {x + y}`
+/// zalgo_embed!(emit, "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}");
+/// # fn main() {
+/// assert_eq!(add(10, 20), 30);
+/// # }
+/// ```
+/// Since `include!` can only include items or a single expression, and can not be used as a statement,
+/// the macro must be used where items are allowed or as an expression in this mode. The compiler does not know that the crate depends on the environment variable,
+/// so the crate must be compiled again for some other reason when the variable is changed.
+///
 /// # Errors
 ///
-/// This macro utilizes [`zalgo_decode`] internally, and converts its errors
-/// into compile errors. E.g. it will most likely result in a compile error if you
+/// This macro validates its input in the same way as [`ZalgoString::from_encoded`], and converts its errors
+/// into compile errors. E.g. it results in a compile error if you
 /// use this macro on a string that was not generated by [`zalgo_encode`]:
 /// ```compile_fail
 /// # use zalgo_codec_macro::zalgo_embed;
-/// // compile error: the given string decodes into an invalid utf-8 sequence of 1 bytes from index 0
+/// // compile error: could not decode the string because it begins with 'Z' instead of 'E'
 /// zalgo_embed!("Zalgo");
 /// ```
+/// Every literal is checked on its own, so the errors point at the literals that can not be decoded.
+///
+/// If the decoded code can not be split into tokens, e.g. because a delimiter is never closed,
+/// the error shows the decoded code around the problem so that it can be fixed and encoded again.
+/// Other syntax errors in the decoded code are found by the compiler after the macro has expanded,
+/// use the [debug mode](#debugging) to see the code they refer to.
+///
+/// Any other macro than the ones listed above results in a compile error:
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgo_embed;
+/// // compile error: `stringify!` is not supported
+/// zalgo_embed!(stringify!(x + y));
+/// ```
 #[proc_macro]
 pub fn zalgo_embed(encoded: TokenStream) -> TokenStream {
-    let encoded = parse_macro_input!(encoded as LitStr).value();
-
-    match zalgo_decode(&encoded) {
-        Ok(decoded) => match decoded.parse() {
+    let (attributes, encoded) = split_attributes(encoded);
+    let arguments = parse_macro_input!(encoded with parse_embed_arguments);
+    let (decoded, mut token_stream) = match decode_literals(&arguments.literals, arguments.items) {
+        Ok(decoded) => decoded,
+        Err(e) => return e.into_compile_error().into(),
+    };
+    let transformed = arguments.crate_path.is_some() || !attributes.is_empty();
+    if let Some(path) = arguments.crate_path {
+        match path.parse() {
+            Ok(path) => token_stream = replace_crate_marker(token_stream, &path),
+            Err(e) => {
+                return Error::new(Span::call_site().into(), e)
+                    .into_compile_error()
+                    .into()
+            }
+        }
+    }
+    if !attributes.is_empty() {
+        token_stream = match add_attributes(token_stream, attributes) {
             Ok(token_stream) => token_stream,
-            Err(e) => Error::new(encoded.span(), e).into_compile_error().into(),
-        },
-        Err(e) => Error::new(
-            encoded.span(),
-            format!("the given string decodes into an {e}"),
+            Err(e) => return e.into_compile_error().into(),
+        };
+    }
+
+    let emit_dir = emit_sources_dir().or_else(|| arguments.emit.then(default_source_dir));
+    if let Some(dir) = emit_dir {
+        // The file must contain the code after the substitutions, which is only available as tokens.
+        let source = if transformed {
+            token_stream.to_string()
+        } else {
+            decoded.clone()
+        };
+        token_stream = match include_source(&dir, &source) {
+            Ok(include) => include,
+            Err(e) => return e.into_compile_error().into(),
+        };
+    }
+
+    if arguments.debug {
+        match write_debug_source(&decoded) {
+            Ok(note) => note.into_iter().chain(token_stream).collect(),
+            Err(e) => e.into_compile_error().into(),
+        }
+    } else {
+        token_stream
+    }
+}
+
+/// Splits the outer attributes, like `#[allow(dead_code)]`, at the start of the tokens from the rest of them.
+fn split_attributes(tokens: TokenStream) -> (TokenStream, TokenStream) {
+    let trees: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut len = 0;
+    while let [TokenTree::Punct(pound), TokenTree::Group(group), ..] = &trees[len..] {
+        if pound.as_char() != '#' || group.delimiter() != Delimiter::Bracket {
+            break;
+        }
+        len += 2;
+    }
+    let mut trees = trees.into_iter();
+    let attributes = trees.by_ref().take(len).collect();
+    (attributes, trees.collect())
+}
+
+/// Parses the tokens as the items of a file, and puts the given attributes in front of the other attributes of every item.
+fn add_attributes(tokens: TokenStream, attributes: TokenStream) -> Result<TokenStream, Error> {
+    let attributes = Attribute::parse_outer.parse(attributes)?;
+    let mut file: File = syn::parse(tokens).map_err(|e| {
+        Error::new(
+            e.span(),
+            format!("attributes can only be added to items, but the decoded code is not a list of items: {e}"),
         )
-        .into_compile_error()
-        .into(),
+    })?;
+    for item in &mut file.items {
+        match item_attributes(item) {
+            Some(item_attributes) => {
+                item_attributes.splice(0..0, attributes.iter().cloned());
+            }
+            // Tokens that `syn` does not understand as any other item.
+            None => {
+                let mut tokens: TokenStream = attributes
+                    .iter()
+                    .map(|attribute| TokenStream::from(attribute.to_token_stream()))
+                    .collect();
+                tokens.extend(TokenStream::from(item.to_token_stream()));
+                *item = Item::Verbatim(tokens.into());
+            }
+        }
+    }
+    Ok(file.into_token_stream().into())
+}
+
+/// Returns the attributes of the item, or `None` if it is an item that `syn` could not parse.
+fn item_attributes(item: &mut Item) -> Option<&mut Vec<Attribute>> {
+    match item {
+        Item::Const(item) => Some(&mut item.attrs),
+        Item::Enum(item) => Some(&mut item.attrs),
+        Item::ExternCrate(item) => Some(&mut item.attrs),
+        Item::Fn(item) => Some(&mut item.attrs),
+        Item::ForeignMod(item) => Some(&mut item.attrs),
+        Item::Impl(item) => Some(&mut item.attrs),
+        Item::Macro(item) => Some(&mut item.attrs),
+        Item::Mod(item) => Some(&mut item.attrs),
+        Item::Static(item) => Some(&mut item.attrs),
+        Item::Struct(item) => Some(&mut item.attrs),
+        Item::Trait(item) => Some(&mut item.attrs),
+        Item::TraitAlias(item) => Some(&mut item.attrs),
+        Item::Type(item) => Some(&mut item.attrs),
+        Item::Union(item) => Some(&mut item.attrs),
+        Item::Use(item) => Some(&mut item.attrs),
+        _ => None,
+    }
+}
+
+/// The arguments of [`zalgo_embed!`].
+struct EmbedArguments {
+    /// Whether the decoded source should be written to a file.
+    debug: bool,
+    /// Whether the decoded source should be written to a file and included from there.
+    emit: bool,
+    /// The path that replaces every [`CRATE_MARKER`] in the decoded code.
+    crate_path: Option<String>,
+    /// Whether every literal is a complete encoding of its own.
+    items: bool,
+    /// The encoded strings.
+    literals: Vec<LitStr>,
+}
+
+/// Parses the arguments of [`zalgo_embed!`]: an optional `debug` flag followed by a comma,
+/// an optional `crate = path` argument followed by a comma, and the literals that are parsed by [`parse_literals`].
+fn parse_embed_arguments(input: ParseStream) -> syn::Result<EmbedArguments> {
+    let mut debug = false;
+    let mut emit = false;
+    while let Some(flag) = parse_flag(input, &["debug", "emit"])? {
+        match flag.as_str() {
+            "debug" => debug = true,
+            _ => emit = true,
+        }
+    }
+
+    let crate_path = parse_crate_path(input)?;
+    if crate_path.is_some() {
+        input.parse::<Token![,]>()?;
+    }
+
+    let items = input.peek(Ident) && input.peek2(Token![:]);
+    if items {
+        let mode: Ident = input.parse()?;
+        if mode != "items" {
+            return Err(Error::new(mode.span(), "expected `items` or a string"));
+        }
+        input.parse::<Token![:]>()?;
+    }
+
+    Ok(EmbedArguments {
+        debug,
+        emit,
+        crate_path,
+        items,
+        literals: parse_literals(input)?,
+    })
+}
+
+/// Parses a `crate = path` argument if there is one, and returns the path.
+fn parse_crate_path(input: ParseStream) -> syn::Result<Option<String>> {
+    if !(input.peek(Token![crate]) && input.peek2(Token![=])) {
+        return Ok(None);
+    }
+    input.parse::<Token![crate]>()?;
+    input.parse::<Token![=]>()?;
+    let mut path = String::new();
+    if input.peek(Token![::]) {
+        input.parse::<Token![::]>()?;
+        path.push_str("::");
+    }
+    // The path may start with a keyword like `crate` or `super`.
+    path.push_str(&input.call(Ident::parse_any)?.to_string());
+    while input.peek(Token![::]) {
+        input.parse::<Token![::]>()?;
+        path.push_str("::");
+        path.push_str(&input.call(Ident::parse_any)?.to_string());
+    }
+    Ok(Some(path))
+}
+
+/// The identifier in decoded code that is replaced by the path given to [`zalgo_embed!`] with `crate = path`.
+const CRATE_MARKER: &str = "__zalgo_crate__";
+
+/// Replaces every [`CRATE_MARKER`] identifier in the tokens, also inside of groups, with the given path.
+fn replace_crate_marker(tokens: TokenStream, path: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|tree| match tree {
+            TokenTree::Ident(ident) if ident.to_string() == CRATE_MARKER => path.clone(),
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    replace_crate_marker(group.stream(), path),
+                );
+                replaced.set_span(group.span());
+                TokenTree::Group(replaced).into()
+            }
+            tree => tree.into(),
+        })
+        .collect()
+}
+
+/// Parses a flag with one of the given names followed by a comma, and returns its name if it was there.
+///
+/// An identifier followed by a comma is always parsed as a flag, so any other name is an error.
+fn parse_flag(input: ParseStream, names: &[&str]) -> syn::Result<Option<String>> {
+    if !(input.peek(Ident) && input.peek2(Token![,])) {
+        return Ok(None);
+    }
+    let flag: Ident = input.parse()?;
+    let flag_name = flag.to_string();
+    if !names.contains(&flag_name.as_str()) {
+        let expected = match names {
+            [name] => format!("`{name}` or a string"),
+            [names @ .., last] => {
+                let names: Vec<String> = names.iter().map(|name| format!("`{name}`, ")).collect();
+                format!("{}`{last}`, or a string", names.concat())
+            }
+            [] => "a string".to_string(),
+        };
+        return Err(Error::new(flag.span(), format!("expected {expected}")));
+    }
+    input.parse::<Token![,]>()?;
+    Ok(Some(flag_name))
+}
+
+/// The environment variable that makes [`zalgo_embed!`] write the decoded source of every call to a directory
+/// and include it from there.
+const EMIT_SOURCES_VAR: &str = "ZALGO_EMBED_EMIT_SOURCES";
+
+/// Returns the directory that [`zalgo_embed!`] writes decoded source to if no other directory is given.
+///
+/// This is a `zalgo_embed` directory in the `OUT_DIR` of the crate that uses the macro if it has one,
+/// and otherwise in its target directory.
+fn default_source_dir() -> PathBuf {
+    match env::var_os("OUT_DIR").or_else(|| env::var_os("CARGO_TARGET_DIR")) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default()).join("target"),
+    }
+    .join("zalgo_embed")
+}
+
+/// Returns the directory given by [`EMIT_SOURCES_VAR`], relative to the manifest directory
+/// of the crate that uses the macro, if the variable is set.
+fn emit_sources_dir() -> Option<PathBuf> {
+    let dir = env::var_os(EMIT_SOURCES_VAR).filter(|dir| !dir.is_empty())?;
+    Some(PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default()).join(dir))
+}
+
+/// Writes source code to a file in the given directory, and returns the absolute path to the file
+/// and the hash of the source that the file is named after.
+///
+/// Since the file is named after the hash, every call with different source gets a file of its own,
+/// and a call with the same source writes to the same file every time.
+fn write_source(dir: &Path, source: &str) -> Result<(PathBuf, u64), Error> {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    let hash = hasher.finish();
+
+    let path = dir.join(format!("{hash:016x}.rs"));
+    match fs::create_dir_all(dir)
+        .and_then(|()| fs::write(&path, source))
+        .and_then(|()| path::absolute(&path))
+    {
+        Ok(path) => Ok((path, hash)),
+        Err(e) => Err(Error::new(
+            Span::call_site().into(),
+            format!(
+                "failed to write the decoded source to `{}`: {e}",
+                path.display()
+            ),
+        )),
+    }
+}
+
+/// Writes the decoded source of a [`zalgo_embed!`] call in debug mode to a file in the [`default_source_dir`],
+/// and returns items that make the compiler emit a warning that contains the path to the file.
+fn write_debug_source(source: &str) -> Result<TokenStream, Error> {
+    let (path, hash) = write_source(&default_source_dir(), source)?;
+
+    // Using a deprecated item is the only way to emit a warning on stable.
+    let note = format!(
+        "zalgo_embed! wrote the decoded source to `{}`",
+        path.display()
+    );
+    let name = format!("__ZALGO_EMBED_DEBUG_{hash:016X}");
+    match format!(
+        "#[deprecated(note = {})] #[doc(hidden)] const {name}: () = (); const _: () = {name};",
+        Literal::string(&note)
+    )
+    .parse()
+    {
+        Ok(token_stream) => Ok(token_stream),
+        Err(e) => Err(Error::new(Span::call_site().into(), e)),
+    }
+}
+
+/// Writes the source to a file in the given directory, and returns an invocation of `include!` that includes it.
+fn include_source(dir: &Path, source: &str) -> Result<TokenStream, Error> {
+    let (path, _) = write_source(dir, source)?;
+    // With braces it needs no `;` where items are allowed.
+    format!(
+        "include! {{ {} }}",
+        Literal::string(&path.to_string_lossy())
+    )
+    .parse()
+    .map_err(|e| Error::new(Span::call_site().into(), e))
+}
+
+/// Decodes the given literals as if they were one encoded string, as described in [`zalgo_embed!`],
+/// and parses the result into tokens.
+///
+/// Returns the decoded source together with its tokens.
+/// The errors point at the characters in the literals that caused them, see [`error_at`].
+fn decode_literals(literals: &[LitStr], items: bool) -> Result<(String, TokenStream), Error> {
+    let mut decoded = String::new();
+    // The range in `decoded` that each literal decodes to, and the number of characters in front of its encoded bytes.
+    let mut parts = Vec::with_capacity(literals.len());
+    let mut errors: Option<Error> = None;
+    for (i, literal) in literals.iter().enumerate() {
+        let value = literal.value();
+        // Whitespace is never part of an encoding, so the final line ending of an included file can be ignored.
+        let fragment = value.trim_end();
+        // Every literal after the first may leave out the leading "E", unless they are all complete encodings.
+        let prefixed = !items && i > 0 && !fragment.starts_with('E');
+        let result = if prefixed {
+            ZalgoString::from_encoded(&format!("E{fragment}"))
+        } else {
+            ZalgoString::from_encoded(fragment)
+        };
+        match result {
+            Ok(part) => {
+                if items && i > 0 {
+                    decoded.push('\n');
+                }
+                let start = decoded.len();
+                decoded.push_str(&part.into_decoded_string());
+                parts.push((start..decoded.len(), literal, usize::from(!prefixed)));
+            }
+            Err(e) => {
+                let message = if items {
+                    format!("string {} of {}: {e}", i + 1, literals.len())
+                } else {
+                    e.to_string()
+                };
+                let error = match e.index() {
+                    Some(index) => error_at(literal, index - usize::from(prefixed), message),
+                    None => Error::new(literal.span(), message),
+                };
+                match errors.as_mut() {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+    }
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    // The compiler does not say where the code fails to parse, and panics on delimiters that do not match,
+    // so the common problems are looked for first.
+    if let Some((index, problem)) = find_lex_error(&decoded) {
+        let message = format!(
+            "the decoded code can not be parsed: {problem}{}",
+            describe_decoded(&decoded, Some(index))
+        );
+        // Every decoded byte except the line breaks between items comes from one character in the literal it was decoded from.
+        let (range, literal, skipped) = parts
+            .iter()
+            .rfind(|(range, ..)| range.start <= index)
+            .expect("the first literal starts at the beginning of the decoded code");
+        return Err(
+            match literal
+                .value()
+                .char_indices()
+                .nth(index - range.start + skipped)
+            {
+                Some((byte, _)) => error_at(literal, byte, message),
+                None => Error::new(literal.span(), message),
+            },
+        );
+    }
+
+    match decoded.parse() {
+        Ok(token_stream) => Ok((decoded, token_stream)),
+        Err(e) => {
+            let message = format!("{e}{}", describe_decoded(&decoded, None));
+            let span = match literals {
+                [literal] => literal.span(),
+                _ => Span::call_site().into(),
+            };
+            Err(Error::new(span, message))
+        }
+    }
+}
+
+/// The number of lines in front of the line with a problem that [`describe_decoded`] shows.
+const CONTEXT_LINES: usize = 2;
+
+/// The length of the longest decoded code that [`describe_decoded`] shows in full.
+const MAX_SHOWN_LEN: usize = 512;
+
+/// Shows the decoded code that a problem was found in, so that it can be fixed and encoded again.
+///
+/// If the byte index of the problem is known, the line that contains it and the [`CONTEXT_LINES`] lines
+/// in front of it are shown with a caret under the problem. Otherwise the whole code is shown if it is short.
+/// The result is meant to be appended to an error message, and is empty if there is nothing to show.
+fn describe_decoded(decoded: &str, index: Option<usize>) -> String {
+    match index {
+        Some(index) => {
+            let line = decoded[..index].matches('\n').count();
+            // Decoded code is ASCII, so every byte is a column.
+            let column = index
+                - decoded[..index]
+                    .rfind('\n')
+                    .map_or(0, |newline| newline + 1);
+            let width = (line + 1).to_string().len();
+
+            let mut description = format!(
+                "\n\ndecoded code, line {}, column {}:",
+                line + 1,
+                column + 1
+            );
+            for (number, text) in decoded
+                .split('\n')
+                .enumerate()
+                .take(line + 1)
+                .skip(line.saturating_sub(CONTEXT_LINES))
+            {
+                description.push_str(&format!("\n{:>width$} | {text}", number + 1));
+            }
+            description.push_str(&format!("\n{:width$} | {:column$}^", "", ""));
+            description
+        }
+        None if decoded.len() <= MAX_SHOWN_LEN => format!("\n\ndecoded code:\n{decoded}"),
+        None => String::new(),
+    }
+}
+
+/// Creates an error that points at the character at the given byte index in the value of the literal.
+///
+/// This is only possible when the value is written out as it is in the source code, i.e. without escapes,
+/// and when the compiler supports spans that cover a part of a literal. Otherwise the error points at the whole literal.
+fn error_at<T: Display>(literal: &LitStr, index: usize, message: T) -> Error {
+    let value = literal.value();
+    let end = index
+        + value
+            .get(index..)
+            .and_then(|rest| rest.chars().next())
+            .map_or(0, char::len_utf8);
+
+    let token = literal.token();
+    let source = token.to_string();
+    // The value of a raw string starts after its hashes and opening quote.
+    let subspan = match (source.find('"'), source.rfind('"')) {
+        (Some(open), Some(close)) if source.get(open + 1..close) == Some(value.as_str()) => {
+            token.subspan(open + 1 + index..open + 1 + end)
+        }
+        _ => None,
+    };
+
+    match subspan {
+        Some(span) => Error::new(span, message),
+        None => Error::new(literal.span(), message),
+    }
+}
+
+/// Returns the byte index of the first problem that keeps the given code from being split into tokens,
+/// together with a description of the problem.
+///
+/// The compiler does not say where such problems are, so this looks for the common ones:
+/// delimiters that do not match, and literals or comments that never end.
+fn find_lex_error(code: &str) -> Option<(usize, &'static str)> {
+    let bytes = code.as_bytes();
+    let is_ident = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_';
+    // The closing delimiters that are expected, and the indices of their opening delimiters.
+    let mut open = Vec::new();
+    let mut i = 0;
+    while let Some(&byte) = bytes.get(i) {
+        let rest = &code[i..];
+        let raw_prefix = match rest.as_bytes() {
+            [b'r', b'"' | b'#', ..] => Some(1),
+            [b'b' | b'c', b'r', b'"' | b'#', ..] => Some(2),
+            _ => None,
+        };
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            match block_comment_len(rest) {
+                Some(len) => len,
+                None => return Some((i, "unterminated block comment")),
+            }
+        } else if let (Some(prefix), false) = (raw_prefix, i > 0 && is_ident(bytes[i - 1])) {
+            let hashes = rest[prefix..].bytes().take_while(|&b| b == b'#').count();
+            if rest.as_bytes().get(prefix + hashes) == Some(&b'"') {
+                let start = prefix + hashes + 1;
+                let terminator = format!("\"{}", "#".repeat(hashes));
+                match rest[start..].find(&terminator) {
+                    Some(end) => start + end + terminator.len(),
+                    None => return Some((i, "unterminated raw string")),
+                }
+            } else {
+                // A raw identifier like `r#type`.
+                prefix + hashes
+            }
+        } else if byte == b'"' {
+            let mut escaped = false;
+            match rest.bytes().skip(1).position(|b| {
+                let end = b == b'"' && !escaped;
+                escaped = b == b'\\' && !escaped;
+                end
+            }) {
+                Some(end) => end + 2,
+                None => return Some((i, "unterminated double quote string")),
+            }
+        } else if byte == b'\'' {
+            match rest.as_bytes() {
+                // The escaped character is skipped, since it may be a quote.
+                [_, b'\\', _, ..] => match rest[3..].find('\'') {
+                    Some(end) => end + 4,
+                    None => return Some((i, "unterminated character literal")),
+                },
+                [_, _, b'\'', ..] => 3,
+                // A lifetime or a label.
+                _ => 1,
+            }
+        } else {
+            match byte {
+                b'(' => open.push((b')', i)),
+                b'[' => open.push((b']', i)),
+                b'{' => open.push((b'}', i)),
+                b')' | b']' | b'}' => match open.pop() {
+                    Some((expected, _)) if expected == byte => {}
+                    Some(_) => return Some((i, "mismatched closing delimiter")),
+                    None => return Some((i, "unexpected closing delimiter")),
+                },
+                _ => {}
+            }
+            1
+        };
+        i += len;
+    }
+    open.first().map(|&(_, i)| (i, "unclosed delimiter"))
+}
+
+/// Returns the length of the block comment that the code starts with, including the block comments nested in it,
+/// or `None` if it never ends.
+fn block_comment_len(code: &str) -> Option<usize> {
+    let bytes = code.as_bytes();
+    let mut depth = 0_usize;
+    let mut i = 0;
+    while let Some(pair) = bytes.get(i..i + 2) {
+        match pair {
+            b"/*" => {
+                depth += 1;
+                i += 2;
+            }
+            b"*/" => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Parses one or more comma separated strings with [`parse_string`], optionally followed by a comma.
+fn parse_literals(input: ParseStream) -> syn::Result<Vec<LitStr>> {
+    let mut literals = vec![parse_string(input)?];
+    while !input.is_empty() {
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+        literals.push(parse_string(input)?);
+    }
+    Ok(literals)
+}
+
+/// Parses a string literal, or an invocation of one of the built-in macros `concat!`, `include_str!`, and `env!`.
+///
+/// The input of a proc-macro is not expanded before it is passed to it, so the built-in macros are evaluated here.
+/// The value of a macro is returned as a literal with the span of the name of the macro.
+fn parse_string(input: ParseStream) -> syn::Result<LitStr> {
+    if input.peek(LitStr) {
+        return input.parse();
+    }
+    if !(input.peek(Ident) && input.peek2(Token![!])) {
+        return Err(input
+            .error("expected a string literal or a call to `concat!`, `include_str!`, or `env!`"));
+    }
+
+    let name: Ident = input.parse()?;
+    input.parse::<Token![!]>()?;
+    let arguments;
+    if input.peek(token::Paren) {
+        parenthesized!(arguments in input);
+    } else if input.peek(token::Bracket) {
+        bracketed!(arguments in input);
+    } else {
+        braced!(arguments in input);
+    }
+
+    let value = match name.to_string().as_str() {
+        "concat" => arguments
+            .parse_terminated(parse_string, Token![,])?
+            .iter()
+            .map(LitStr::value)
+            .collect(),
+        "include_str" => {
+            let path = parse_string(&arguments)?;
+            if !arguments.is_empty() {
+                arguments.parse::<Token![,]>()?;
+            }
+            read_file(&path)?.1
+        }
+        "env" => {
+            let variable = parse_string(&arguments)?;
+            let message = if arguments.is_empty() {
+                None
+            } else {
+                arguments.parse::<Token![,]>()?;
+                let message = parse_string(&arguments)?;
+                if !arguments.is_empty() {
+                    arguments.parse::<Token![,]>()?;
+                }
+                Some(message.value())
+            };
+            match env::var(variable.value()) {
+                Ok(value) => value,
+                Err(_) => {
+                    return Err(Error::new(
+                        variable.span(),
+                        message.unwrap_or_else(|| {
+                            format!(
+                                "environment variable `{}` not defined at compile time",
+                                variable.value()
+                            )
+                        }),
+                    ))
+                }
+            }
+        }
+        _ => {
+            return Err(Error::new(
+                name.span(),
+                format!("`{name}!` is not supported, the input must be a string literal or an invocation of `concat!`, `include_str!`, or `env!`"),
+            ))
+        }
+    };
+    if !arguments.is_empty() {
+        return Err(arguments.error("unexpected argument"));
+    }
+
+    Ok(LitStr::new(&value, name.span()))
+}
+
+/// Works like [`zalgo_embed!`], but reads the encoded string from a file.
+///
+/// The path is relative to the directory that contains the `Cargo.toml` of the crate that uses the macro.
+/// This lets you keep long grapheme clusters out of your source files.
+/// Whitespace at the end of the file, like the final line ending that many editors add, is ignored.
+///
+/// Note that the compiler does not know that the code depends on the file,
+/// so changes to it are only picked up when the crate is compiled again for some other reason.
+///
+/// # Example
+///
+/// If the file `tests/fixtures/multiply.zalgo` contains the result of encoding
+/// `pub fn multiply(x: i32, y: i32) -> i32 { x * y }` we can add the `multiply` function to our program with
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed_file;
+/// zalgo_embed_file!("tests/fixtures/multiply.zalgo");
+/// assert_eq!(multiply(3, 7), 21);
+/// ```
+///
+/// # Errors
+///
+/// Gives a compile error that contains the path to the file if the file can not be read,
+/// if its contents can not be decoded, or if the decoded contents are not valid Rust tokens.
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgo_embed_file;
+/// // compile error: failed to read `.../missing.zalgo`: No such file or directory (os error 2)
+/// zalgo_embed_file!("missing.zalgo");
+/// ```
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgo_embed_file;
+/// // compile error: failed to decode `.../Cargo.toml`: ...
+/// zalgo_embed_file!("Cargo.toml");
+/// ```
+#[proc_macro]
+pub fn zalgo_embed_file(path: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(path as LitStr);
+    match decode_file(&path) {
+        Ok(token_stream) => token_stream,
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+/// Decodes the contents of the file at the given path, which is interpreted as in [`read_file`], into tokens.
+fn decode_file(path: &LitStr) -> Result<TokenStream, Error> {
+    let (full_path, encoded) = read_file(path)?;
+    let decoded = match zalgo_decode(encoded.trim_end()) {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            return Err(Error::new(
+                path.span(),
+                format!("failed to decode `{}`: {e}", full_path.display()),
+            ))
+        }
+    };
+    // The problems that `find_lex_error` finds are reported without parsing, as in `decode_literals`.
+    let problem = match find_lex_error(&decoded) {
+        Some((index, problem)) => format!("{problem}{}", describe_decoded(&decoded, Some(index))),
+        None => match decoded.parse() {
+            Ok(token_stream) => return Ok(token_stream),
+            Err(e) => format!("{e}{}", describe_decoded(&decoded, None)),
+        },
+    };
+    Err(Error::new(
+        path.span(),
+        format!(
+            "the decoded contents of `{}` are not valid tokens: {problem}",
+            full_path.display()
+        ),
+    ))
+}
+
+/// Decodes an encoded module body and uses it as the body of the module that the attribute is placed on.
+///
+/// Items created by [`zalgo_embed!`] end up in the scope where the macro is used,
+/// while this attribute keeps them in a module of their own.
+/// The attribute is placed on a module with an empty body, like `mod hidden {}`.
+/// It also accepts a module declared without a body, like `mod hidden;`, but the compiler
+/// only allows that on nightly with the `proc_macro_hygiene` feature.
+/// The visibility of the module and any other attributes on it are kept.
+///
+/// The encoded body is given either inline with `src = "..."`, or as the path to a file with `path = "..."`.
+/// The path is interpreted in the same way as the path given to [`zalgo_embed_file!`].
+/// Like in [`zalgo_embed!`] the value may also be given by one of the built-in macros `concat!`, `include_str!`, or `env!`.
+///
+/// The attribute can not share the name of [`zalgo_embed!`], since a crate can not define
+/// a function-like macro and an attribute macro with the same name.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_macro::zalgo_embed_mod;
+/// // The body of this module is
+/// // `pub fn add(x: i32, y: i32) -> i32 {x + y}`
+/// #[zalgo_embed_mod(src = "E\u{350}\u{355}\u{342}\u{300}\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}")]
+/// mod math {}
+///
+/// assert_eq!(math::add(10, 20), 30);
+/// ```
+///
+/// # Errors
+///
+/// Gives a compile error if the arguments are not a single `src` or `path` argument,
+/// if the encoded body can not be read or decoded, or if the attribute is not placed on a module
+/// without contents.
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgo_embed_mod;
+/// // compile error: the module must be empty, its contents are given by the attribute
+/// #[zalgo_embed_mod(path = "tests/fixtures/rectangle.zalgo")]
+/// mod shapes {
+///     pub struct Circle;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn zalgo_embed_mod(arguments: TokenStream, item: TokenStream) -> TokenStream {
+    let body = match parse_macro_input!(arguments with parse_module_source) {
+        ModuleSource::Src(encoded) => decode_literals(&[encoded], false).map(|(_, body)| body),
+        ModuleSource::Path(path) => decode_file(&path),
+    };
+    match body.and_then(|body| replace_module_body(item, body)) {
+        Ok(module) => module,
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+/// Where [`zalgo_embed_mod`] finds the encoded body of a module.
+enum ModuleSource {
+    /// The encoded body itself.
+    Src(LitStr),
+    /// The path to a file that contains the encoded body.
+    Path(LitStr),
+}
+
+/// Parses the single `src = "..."` or `path = "..."` argument of [`zalgo_embed_mod`].
+fn parse_module_source(input: ParseStream) -> syn::Result<ModuleSource> {
+    if input.is_empty() {
+        return Err(input.error("expected `src = \"...\"` or `path = \"...\"`"));
+    }
+    let key: Ident = input.parse()?;
+    input.parse::<Token![=]>()?;
+    let value = parse_string(input)?;
+    if !input.is_empty() {
+        input.parse::<Token![,]>()?;
+    }
+    if !input.is_empty() {
+        return Err(input.error("expected a single `src` or `path` argument"));
+    }
+
+    match key.to_string().as_str() {
+        "src" => Ok(ModuleSource::Src(value)),
+        "path" => Ok(ModuleSource::Path(value)),
+        _ => Err(Error::new(key.span(), "expected `src` or `path`")),
+    }
+}
+
+/// Gives the module declared by `item` the given body.
+///
+/// The tokens in front of the name of the module, like its attributes and visibility, are kept as they are.
+fn replace_module_body(item: TokenStream, body: TokenStream) -> Result<TokenStream, Error> {
+    let mut trees = item.into_iter();
+    let mut module: Vec<TokenTree> = Vec::new();
+    let mut found_mod = false;
+    for tree in trees.by_ref() {
+        found_mod = matches!(&tree, TokenTree::Ident(ident) if ident.to_string() == "mod");
+        module.push(tree);
+        if found_mod {
+            break;
+        }
+    }
+    if !found_mod {
+        return Err(Error::new(
+            Span::call_site().into(),
+            "`#[zalgo_embed_mod]` can only be placed on a module",
+        ));
+    }
+
+    match trees.next() {
+        Some(TokenTree::Ident(name)) => module.push(TokenTree::Ident(name)),
+        _ => {
+            return Err(Error::new(
+                Span::call_site().into(),
+                "expected the name of the module",
+            ))
+        }
+    }
+    let span = match trees.next() {
+        Some(TokenTree::Punct(semicolon)) if semicolon.as_char() == ';' => semicolon.span(),
+        Some(TokenTree::Group(group))
+            if group.delimiter() == Delimiter::Brace && group.stream().is_empty() =>
+        {
+            group.span()
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            return Err(Error::new(
+                group.span().into(),
+                "the module must be empty, its contents are given by the attribute",
+            ))
+        }
+        _ => return Err(Error::new(Span::call_site().into(), "expected `;` or `{}`")),
+    };
+
+    let mut body = Group::new(Delimiter::Brace, body);
+    body.set_span(span);
+    module.push(TokenTree::Group(body));
+    Ok(module.into_iter().collect())
+}
+
+/// Reads the file at the given path, which is relative to the directory of the manifest of the crate that is being compiled.
+///
+/// Returns the full path to the file together with its contents.
+fn read_file(path: &LitStr) -> Result<(PathBuf, String), Error> {
+    let relative = path.value();
+    let full_path = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir).join(relative),
+        None => PathBuf::from(relative),
+    };
+
+    match fs::read_to_string(&full_path) {
+        Ok(contents) => Ok((full_path, contents)),
+        Err(e) => Err(Error::new(
+            path.span(),
+            format!("failed to read `{}`: {e}", full_path.display()),
+        )),
     }
 }
 
@@ -123,6 +1150,33 @@ pub fn zalgo_embed(encoded: TokenStream) -> TokenStream {
 /// assert_eq!(ZS, "É̺͇͌͏");
 /// ```
 ///
+/// Like [`zalgo_embed!`] it evaluates the built-in macros `concat!`, `include_str!`, and `env!` in its input:
+/// ```
+/// # use zalgo_codec_macro::zalgofy;
+/// const ZS: &str = zalgofy!(concat!("Zal", "go"));
+/// assert_eq!(ZS, zalgofy!("Zalgo"));
+/// ```
+///
+/// If the first argument is `bytes` it expands to a byte string literal with the same bytes instead,
+/// for when the encoded string is only needed as bytes:
+/// ```
+/// # use zalgo_codec_macro::zalgofy;
+/// const ZB: &[u8] = zalgofy!(bytes, "Zalgo");
+/// assert_eq!(ZB, zalgofy!("Zalgo").as_bytes());
+/// ```
+///
+/// If one of the arguments in front of the string is `verify` it expands to a block that also checks at compile time
+/// that the encoded literal decodes into the original string. The check only uses the standard library,
+/// so if the expanded code is copied into a source file, e.g. to avoid the dependency on this crate,
+/// an encoded literal that is later changed by mistake results in a compile error instead of wrong text at runtime.
+/// ```
+/// # use zalgo_codec_macro::zalgofy;
+/// const ZS: &str = zalgofy!(verify, "Zalgo");
+/// assert_eq!(ZS, zalgofy!("Zalgo"));
+/// const ZB: &[u8] = zalgofy!(verify, bytes, "Zalgo");
+/// assert_eq!(ZB, zalgofy!(bytes, "Zalgo"));
+/// ```
+///
 /// # Errors
 ///
 /// This macro uses [`zalgo_encode`] internally and converts its errors into compile errors.
@@ -139,15 +1193,398 @@ pub fn zalgo_embed(encoded: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro]
 pub fn zalgofy(string: TokenStream) -> TokenStream {
-    let string = parse_macro_input!(string as LitStr).value();
-    match zalgo_encode(&string) {
-        Ok(encoded) => {
-            let string = format!("\"{encoded}\"");
-            match string.parse() {
-                Ok(token_stream) => token_stream,
-                Err(e) => Error::new(string.span(), e).into_compile_error().into(),
-            }
+    let ZalgofyArguments {
+        bytes,
+        verify,
+        literal,
+    } = parse_macro_input!(string with parse_zalgofy_arguments);
+    let text = literal.value();
+    let encoded = match zalgo_encode(&text) {
+        Ok(encoded) if bytes => Literal::byte_string(encoded.as_bytes()),
+        Ok(encoded) => Literal::string(&encoded),
+        Err(e) => return error_at(&literal, e.index(), e).into_compile_error().into(),
+    };
+    if verify {
+        match verified_literal(encoded, &text, bytes) {
+            Ok(block) => block,
+            Err(e) => e.into_compile_error().into(),
         }
-        Err(e) => Error::new(string.span(), e).to_compile_error().into(),
+    } else {
+        TokenTree::Literal(encoded).into()
+    }
+}
+
+/// The arguments of [`zalgofy!`].
+struct ZalgofyArguments {
+    /// Whether the result should be a byte string.
+    bytes: bool,
+    /// Whether the result should be checked to decode into the string at compile time.
+    verify: bool,
+    /// The string to encode.
+    literal: LitStr,
+}
+
+/// Parses the arguments of [`zalgofy!`]: the optional `bytes` and `verify` flags in any order
+/// followed by commas, and a string.
+fn parse_zalgofy_arguments(input: ParseStream) -> syn::Result<ZalgofyArguments> {
+    let mut bytes = false;
+    let mut verify = false;
+    while let Some(flag) = parse_flag(input, &["bytes", "verify"])? {
+        match flag.as_str() {
+            "bytes" => bytes = true,
+            _ => verify = true,
+        }
+    }
+    let literal = parse_string(input)?;
+    if !input.is_empty() {
+        input.parse::<Token![,]>()?;
+    }
+    if !input.is_empty() {
+        return Err(input.error("unexpected argument"));
+    }
+    Ok(ZalgofyArguments {
+        bytes,
+        verify,
+        literal,
+    })
+}
+
+/// Returns a block that evaluates to the given encoded literal, and that fails to compile
+/// if the literal does not decode into the given text.
+///
+/// The check only uses the standard library, so the code still works if it is copied out of an expansion of the macro.
+fn verified_literal(encoded: Literal, text: &str, bytes: bool) -> Result<TokenStream, Error> {
+    let (ty, as_bytes) = if bytes {
+        ("[u8]", "")
+    } else {
+        ("str", ".as_bytes()")
+    };
+    format!(
+        "{{
+            const ENCODED: &{ty} = {encoded};
+            const _: () = {{
+                let encoded = ENCODED{as_bytes};
+                let text: &[u8] = {text};
+                let mut matches = encoded.len() == 1 + 2 * text.len() && encoded[0] == b'E';
+                let mut i = 0;
+                while matches && i < text.len() {{
+                    let (odd, even) = (encoded[1 + 2 * i], encoded[2 + 2 * i]);
+                    matches = ((odd << 6 & 64 | even & 63) + 22) % 133 + 10 == text[i];
+                    i += 1;
+                }}
+                assert!(matches, \"the encoded literal does not decode into the text it was made from\");
+            }};
+            ENCODED
+        }}",
+        text = Literal::byte_string(text.as_bytes()),
+    )
+    .parse()
+    .map_err(|e| Error::new(Span::call_site().into(), e))
+}
+
+/// At compile time this proc-macro decodes the given encoded string literal into a string literal.
+///
+/// This is the inverse of [`zalgofy!`]. Unlike [`zalgo_embed!`] the decoded text does not have to be
+/// valid Rust code, so it can be used for plain text.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_macro::zalgo_decodify;
+/// const SECRET: &str = zalgo_decodify!("E\u{324}\u{34f}\u{34e}\u{307}\u{354}\u{300}\u{352}\u{345}\u{341}\u{344}\u{300}\u{354}\u{348}\u{349}\u{353}\u{300}\u{34d}\u{34f}\u{34d}\u{301}");
+/// assert_eq!(SECRET, "Don't read this mom!");
+/// ```
+///
+/// Like [`zalgo_embed!`] it evaluates the built-in macros `concat!`, `include_str!`, and `env!` in its input.
+///
+/// # Errors
+///
+/// This macro validates its input in the same way as [`ZalgoString::from_encoded`], and converts its errors
+/// into compile errors.
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgo_decodify;
+/// // compile error: could not decode the string because it begins with 'Z' instead of 'E'
+/// const TEXT: &str = zalgo_decodify!("Zalgo");
+/// ```
+#[proc_macro]
+pub fn zalgo_decodify(encoded: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(encoded with parse_string);
+    match ZalgoString::from_encoded(&literal.value()) {
+        Ok(decoded) => TokenTree::Literal(Literal::string(&decoded.into_decoded_string())).into(),
+        Err(e) => match e.index() {
+            Some(index) => error_at(&literal, index, e),
+            None => Error::new(literal.span(), e),
+        }
+        .into_compile_error()
+        .into(),
+    }
+}
+
+/// Works like [`zalgofy!`], but encodes the contents of a file.
+///
+/// The path is relative to the directory that contains the `Cargo.toml` of the crate that uses the macro,
+/// in the same way as in [`zalgo_embed_file!`]. This lets you edit the text that is encoded as a normal file.
+/// CRLF line endings in the file are turned into LF line endings before it is encoded,
+/// so the result is the same on every platform.
+///
+/// Note that the compiler does not know that the code depends on the file,
+/// so changes to it are only picked up when the crate is compiled again for some other reason.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_macro::zalgofy_file;
+/// // The file contains the text "Zalgo" followed by a line ending.
+/// const PAYLOAD: &str = zalgofy_file!("tests/fixtures/zalgo.txt");
+/// assert_eq!(PAYLOAD, zalgo_codec_common::zalgo_encode("Zalgo\n").unwrap());
+/// ```
+///
+/// # Errors
+///
+/// Gives a compile error that contains the path to the file if the file can not be read,
+/// or if it contains a character that can not be encoded.
+/// In the latter case the error also contains the line and column of the character.
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgofy_file;
+/// // compile error: failed to encode `.../LICENSE-MIT`: can not encode 'ö' character at string index 57, on line 3 at column 45
+/// const PAYLOAD: &str = zalgofy_file!("LICENSE-MIT");
+/// ```
+#[proc_macro]
+pub fn zalgofy_file(path: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(path as LitStr);
+    let (full_path, contents) = match read_file(&path) {
+        Ok(file) => file,
+        Err(e) => return e.into_compile_error().into(),
+    };
+
+    match zalgo_encode(&contents.replace("\r\n", "\n")) {
+        Ok(encoded) => TokenTree::Literal(Literal::string(&encoded)).into(),
+        Err(e) => Error::new(
+            path.span(),
+            format!("failed to encode `{}`: {e}", full_path.display()),
+        )
+        .into_compile_error()
+        .into(),
+    }
+}
+
+/// Replaces the string literals in the item it is placed on with code that decodes
+/// their encoded form at runtime, so that the text of the literals is not stored in the binary.
+///
+/// This does what [`zalgofy!`] and [`zalgo_decode`] do by hand for every string literal in e.g. a function.
+/// Every literal is encoded at compile time and replaced by a block that decodes it the first time it is evaluated,
+/// and evaluates to a `&'static str` of the decoded string. The decoding uses a `std::sync::OnceLock`,
+/// so the crate that uses the attribute must have access to the standard library.
+///
+/// The generated code calls `zalgo_decode` from the crate `zalgo_codec_common`.
+/// If that crate is only available by another name, e.g. as `zalgo_codec` through the `zalgo-codec` crate,
+/// its path can be given to the attribute as `#[zalgofy_strings(crate = zalgo_codec)]`.
+///
+/// Since the replaced literals are not constants, the literals where a constant or a literal is needed are left as they are.
+/// These are the literals
+///  - in the values of constants and statics, and in inline `const` blocks,
+///  - in the bodies of `const` functions,
+///  - in types, and in the arguments and default values of const generic parameters,
+///  - in patterns, like the arms of a `match` or the pattern of a `let` or `if let`,
+///  - in attributes and in the arguments of macros, like the format string of `format!`,
+///  - and in the ABI of `extern` blocks and functions.
+///
+/// Only string literals are replaced, and empty strings are left as they are.
+///
+/// # Example
+///
+/// ```
+/// # use zalgo_codec_macro::zalgofy_strings;
+/// #[zalgofy_strings]
+/// fn greet(name: &str) -> String {
+///     // This literal is stored in the binary in its encoded form.
+///     let greeting = "Hello";
+///     match name {
+///         // This literal is a pattern, so it is left as it is.
+///         "" => format!("{greeting}!"),
+///         name => format!("{greeting}, {name}!"),
+///     }
+/// }
+///
+/// assert_eq!(greet("Zalgo"), "Hello, Zalgo!");
+/// assert_eq!(greet(""), "Hello!");
+/// ```
+///
+/// # Errors
+///
+/// Gives a compile error if a literal that would be replaced contains a character that can not be encoded.
+/// ```compile_fail
+/// # use zalgo_codec_macro::zalgofy_strings;
+/// #[zalgofy_strings]
+/// fn price() -> &'static str {
+///     // compile error: can not encode '€' character at string index 1, on line 1 at column 2
+///     "5€"
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn zalgofy_strings(arguments: TokenStream, item: TokenStream) -> TokenStream {
+    let crate_path = parse_macro_input!(arguments with parse_zalgofy_strings_arguments);
+    let mut item = parse_macro_input!(item as Item);
+    let mut replacer = StringReplacer {
+        crate_path,
+        errors: None,
+    };
+    replacer.visit_item_mut(&mut item);
+    match replacer.errors {
+        Some(errors) => errors.into_compile_error().into(),
+        None => item.into_token_stream().into(),
+    }
+}
+
+/// Parses the arguments of [`zalgofy_strings`]: nothing, or the path to the crate with `zalgo_decode`.
+fn parse_zalgofy_strings_arguments(input: ParseStream) -> syn::Result<String> {
+    let crate_path = parse_crate_path(input)?;
+    if !input.is_empty() {
+        return Err(input.error("expected `crate = path` or nothing"));
+    }
+    Ok(crate_path.unwrap_or_else(|| "::zalgo_codec_common".to_string()))
+}
+
+/// Replaces the string literals in the item that [`zalgofy_strings`] is placed on.
+///
+/// The parts of the item where a literal must be left as it is are not visited.
+struct StringReplacer {
+    /// The path to the crate with `zalgo_decode`.
+    crate_path: String,
+    /// The errors for the literals that could not be encoded.
+    errors: Option<Error>,
+}
+
+impl VisitMut for StringReplacer {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(string),
+            ..
+        }) = expr
+        else {
+            return visit_mut::visit_expr_mut(self, expr);
+        };
+        if string.value().is_empty() {
+            return;
+        }
+        match zalgofy_literal(string, &self.crate_path) {
+            Ok(replaced) => *expr = replaced,
+            Err(error) => match self.errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => self.errors = Some(error),
+            },
+        }
+    }
+
+    // Constants, statics, and inline `const` blocks.
+    fn visit_item_const_mut(&mut self, _: &mut ItemConst) {}
+    fn visit_item_static_mut(&mut self, _: &mut ItemStatic) {}
+    fn visit_impl_item_const_mut(&mut self, _: &mut ImplItemConst) {}
+    fn visit_trait_item_const_mut(&mut self, _: &mut TraitItemConst) {}
+    fn visit_expr_const_mut(&mut self, _: &mut ExprConst) {}
+
+    // `const` functions.
+    fn visit_item_fn_mut(&mut self, function: &mut ItemFn) {
+        if function.sig.constness.is_none() {
+            visit_mut::visit_item_fn_mut(self, function);
+        }
+    }
+    fn visit_impl_item_fn_mut(&mut self, function: &mut ImplItemFn) {
+        if function.sig.constness.is_none() {
+            visit_mut::visit_impl_item_fn_mut(self, function);
+        }
+    }
+    fn visit_trait_item_fn_mut(&mut self, function: &mut TraitItemFn) {
+        if function.sig.constness.is_none() {
+            visit_mut::visit_trait_item_fn_mut(self, function);
+        }
+    }
+
+    // Types and const generics.
+    fn visit_type_mut(&mut self, _: &mut Type) {}
+    fn visit_generic_argument_mut(&mut self, _: &mut GenericArgument) {}
+    fn visit_const_param_mut(&mut self, _: &mut ConstParam) {}
+
+    // Patterns, attributes, and the arguments of macros.
+    fn visit_pat_mut(&mut self, _: &mut Pat) {}
+    fn visit_attribute_mut(&mut self, _: &mut Attribute) {}
+    fn visit_macro_mut(&mut self, _: &mut Macro) {}
+}
+
+/// Returns code that decodes the encoded form of the string literal at runtime.
+fn zalgofy_literal(string: &LitStr, crate_path: &str) -> Result<Expr, Error> {
+    let encoded = zalgo_encode(&string.value()).map_err(|e| error_at(string, e.index(), e))?;
+    let code = format!(
+        "{{ static DECODED: ::std::sync::OnceLock<::std::string::String> = ::std::sync::OnceLock::new(); \
+        DECODED.get_or_init(|| {crate_path}::zalgo_decode({}).unwrap()).as_str() }}",
+        Literal::string(&encoded),
+    );
+    let mut block = Group::new(
+        Delimiter::None,
+        code.parse()
+            .map_err(|e| Error::new(Span::call_site().into(), e))?,
+    );
+    block.set_span(string.span().unwrap());
+    Ok(Expr::Verbatim(
+        TokenStream::from(TokenTree::Group(block)).into(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_describe_decoded() {
+        assert_eq!(
+            describe_decoded("fn add(x: i32, -> i32 {}", Some(6)),
+            "\n\ndecoded code, line 1, column 7:\n1 | fn add(x: i32, -> i32 {}\n  |       ^"
+        );
+        let code = "mod m {\n    fn a() {}\n    fn b() {}\n    fn c( {}\n}\n";
+        assert_eq!(
+            describe_decoded(code, Some(code.find("( {").unwrap())),
+            "\n\ndecoded code, line 4, column 9:\n2 |     fn a() {}\n3 |     fn b() {}\n4 |     fn c( {}\n  |         ^"
+        );
+        assert_eq!(describe_decoded("x +", None), "\n\ndecoded code:\nx +");
+        assert_eq!(describe_decoded(&"x + ".repeat(200), None), "");
+    }
+
+    #[test]
+    fn test_find_lex_error() {
+        assert_eq!(find_lex_error("fn f(x: i32) -> i32 { x }"), None);
+        assert_eq!(
+            find_lex_error(r##"let s = r#"(")"#; let c = '('; let b = b'\''; 'a: loop {}"##),
+            None
+        );
+        assert_eq!(find_lex_error("// (\n/* [ */ {}"), None);
+        assert_eq!(
+            find_lex_error("fn f() { (x] }"),
+            Some((11, "mismatched closing delimiter"))
+        );
+        assert_eq!(
+            find_lex_error("x + y)"),
+            Some((5, "unexpected closing delimiter"))
+        );
+        assert_eq!(
+            find_lex_error("fn f() { if x { y }"),
+            Some((7, "unclosed delimiter"))
+        );
+        assert_eq!(
+            find_lex_error(r#"let s = "\"(;"#),
+            Some((8, "unterminated double quote string"))
+        );
+        assert_eq!(
+            find_lex_error(r##"let s = r#"abc"; "##),
+            Some((8, "unterminated raw string"))
+        );
+        assert_eq!(
+            find_lex_error("x /* y"),
+            Some((2, "unterminated block comment"))
+        );
+        assert_eq!(find_lex_error("/* a /* ( */ ) */ fn f() {}"), None);
+        assert_eq!(
+            find_lex_error("/* a /* b */ fn f() {}"),
+            Some((0, "unterminated block comment"))
+        );
     }
 }
@@ -0,0 +1,34 @@
+use zalgo_codec_macro::{zalgo_embed, zalgofy};
+
+// Defines `multiply`.
+zalgo_embed!(include_str!("tests/fixtures/multiply.zalgo"));
+
+#[test]
+fn test_embed_include_str() {
+    assert_eq!(multiply(6, 7), 42);
+}
+
+#[test]
+fn test_embed_concat() {
+    // `fn add(x: i32, y: i32) -> i32 {x + y}` assembled from three parts.
+    let add: fn(i32, i32) -> i32 = {
+        zalgo_embed!(concat!(
+            "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}",
+            concat!(
+                "\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}",
+                "\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}",
+            ),
+        ));
+        add
+    };
+    assert_eq!(add(10, 20), 30);
+}
+
+#[test]
+fn test_zalgofy_builtin_macros() {
+    assert_eq!(zalgofy!(concat!("Zal", "go")), zalgofy!("Zalgo"));
+    assert_eq!(
+        zalgofy!(env!("CARGO_PKG_NAME")),
+        zalgofy!("zalgo-codec-macro")
+    );
+}
@@ -0,0 +1,15 @@
+use zalgo_codec_common::zalgo_decode;
+use zalgo_codec_macro::zalgo_decodify;
+
+#[test]
+fn test_decodify() {
+    // The encoding of
+    // He said "hi"
+    // C:\path\to\file
+    const TEXT: &str = zalgo_decodify!("E\u{328}\u{345}\u{300}\u{353}\u{341}\u{349}\u{344}\u{300}\u{302}\u{348}\u{349}\u{302}\u{36f}\u{323}\u{31a}\u{33c}\u{350}\u{341}\u{354}\u{348}\u{33c}\u{354}\u{34f}\u{33c}\u{346}\u{349}\u{34c}\u{345}\u{36f}");
+    assert_eq!(
+        TEXT,
+        zalgo_decode("E\u{328}\u{345}\u{300}\u{353}\u{341}\u{349}\u{344}\u{300}\u{302}\u{348}\u{349}\u{302}\u{36f}\u{323}\u{31a}\u{33c}\u{350}\u{341}\u{354}\u{348}\u{33c}\u{354}\u{34f}\u{33c}\u{346}\u{349}\u{34c}\u{345}\u{36f}").unwrap()
+    );
+    assert_eq!(TEXT, "He said \"hi\"\nC:\\path\\to\\file\n");
+}
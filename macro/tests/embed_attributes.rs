@@ -0,0 +1,39 @@
+#![deny(warnings)]
+
+use zalgo_codec_macro::zalgo_embed;
+
+// Defines `ORIGIN`, `Point`, its `Display` implementation, and the function `unused`,
+// which would fail the build without the attribute.
+zalgo_embed!(
+    #[allow(dead_code)]
+    include_str!("tests/fixtures/point.zalgo")
+);
+
+mod only_in_tests {
+    use zalgo_codec_macro::zalgo_embed;
+
+    // `fn unused() {}`
+    zalgo_embed!(
+        #[cfg(test)]
+        #[allow(dead_code)]
+        "E\u{346}\u{34e}\u{300}\u{355}\u{34e}\u{355}\u{353}\u{345}\u{344}\u{308}\u{309}\u{300}\u{35b}\u{35d}"
+    );
+}
+
+mod const_generics {
+    use zalgo_codec_macro::zalgo_embed;
+
+    // `struct Wrapper<const N: usize = { 1 + 1 }>([u8; N]);`
+    // `type Pair = Wrapper<{ 1 + 1 }>;`
+    // `fn pair() -> Pair { Wrapper([0; 2]) }`
+    // The blocks in the generics do not end the items.
+    zalgo_embed!(
+        #[allow(dead_code)]
+        "E\u{353}\u{354}\u{352}\u{355}\u{343}\u{354}\u{300}\u{337}\u{352}\u{341}\u{350}\u{350}\u{345}\u{352}\u{31c}\u{343}\u{34f}\u{34e}\u{353}\u{354}\u{300}\u{32e}\u{31a}\u{300}\u{355}\u{353}\u{349}\u{35a}\u{345}\u{300}\u{31d}\u{300}\u{35b}\u{300}\u{311}\u{300}\u{30b}\u{300}\u{311}\u{300}\u{35d}\u{31e}\u{308}\u{33b}\u{355}\u{318}\u{31b}\u{300}\u{32e}\u{33d}\u{309}\u{31b}\u{36f}\u{354}\u{359}\u{350}\u{345}\u{300}\u{330}\u{341}\u{349}\u{352}\u{300}\u{31d}\u{300}\u{337}\u{352}\u{341}\u{350}\u{350}\u{345}\u{352}\u{31c}\u{35b}\u{300}\u{311}\u{300}\u{30b}\u{300}\u{311}\u{300}\u{35d}\u{31e}\u{31b}\u{36f}\u{346}\u{34e}\u{300}\u{350}\u{341}\u{349}\u{352}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{330}\u{341}\u{349}\u{352}\u{300}\u{35b}\u{300}\u{337}\u{352}\u{341}\u{350}\u{350}\u{345}\u{352}\u{308}\u{33b}\u{310}\u{31b}\u{300}\u{312}\u{33d}\u{309}\u{300}\u{35d}"
+    );
+}
+
+#[test]
+fn test_embed_attributes() {
+    assert_eq!(ORIGIN.to_string(), "(0, 0)");
+}
@@ -0,0 +1,25 @@
+use zalgo_codec_common as renamed;
+use zalgo_codec_macro::zalgo_embed;
+
+// `pub fn encoded_len(s: &str) -> usize {__zalgo_crate__::zalgo_encode(s).unwrap().len()}`
+zalgo_embed!(
+    crate = renamed,
+    "E\u{350}\u{355}\u{342}\u{300}\u{346}\u{34e}\u{300}\u{345}\u{34e}\u{343}\u{34f}\u{344}\u{345}\u{344}\u{33f}\u{34c}\u{345}\u{34e}\u{308}\u{353}\u{31a}\u{300}\u{306}\u{353}\u{354}\u{352}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{355}\u{353}\u{349}\u{35a}\u{345}\u{300}\u{35b}\u{33f}\u{33f}\u{35a}\u{341}\u{34c}\u{347}\u{34f}\u{33f}\u{343}\u{352}\u{341}\u{354}\u{345}\u{33f}\u{33f}\u{31a}\u{31a}\u{35a}\u{341}\u{34c}\u{347}\u{34f}\u{33f}\u{345}\u{34e}\u{343}\u{34f}\u{344}\u{345}\u{308}\u{353}\u{309}\u{30e}\u{355}\u{34e}\u{357}\u{352}\u{341}\u{350}\u{308}\u{309}\u{30e}\u{34c}\u{345}\u{34e}\u{308}\u{309}\u{35d}"
+);
+
+mod nested {
+    use zalgo_codec_macro::zalgo_embed;
+
+    // The same code with a path that starts with `super`.
+    zalgo_embed!(
+        crate = super::renamed,
+        "E\u{350}\u{355}\u{342}\u{300}\u{346}\u{34e}\u{300}\u{345}\u{34e}\u{343}\u{34f}\u{344}\u{345}\u{344}\u{33f}\u{34c}\u{345}\u{34e}\u{308}\u{353}\u{31a}\u{300}\u{306}\u{353}\u{354}\u{352}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{355}\u{353}\u{349}\u{35a}\u{345}\u{300}\u{35b}\u{33f}\u{33f}\u{35a}\u{341}\u{34c}\u{347}\u{34f}\u{33f}\u{343}\u{352}\u{341}\u{354}\u{345}\u{33f}\u{33f}\u{31a}\u{31a}\u{35a}\u{341}\u{34c}\u{347}\u{34f}\u{33f}\u{345}\u{34e}\u{343}\u{34f}\u{344}\u{345}\u{308}\u{353}\u{309}\u{30e}\u{355}\u{34e}\u{357}\u{352}\u{341}\u{350}\u{308}\u{309}\u{30e}\u{34c}\u{345}\u{34e}\u{308}\u{309}\u{35d}"
+    );
+}
+
+#[test]
+fn test_embed_crate_path() {
+    let expected = renamed::zalgo_encode("Zalgo").unwrap().len();
+    assert_eq!(encoded_len("Zalgo"), expected);
+    assert_eq!(nested::encoded_len("Zalgo"), expected);
+}
@@ -0,0 +1,27 @@
+// The debug mode reports where it wrote the decoded source with a deprecation warning.
+#![allow(deprecated)]
+
+use std::{fs, path::PathBuf};
+use zalgo_codec_macro::zalgo_embed;
+
+// `fn add(x: i32, y: i32) -> i32 {x + y}`
+zalgo_embed!(
+    debug,
+    "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}"
+);
+
+#[test]
+fn test_embed_debug() {
+    assert_eq!(add(10, 20), 30);
+
+    let dir = match option_env!("OUT_DIR").or(option_env!("CARGO_TARGET_DIR")) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"),
+    }
+    .join("zalgo_embed");
+    let dumped = fs::read_dir(dir).unwrap().any(|entry| {
+        fs::read_to_string(entry.unwrap().path()).unwrap()
+            == "fn add(x: i32, y: i32) -> i32 {x + y}"
+    });
+    assert!(dumped);
+}
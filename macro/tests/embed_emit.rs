@@ -0,0 +1,26 @@
+use zalgo_codec_macro::zalgo_embed;
+
+// `fn source_file() -> &'static str {file!()}`
+zalgo_embed!(
+    emit,
+    "E\u{346}\u{34e}\u{300}\u{353}\u{34f}\u{355}\u{352}\u{343}\u{345}\u{33f}\u{346}\u{349}\u{34c}\u{345}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{306}\u{307}\u{353}\u{354}\u{341}\u{354}\u{349}\u{343}\u{300}\u{353}\u{354}\u{352}\u{300}\u{35b}\u{346}\u{349}\u{34c}\u{345}\u{301}\u{308}\u{309}\u{35d}"
+);
+
+#[test]
+fn test_embed_emit() {
+    // The included code refers to the file that it was written to.
+    let path = std::path::Path::new(source_file());
+    assert!(path.is_absolute());
+    assert_eq!(path.parent().unwrap().file_name().unwrap(), "zalgo_embed");
+    assert_eq!(
+        std::fs::read_to_string(path).unwrap(),
+        "fn source_file() -> &'static str {file!()}"
+    );
+}
+
+#[test]
+fn test_embed_emit_expression() {
+    // `1 + 2`
+    let three: i32 = zalgo_embed!(emit, "E\u{311}\u{300}\u{30b}\u{300}\u{312}");
+    assert_eq!(three, 3);
+}
@@ -0,0 +1,10 @@
+use zalgo_codec_macro::zalgo_embed_file;
+
+// Defines `multiply`.
+zalgo_embed_file!("tests/fixtures/multiply.zalgo");
+
+#[test]
+fn test_embed_file() {
+    assert_eq!(multiply(6, 7), 42);
+    assert_eq!(multiply(-3, 5), -15);
+}
@@ -0,0 +1,36 @@
+use zalgo_codec_macro::zalgo_embed;
+
+#[test]
+fn test_embed_fragments() {
+    // `fn add(x: i32, y: i32) -> i32 {x + y}` in a single literal.
+    let single: fn(i32, i32) -> i32 = {
+        zalgo_embed!("E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}");
+        add
+    };
+
+    // The same encoding split into three literals.
+    let split: fn(i32, i32) -> i32 = {
+        zalgo_embed!(
+            "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}",
+            "\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}",
+            "\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}",
+        );
+        add
+    };
+
+    // Literals after the first may also start with an "E".
+    let prefixed: fn(i32, i32) -> i32 = {
+        zalgo_embed!(
+            "E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}",
+            "E\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}",
+            "E\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}"
+        );
+        add
+    };
+
+    for (x, y) in [(10, 20), (-5, 3), (0, 0)] {
+        assert_eq!(single(x, y), x + y);
+        assert_eq!(split(x, y), single(x, y));
+        assert_eq!(prefixed(x, y), single(x, y));
+    }
+}
@@ -0,0 +1,16 @@
+use zalgo_codec_macro::zalgo_embed;
+
+// `fn one() -> i32 {1}`, `fn two() -> i32 {one() + one()}`, and `fn three() -> i32 {one() + two()}`
+zalgo_embed!(
+    items:
+    "E\u{346}\u{34e}\u{300}\u{34f}\u{34e}\u{345}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{311}\u{35d}",
+    "E\u{346}\u{34e}\u{300}\u{354}\u{357}\u{34f}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{34f}\u{34e}\u{345}\u{308}\u{309}\u{300}\u{30b}\u{300}\u{34f}\u{34e}\u{345}\u{308}\u{309}\u{35d}",
+    "E\u{346}\u{34e}\u{300}\u{354}\u{348}\u{352}\u{345}\u{345}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{34f}\u{34e}\u{345}\u{308}\u{309}\u{300}\u{30b}\u{300}\u{354}\u{357}\u{34f}\u{308}\u{309}\u{35d}",
+);
+
+#[test]
+fn test_embed_items() {
+    assert_eq!(one(), 1);
+    assert_eq!(two(), 2);
+    assert_eq!(three(), 3);
+}
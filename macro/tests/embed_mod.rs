@@ -0,0 +1,31 @@
+use zalgo_codec_macro::zalgo_embed_mod;
+
+// The body of this module is
+// pub struct Point {pub x: i32, pub y: i32}
+// pub fn origin() -> Point {Point {x: 0, y: 0}}
+#[zalgo_embed_mod(
+    src = "E\u{350}\u{355}\u{342}\u{300}\u{353}\u{354}\u{352}\u{355}\u{343}\u{354}\u{300}\u{330}\u{34f}\u{349}\u{34e}\u{354}\u{300}\u{35b}\u{350}\u{355}\u{342}\u{300}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{350}\u{355}\u{342}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{35d}\u{36f}\u{350}\u{355}\u{342}\u{300}\u{346}\u{34e}\u{300}\u{34f}\u{352}\u{349}\u{347}\u{349}\u{34e}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{330}\u{34f}\u{349}\u{34e}\u{354}\u{300}\u{35b}\u{330}\u{34f}\u{349}\u{34e}\u{354}\u{300}\u{35b}\u{358}\u{31a}\u{300}\u{310}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{310}\u{35d}\u{35d}\u{36f}"
+)]
+mod hidden {}
+
+/// Contains `Rectangle`.
+#[zalgo_embed_mod(path = "tests/fixtures/rectangle.zalgo")]
+#[allow(dead_code)]
+pub(crate) mod shapes {}
+
+#[test]
+fn test_embed_mod_src() {
+    let origin = hidden::origin();
+    assert_eq!((origin.x, origin.y), (0, 0));
+    let point = hidden::Point { x: 3, y: -4 };
+    assert_eq!(point.x + point.y, -1);
+}
+
+#[test]
+fn test_embed_mod_path() {
+    let rectangle = shapes::Rectangle {
+        width: 3,
+        height: 4,
+    };
+    assert_eq!(rectangle.area(), 12);
+}
@@ -0,0 +1,5 @@
+use zalgo_codec_macro::zalgo_embed;
+
+zalgo_embed!("E\u{360}");
+
+fn main() {}
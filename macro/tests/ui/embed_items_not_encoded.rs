@@ -0,0 +1,5 @@
+use zalgo_codec_macro::zalgo_embed;
+
+zalgo_embed!(items: "E\u{311}", "Zalgo", "E\u{312}");
+
+fn main() {}
@@ -0,0 +1,7 @@
+use zalgo_codec_macro::zalgo_embed;
+
+const CODE: &str = "E\u{311}\u{300}\u{30b}\u{300}\u{312}";
+
+zalgo_embed!(CODE);
+
+fn main() {}
@@ -0,0 +1,5 @@
+use zalgo_codec_macro::zalgo_embed;
+
+zalgo_embed!("Zalgo");
+
+fn main() {}
@@ -0,0 +1,6 @@
+use zalgo_codec_macro::zalgo_embed;
+
+fn main() {
+    // `(1 + 2]`
+    let _ = zalgo_embed!("E\u{308}\u{311}\u{300}\u{30b}\u{300}\u{312}\u{33d}");
+}
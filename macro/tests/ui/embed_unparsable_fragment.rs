@@ -0,0 +1,9 @@
+use zalgo_codec_macro::zalgo_embed;
+
+// `fn f() -> i32 {` followed by `(1 + 2]}`
+zalgo_embed!(
+    "E\u{346}\u{34e}\u{300}\u{346}\u{308}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}",
+    "\u{308}\u{311}\u{300}\u{30b}\u{300}\u{312}\u{33d}\u{35d}",
+);
+
+fn main() {}
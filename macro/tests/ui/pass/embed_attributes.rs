@@ -0,0 +1,8 @@
+#![deny(warnings)]
+
+use zalgo_codec_macro::zalgo_embed;
+
+// `fn unused() {}`
+zalgo_embed!(#[allow(dead_code)] #[doc(hidden)] "E\u{346}\u{34e}\u{300}\u{355}\u{34e}\u{355}\u{353}\u{345}\u{344}\u{308}\u{309}\u{300}\u{35b}\u{35d}");
+
+fn main() {}
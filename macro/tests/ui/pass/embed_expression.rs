@@ -0,0 +1,7 @@
+use zalgo_codec_macro::zalgo_embed;
+
+fn main() {
+    // `1 + 2`
+    let three: i32 = zalgo_embed!("E\u{311}\u{300}\u{30b}\u{300}\u{312}");
+    assert_eq!(three, 3);
+}
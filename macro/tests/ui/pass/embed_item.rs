@@ -0,0 +1,8 @@
+use zalgo_codec_macro::zalgo_embed;
+
+// `fn add(x: i32, y: i32) -> i32 {x + y}`
+zalgo_embed!("E\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{35d}");
+
+fn main() {
+    assert_eq!(add(10, 20), 30);
+}
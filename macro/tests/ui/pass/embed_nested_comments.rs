@@ -0,0 +1,10 @@
+use zalgo_codec_macro::zalgo_embed;
+
+// `/* a /* ( */ ) */`
+// `fn add(x: i32, y: i32) -> i32 { x + y }`
+// The delimiters are in a nested block comment, so they do not have to match.
+zalgo_embed!("E\u{30f}\u{30a}\u{300}\u{341}\u{300}\u{30f}\u{30a}\u{300}\u{308}\u{300}\u{30a}\u{30f}\u{300}\u{309}\u{300}\u{30a}\u{30f}\u{36f}\u{346}\u{34e}\u{300}\u{341}\u{344}\u{344}\u{308}\u{358}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{30c}\u{300}\u{359}\u{31a}\u{300}\u{349}\u{313}\u{312}\u{309}\u{300}\u{30d}\u{31e}\u{300}\u{349}\u{313}\u{312}\u{300}\u{35b}\u{300}\u{358}\u{300}\u{30b}\u{300}\u{359}\u{300}\u{35d}");
+
+fn main() {
+    assert_eq!(add(10, 20), 30);
+}
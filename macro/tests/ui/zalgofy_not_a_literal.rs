@@ -0,0 +1,7 @@
+use zalgo_codec_macro::zalgofy;
+
+const TEXT: &str = "Zalgo";
+
+const ENCODED: &str = zalgofy!(TEXT);
+
+fn main() {}
@@ -0,0 +1,13 @@
+use zalgo_codec_macro::zalgofy_strings;
+
+// The literal after the const generic parameter and the `let` without a value is encoded.
+#[zalgofy_strings]
+fn price<const N: usize>() -> &'static str {
+    let price;
+    price = "5€";
+    price
+}
+
+fn main() {
+    println!("{}", price::<1>());
+}
@@ -0,0 +1,7 @@
+use zalgo_codec_macro::zalgofy;
+
+const ZS: &str = zalgofy!("Zålgo");
+
+fn main() {
+    println!("{ZS}");
+}
@@ -0,0 +1,7 @@
+use zalgo_codec_macro::zalgofy;
+
+const ZB: &[u8] = zalgofy!(byte, "Zalgo");
+
+fn main() {
+    println!("{ZB:?}");
+}
@@ -0,0 +1,25 @@
+// The expansion of `zalgofy!(verify, "Zalgo")` with the last character of the encoded literal changed,
+// as if it had been copied into the source and then edited by mistake.
+const ZS: &str = {
+    const ENCODED: &str = "E\u{33a}\u{341}\u{34c}\u{347}\u{34e}";
+    const _: () = {
+        let encoded = ENCODED.as_bytes();
+        let text: &[u8] = b"Zalgo";
+        let mut matches = encoded.len() == 1 + 2 * text.len() && encoded[0] == b'E';
+        let mut i = 0;
+        while matches && i < text.len() {
+            let (odd, even) = (encoded[1 + 2 * i], encoded[2 + 2 * i]);
+            matches = ((odd << 6 & 64 | even & 63) + 22) % 133 + 10 == text[i];
+            i += 1;
+        }
+        assert!(
+            matches,
+            "the encoded literal does not decode into the text it was made from"
+        );
+    };
+    ENCODED
+};
+
+fn main() {
+    println!("{ZS}");
+}
@@ -0,0 +1,20 @@
+use zalgo_codec_macro::zalgofy;
+
+#[test]
+fn test_zalgofy_bytes() {
+    const BYTES: &[u8] = zalgofy!(
+        bytes,
+        "fn main() {
+    println!(\"Hello, \\\"world\\\"!\");
+}
+"
+    );
+    const STRING: &str = zalgofy!(
+        "fn main() {
+    println!(\"Hello, \\\"world\\\"!\");
+}
+"
+    );
+    assert_eq!(BYTES, STRING.as_bytes());
+    assert_eq!(zalgofy!(bytes, "Zalgo",), zalgofy!("Zalgo").as_bytes());
+}
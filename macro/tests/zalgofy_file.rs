@@ -0,0 +1,15 @@
+use zalgo_codec_common::zalgo_decode;
+use zalgo_codec_macro::zalgofy_file;
+
+const PAYLOAD: &str = zalgofy_file!("tests/fixtures/zalgo.txt");
+
+#[test]
+fn test_zalgofy_file() {
+    // The fixture has a CRLF line ending, which is turned into an LF line ending.
+    let contents = include_str!("fixtures/zalgo.txt");
+    assert!(contents.contains("\r\n"));
+    assert_eq!(
+        zalgo_decode(PAYLOAD).unwrap(),
+        contents.replace("\r\n", "\n")
+    );
+}
@@ -0,0 +1,87 @@
+use zalgo_codec_common::zalgo_encode;
+use zalgo_codec_macro::{zalgofy, zalgofy_strings};
+
+#[zalgofy_strings]
+fn describe(shape: &str, sides: u32) -> String {
+    const UNKNOWN: &str = "an unknown shape";
+    let kind = match shape {
+        "square" => "A shape with four equal sides",
+        "triangle" if sides == 3 => "A shape with three sides",
+        _ => UNKNOWN,
+    };
+    if let "circle" = shape {
+        return format!("{kind}, but it is round");
+    }
+    format!("{kind} and {sides} corners")
+}
+
+struct Chant<const N: usize>;
+
+#[zalgofy_strings]
+impl<const N: usize> Chant<N> {
+    fn words() -> String {
+        let word;
+        word = "He comes";
+        [word; N].join(", ")
+    }
+}
+
+#[zalgofy_strings]
+fn herald<const N: usize>() -> &'static str {
+    let herald;
+    herald = "The pony he comes";
+    herald
+}
+
+mod renamed {
+    use zalgo_codec_common as zc;
+    use zalgo_codec_macro::zalgofy_strings;
+
+    #[zalgofy_strings(crate = zc)]
+    pub fn secret() -> &'static str {
+        r#"This text is not stored in the binary as it is"#
+    }
+}
+
+#[test]
+fn test_zalgofy_strings() {
+    // The expected strings are encoded so that they are not stored in the binary either.
+    let encoded = |text: String| zalgo_encode(&text).unwrap();
+    assert_eq!(
+        encoded(describe("square", 4)),
+        zalgofy!("A shape with four equal sides and 4 corners")
+    );
+    assert_eq!(
+        encoded(describe("triangle", 3)),
+        zalgofy!("A shape with three sides and 3 corners")
+    );
+    assert_eq!(describe("circle", 0), "an unknown shape, but it is round");
+    assert_eq!(encoded(Chant::<2>::words()), zalgofy!("He comes, He comes"));
+    assert_eq!(
+        encoded(herald::<1>().to_string()),
+        zalgofy!("The pony he comes")
+    );
+    assert_eq!(
+        encoded(renamed::secret().to_string()),
+        zalgofy!("This text is not stored in the binary as it is")
+    );
+}
+
+#[test]
+fn test_zalgofy_strings_hides_literals() {
+    let binary = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let contains = |words: &[&str]| {
+        // The text is put together at runtime so that this test does not store it in the binary.
+        let text = words.join(" ");
+        binary
+            .windows(text.len())
+            .any(|window| window == text.as_bytes())
+    };
+    assert!(!contains(&["with", "four", "equal"]));
+    assert!(!contains(&["not", "stored", "in"]));
+    // Literals after const generic parameters and `let` without a value are replaced too.
+    assert!(!contains(&["He", "comes"]));
+    assert!(!contains(&["pony", "he", "comes"]));
+    // Literals in constants are left as they are.
+    assert!(contains(&["an", "unknown", "shape"]));
+}
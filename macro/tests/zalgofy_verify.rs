@@ -0,0 +1,24 @@
+use zalgo_codec_macro::zalgofy;
+
+#[test]
+fn test_zalgofy_verify() {
+    const VERIFIED: &str = zalgofy!(
+        verify,
+        "fn main() {
+    println!(\"Hello, world!\");
+}
+"
+    );
+    const PLAIN: &str = zalgofy!(
+        "fn main() {
+    println!(\"Hello, world!\");
+}
+"
+    );
+    assert_eq!(VERIFIED, PLAIN);
+    assert_eq!(zalgofy!(verify, ""), "E");
+    assert_eq!(zalgofy!(bytes, verify, "Zalgo"), PLAIN_BYTES);
+    assert_eq!(zalgofy!(verify, bytes, "Zalgo"), PLAIN_BYTES);
+}
+
+const PLAIN_BYTES: &[u8] = zalgofy!(bytes, "Zalgo");
@@ -0,0 +1,103 @@
+//! WebAssembly bindings to [`zalgo-codec-common`](https://docs.rs/zalgo-codec-common/latest/zalgo_codec_common/),
+//! for converting a string containing only printable ASCII and newlines
+//! into a single unicode grapheme cluster and back from JavaScript.
+//!
+//! The functions are exported to JavaScript with camelCase names, e.g. [`zalgo_encode`] is `zalgoEncode`.
+//!
+//! # Errors
+//!
+//! Errors are thrown as JavaScript `Error` objects whose `message` is the message of the Rust error,
+//! and whose `name` is `"EncodeError"` or `"DecodeError"`.
+//! The details of the error are available as properties on the object:
+//!
+//! | `name` | properties |
+//! |---|---|
+//! | `"EncodeError"` | `kind`, `char`, `line`, `column`, `index` |
+//! | `"DecodeError"` | `kind`, and `char` and `index` if the problem was caused by a specific character |
+//!
+//! The `kind` property is the name of the variant of
+//! [`EncodeErrorKind`](zalgo_codec_common::EncodeErrorKind) or [`DecodeErrorKind`](zalgo_codec_common::DecodeErrorKind),
+//! e.g. `"NotAscii"`.
+
+use js_sys::{Error, Reflect};
+use wasm_bindgen::prelude::*;
+use zalgo_codec_common::{DecodeError, EncodeError, ZalgoString};
+
+/// Encodes a string of printable ASCII and newlines into a single grapheme cluster,
+/// see [`zalgo_codec_common::zalgo_encode`].
+///
+/// # Errors
+///
+/// Throws an `EncodeError` if the input contains a character that can not be encoded.
+#[wasm_bindgen(js_name = zalgoEncode)]
+pub fn zalgo_encode(input: &str) -> Result<String, JsValue> {
+    zalgo_codec_common::zalgo_encode(input).map_err(encode_error)
+}
+
+/// Decodes a grapheme cluster that was made by [`zalgo_encode`] back into the original string.
+///
+/// Unlike [`zalgo_codec_common::zalgo_decode`] the input is validated first,
+/// like in [`ZalgoString::from_encoded`], so that the error names the offending character.
+///
+/// # Errors
+///
+/// Throws a `DecodeError` if the input is not a valid encoding of printable ASCII and newlines.
+#[wasm_bindgen(js_name = zalgoDecode)]
+pub fn zalgo_decode(input: &str) -> Result<String, JsValue> {
+    ZalgoString::from_encoded(input)
+        .map(ZalgoString::into_decoded_string)
+        .map_err(decode_error)
+}
+
+/// Encodes Python code and wraps it in a decoder that decodes and executes it,
+/// see [`zalgo_codec_common::zalgo_wrap_python`].
+///
+/// # Errors
+///
+/// Throws an `EncodeError` if the input contains a character that can not be encoded.
+#[wasm_bindgen(js_name = zalgoWrapPython)]
+pub fn zalgo_wrap_python(python: &str) -> Result<String, JsValue> {
+    zalgo_codec_common::zalgo_wrap_python(python).map_err(encode_error)
+}
+
+/// Recovers the Python code that was wrapped by [`zalgo_wrap_python`],
+/// see [`zalgo_codec_common::zalgo_unwrap_python`].
+///
+/// # Errors
+///
+/// Throws a `DecodeError` if the input is not Python code that was wrapped by [`zalgo_wrap_python`].
+#[wasm_bindgen(js_name = zalgoUnwrapPython)]
+pub fn zalgo_unwrap_python(wrapped: &str) -> Result<String, JsValue> {
+    zalgo_codec_common::zalgo_unwrap_python(wrapped).map_err(decode_error)
+}
+
+/// Converts an [`EncodeError`] into a JavaScript `Error` with the details of the error as properties.
+fn encode_error(e: EncodeError) -> JsValue {
+    let error = Error::new(&e.to_string());
+    error.set_name("EncodeError");
+    set(&error, "kind", format!("{:?}", e.kind()).into());
+    set(&error, "char", e.char().to_string().into());
+    set(&error, "line", e.line().into());
+    set(&error, "column", e.column().into());
+    set(&error, "index", e.index().into());
+    error.into()
+}
+
+/// Converts a [`DecodeError`] into a JavaScript `Error` with the details of the error as properties.
+fn decode_error(e: DecodeError) -> JsValue {
+    let error = Error::new(&e.to_string());
+    error.set_name("DecodeError");
+    set(&error, "kind", format!("{:?}", e.kind()).into());
+    if let Some(character) = e.char() {
+        set(&error, "char", character.to_string().into());
+    }
+    if let Some(index) = e.index() {
+        set(&error, "index", index.into());
+    }
+    error.into()
+}
+
+fn set(error: &Error, property: &str, value: JsValue) {
+    // Setting a property on a newly created `Error` can not fail.
+    let _ = Reflect::set(error, &property.into(), &value);
+}
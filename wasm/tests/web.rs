@@ -0,0 +1,66 @@
+//! Tests that run in a JavaScript runtime, e.g. with `wasm-pack test --node wasm`.
+
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Error, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+use zalgo_codec_wasm::{zalgo_decode, zalgo_encode, zalgo_unwrap_python, zalgo_wrap_python};
+
+fn property(error: &JsValue, name: &str) -> JsValue {
+    Reflect::get(error, &name.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn round_trip() {
+    let text = "Zalgo\nHe comes!";
+    let encoded = zalgo_encode(text).unwrap();
+    assert_eq!(
+        zalgo_encode("Zalgo").unwrap(),
+        "E\u{33a}\u{341}\u{34c}\u{347}\u{34f}"
+    );
+    assert_eq!(zalgo_decode(&encoded).unwrap(), text);
+}
+
+#[wasm_bindgen_test]
+fn python_round_trip() {
+    let python = "print(\"Hello, world!\")\n";
+    let wrapped = zalgo_wrap_python(python).unwrap();
+    assert_eq!(zalgo_unwrap_python(&wrapped).unwrap(), python);
+}
+
+#[wasm_bindgen_test]
+fn encode_error() {
+    let error = zalgo_encode("Zalgo\nZålgo").unwrap_err();
+    assert!(error.is_instance_of::<Error>());
+    let message = error.dyn_ref::<Error>().unwrap().message();
+    assert_eq!(
+        message,
+        "can not encode 'å' character at string index 7, on line 2 at column 2"
+    );
+    assert_eq!(property(&error, "name"), "EncodeError");
+    assert_eq!(property(&error, "kind"), "NotAscii");
+    assert_eq!(property(&error, "char"), "å");
+    assert_eq!(property(&error, "line"), 2);
+    assert_eq!(property(&error, "column"), 2);
+    assert_eq!(property(&error, "index"), 7);
+
+    let error = zalgo_wrap_python("print(\"5€\")").unwrap_err();
+    assert_eq!(property(&error, "char"), "€");
+}
+
+#[wasm_bindgen_test]
+fn decode_error() {
+    let error = zalgo_decode("Zalgo").unwrap_err();
+    assert_eq!(property(&error, "name"), "DecodeError");
+    assert_eq!(property(&error, "kind"), "MissingLeadingE");
+    assert_eq!(property(&error, "char"), "Z");
+    assert_eq!(property(&error, "index"), 0);
+
+    let error = zalgo_decode("").unwrap_err();
+    assert_eq!(property(&error, "kind"), "EmptyInput");
+    assert!(property(&error, "index").is_undefined());
+
+    let error = zalgo_unwrap_python("print(1)").unwrap_err();
+    assert_eq!(property(&error, "kind"), "NotWrappedPython");
+}